@@ -1,7 +1,9 @@
 use crate::commands::error::CommandError;
+use crate::commands::output;
 use super::excel::ExcelProcessor;
 use super::scraper::AltaScraper;
 use crate::models::alta::{AltaQueryResult, DatabaseInfo, ExcelStats, UpdateResult};
+use crate::models::output::OutputTool;
 use crate::AppState;
 use log::{error, info};
 use std::path::PathBuf;
@@ -128,17 +130,6 @@ pub async fn batch_process_excel(
 
     let input = PathBuf::from(&input_path);
 
-    // 生成输出文件路径
-    let output = if let Some(parent) = input.parent() {
-        let stem = input
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        parent.join(format!("{}_禁运标记.xlsx", stem))
-    } else {
-        PathBuf::from(format!("{}_禁运标记.xlsx", input_path))
-    };
-
     // 创建Excel处理器
     let matcher_arc = {
         let _guard = state.matcher.lock().map_err(|e| {
@@ -157,13 +148,17 @@ pub async fn batch_process_excel(
         CommandError::new(format!("文件验证失败: {}", e), "VALIDATION_ERROR")
     })?;
 
+    // 选定输出文件
+    let output = output::claim_output(OutputTool::Alta, &input, "禁运标记")?;
+
     // 处理Excel
     let stats = processor
-        .process_excel(&input, &output, match_length)
+        .process_excel(&input, output.path(), match_length)
         .map_err(|e| {
             error!("处理Excel失败: {}", e);
             CommandError::new(format!("处理失败: {}", e), "PROCESS_ERROR")
         })?;
+    output.keep();
 
     info!("Excel处理完成: {:?}", stats);
 
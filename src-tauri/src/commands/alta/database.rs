@@ -1,9 +1,10 @@
+use super::hs_code;
 use crate::core::database as core_db;
 use crate::models::alta::{DatabaseInfo, ForbiddenItem};
 use anyhow::{Context, Result};
 use chrono::Local;
 use log::{debug, info};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use std::path::Path;
 
 // ============================================================================
@@ -18,8 +19,11 @@ pub mod db_version {
     /// 新版本（支持复杂 HS 编码）
     pub const V2: i32 = 2;
 
+    /// 支持 HS 编码范围和 "из" 标记
+    pub const V3: i32 = 3;
+
     /// 当前目标版本
-    pub const CURRENT: i32 = V2;
+    pub const CURRENT: i32 = V3;
 }
 
 // ============================================================================
@@ -104,10 +108,36 @@ impl DatabaseManager {
         let mut inserted = 0;
 
         for item in &items {
-            // 检查是否有新字段（v2+）
+            // 检查是否有新字段（v2+ / v3+）
             let has_new_columns = MigrationManager::column_exists(&self.conn, "forbidden_items", "raw_text");
+            let has_range_columns = MigrationManager::column_exists(&self.conn, "forbidden_items", "hs_code_high");
 
-            if has_new_columns {
+            if has_range_columns {
+                // 使用 v3+ 的 SQL（包含范围字段）
+                tx.execute(
+                    "INSERT INTO forbidden_items (
+                        hs_code, hs_code_4, hs_code_6, hs_code_8,
+                        description, additional_info, source_url,
+                        created_at, updated_at, raw_text, has_exceptions,
+                        hs_code_high, is_partial
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                    params![
+                        item.hs_code,
+                        item.hs_code_4,
+                        item.hs_code_6,
+                        item.hs_code_8,
+                        item.description,
+                        item.additional_info,
+                        item.source_url,
+                        now,
+                        now,
+                        item.raw_text,
+                        item.has_exceptions.map(|b| if b { 1 } else { 0 }),
+                        item.hs_code_high,
+                        item.is_partial.map(|b| if b { 1 } else { 0 }),
+                    ],
+                )?;
+            } else if has_new_columns {
                 // 使用 v2+ 的 SQL（包含新字段）
                 tx.execute(
                     "INSERT INTO forbidden_items (
@@ -212,35 +242,8 @@ impl DatabaseManager {
         debug!("是否有新字段 (raw_text): {}", has_new_columns);
 
         let items = if has_new_columns {
-            // v2+: 包含新字段
-            // 列顺序：id(0), hs_code(1), hs_code_4(2), hs_code_6(3), hs_code_8(4),
-            //         description(5), additional_info(6), source_url(7), created_at(8),
-            //         updated_at(9), raw_text(10), has_exceptions(11)
-            stmt.query_map([], |row| {
-                let raw_text: Option<String> = row.get(10).ok();
-                let has_exceptions_val: i32 = row.get(11).unwrap_or(0);
-
-                debug!("查询到记录 - hs_code: {:?}, raw_text: {:?}, has_exceptions: {}",
-                    row.get::<_, String>(1),
-                    raw_text,
-                    has_exceptions_val
-                );
-
-                Ok(ForbiddenItem {
-                    id: row.get(0)?,
-                    hs_code: row.get(1)?,
-                    hs_code_4: row.get(2)?,
-                    hs_code_6: row.get(3)?,
-                    hs_code_8: row.get(4)?,
-                    description: row.get(5)?,
-                    additional_info: row.get(6)?,
-                    source_url: row.get(7)?,
-                    created_at: row.get(8)?,
-                    raw_text,
-                    has_exceptions: Some(has_exceptions_val == 1),
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?
+            stmt.query_map([], Self::row_to_item)?
+                .collect::<Result<Vec<_>, _>>()?
         } else {
             // v1: 旧字段
             stmt.query_map([], |row| {
@@ -256,6 +259,8 @@ impl DatabaseManager {
                     created_at: row.get(8)?,
                     raw_text: None,
                     has_exceptions: None,
+                    hs_code_high: None,
+                    is_partial: None,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?
@@ -264,6 +269,66 @@ impl DatabaseManager {
         Ok(items)
     }
 
+    /// 查找包含该 HS 编码的禁运范围（如 "8703 21 – 8703 24"）
+    pub fn search_by_range(&self, hs_code: &str) -> Result<Vec<ForbiddenItem>> {
+        if !MigrationManager::column_exists(&self.conn, "forbidden_items", "hs_code_high") {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM forbidden_items WHERE hs_code_high IS NOT NULL")?;
+
+        let items = stmt
+            .query_map([], Self::row_to_item)?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|item| match &item.hs_code_high {
+                Some(high) => hs_code::range_contains(&item.hs_code, high, hs_code),
+                None => false,
+            })
+            .collect::<Vec<_>>();
+
+        debug!("范围匹配 {}: {} 条", hs_code, items.len());
+
+        Ok(items)
+    }
+
+    /// 将 v2+ 的数据行转换为禁运商品
+    ///
+    /// 列顺序：id(0), hs_code(1), hs_code_4(2), hs_code_6(3), hs_code_8(4),
+    ///         description(5), additional_info(6), source_url(7), created_at(8),
+    ///         updated_at(9), raw_text(10), has_exceptions(11),
+    ///         hs_code_high(12, v3), is_partial(13, v3)
+    fn row_to_item(row: &Row) -> rusqlite::Result<ForbiddenItem> {
+        let raw_text: Option<String> = row.get(10).ok();
+        let has_exceptions_val: i32 = row.get(11).unwrap_or(0);
+        let hs_code_high: Option<String> = row.get::<_, Option<String>>(12).ok().flatten();
+        let is_partial: Option<bool> = row.get::<_, Option<i32>>(13).ok().flatten().map(|v| v == 1);
+
+        debug!("查询到记录 - hs_code: {:?}, raw_text: {:?}, has_exceptions: {}",
+            row.get::<_, String>(1),
+            raw_text,
+            has_exceptions_val
+        );
+
+        Ok(ForbiddenItem {
+            id: row.get(0)?,
+            hs_code: row.get(1)?,
+            hs_code_4: row.get(2)?,
+            hs_code_6: row.get(3)?,
+            hs_code_8: row.get(4)?,
+            description: row.get(5)?,
+            additional_info: row.get(6)?,
+            source_url: row.get(7)?,
+            created_at: row.get(8)?,
+            raw_text,
+            has_exceptions: Some(has_exceptions_val == 1),
+            hs_code_high,
+            is_partial,
+        })
+    }
+
     /// 获取最后更新时间
     pub fn get_last_update_time(&self) -> Result<Option<String>> {
         let mut stmt = self.conn.prepare(
@@ -330,10 +395,13 @@ impl MigrationManager {
             // 按顺序执行迁移
             if current_version < 2 {
                 Self::migrate_v1_to_v2(conn)?;
+                Self::set_version(conn, db_version::V2, "支持复杂 HS 编码格式")?;
             }
 
-            // 更新版本号
-            Self::set_version(conn, target_version, "支持复杂 HS 编码格式")?;
+            if current_version < 3 {
+                Self::migrate_v2_to_v3(conn)?;
+                Self::set_version(conn, db_version::V3, "支持 HS 编码范围")?;
+            }
 
             info!("数据库迁移完成");
         } else {
@@ -407,6 +475,28 @@ impl MigrationManager {
         Ok(())
     }
 
+    /// v2 → v3 迁移：添加范围上界和 "из" 标记
+    fn migrate_v2_to_v3(conn: &Connection) -> Result<()> {
+        info!("执行 v2 → v3 迁移...");
+
+        let columns_to_add = [
+            ("hs_code_high", "TEXT"),
+            ("is_partial", "INTEGER DEFAULT 0"),
+        ];
+
+        for (column, col_type) in &columns_to_add {
+            if !Self::column_exists(conn, "forbidden_items", column) {
+                let sql = format!("ALTER TABLE forbidden_items ADD COLUMN {} {}", column, col_type);
+                conn.execute(&sql, [])?;
+                info!("添加列: {}", column);
+            } else {
+                debug!("列已存在，跳过: {}", column);
+            }
+        }
+
+        Ok(())
+    }
+
     /// 检查列是否存在
     fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
         let sql = format!("PRAGMA table_info({})", table);
@@ -473,6 +563,8 @@ mod tests {
             created_at: None,
             raw_text: None,
             has_exceptions: None,
+            hs_code_high: None,
+            is_partial: None,
         }];
 
         db.update_forbidden_items(items).unwrap();
@@ -480,4 +572,27 @@ mod tests {
         let results = db.search_by_hs_code("123456", Some(4)).unwrap();
         assert_eq!(results.len(), 1);
     }
+
+    #[test]
+    fn test_search_by_range() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path()).unwrap();
+
+        let mut item = ForbiddenItem::new_v1(
+            "870321".to_string(),
+            "Vehicles".to_string(),
+            "Info".to_string(),
+        );
+        item.hs_code_high = Some("870324".to_string());
+        item.is_partial = Some(true);
+
+        db.update_forbidden_items(vec![item]).unwrap();
+
+        let results = db.search_by_range("8703231000").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].hs_code_high.as_deref(), Some("870324"));
+        assert_eq!(results[0].is_partial, Some(true));
+
+        assert!(db.search_by_range("8703400000").unwrap().is_empty());
+    }
 }
@@ -1,7 +1,7 @@
 use super::matcher::HSCodeMatcher;
+use crate::core::tabular::{self, TabularSheet, TabularWorkbook};
 use crate::models::alta::ExcelStats;
 use anyhow::{Context, Result};
-use calamine::{open_workbook, Reader, Xlsx};
 use log::info;
 use rust_xlsxwriter::{Color, Format, Workbook};
 use std::path::Path;
@@ -44,27 +44,16 @@ impl ExcelProcessor {
     ) -> Result<ExcelStats> {
         info!("开始处理Excel: {:?}", input_path);
 
-        // 读取输入文件
-        let mut workbook: Xlsx<_> =
-            open_workbook(input_path).context("Failed to open Excel file")?;
-
-        // 获取第一个工作表
-        let sheet_name = workbook
-            .sheet_names()
-            .first()
-            .context("No sheets found in workbook")?
-            .clone();
-
-        let range = workbook
-            .worksheet_range(&sheet_name)
-            .context("Failed to get worksheet range")?;
-
-        // 读取数据
-        let mut rows: Vec<Vec<String>> = Vec::new();
-        for row in range.rows() {
-            let row_data: Vec<String> = row.iter().map(|cell| cell.to_string()).collect();
-            rows.push(row_data);
-        }
+        // 读取输入文件（xlsx / xls / ods / csv），获取第一个工作表
+        let sheet = Self::read_first_sheet(input_path)?;
+
+        // 读取数据（从检测到的表头行开始）
+        let header_row = sheet.detect_header_row();
+        let rows: Vec<Vec<String>> = sheet
+            .rows()
+            .skip(header_row)
+            .map(|row| row.iter().map(|cell| cell.as_text()).collect())
+            .collect();
 
         if rows.is_empty() {
             anyhow::bail!("Excel文件没有数据");
@@ -103,7 +92,7 @@ impl ExcelProcessor {
         // 处理数据行（跳过表头）
         for (row_idx, row_data) in rows.iter().enumerate().skip(1) {
             // 获取HS编码并匹配
-            let hs_code = row_data[hs_col].trim();
+            let hs_code = row_data.get(hs_col).map(|s| s.trim()).unwrap_or("");
             let matcher = self.matcher.lock().unwrap();
             let match_result = matcher.match_code(hs_code, match_length)?;
             drop(matcher); // 释放锁
@@ -149,44 +138,21 @@ impl ExcelProcessor {
         }
 
         // 检查文件扩展名
-        let ext = file_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-
-        if ext != "xlsx" && ext != "xls" {
-            anyhow::bail!("不支持的文件格式，请使用.xlsx或.xls文件");
+        if !tabular::is_supported(file_path) {
+            anyhow::bail!("不支持的文件格式，请使用 .xlsx / .xls / .ods / .csv 文件");
         }
 
         // 尝试打开文件
-        let mut workbook: Xlsx<_> = open_workbook(file_path).context("无法打开Excel文件")?;
-
-        let sheet_name = workbook
-            .sheet_names()
-            .first()
-            .context("Excel文件没有工作表")?
-            .clone();
-
-        let range = workbook
-            .worksheet_range(&sheet_name)
-            .context("无法读取工作表")?;
+        let sheet = Self::read_first_sheet(file_path)?;
+        let header_row = sheet.detect_header_row();
 
         // 检查是否有数据
-        if range.get_size().0 < 2 {
+        if sheet.height() < header_row + 2 {
             anyhow::bail!("Excel文件没有数据行");
         }
 
         // 检查是否有HS Code列
-        let headers: Vec<String> = range
-            .rows()
-            .next()
-            .context("无法读取表头")?
-            .iter()
-            .map(|cell| cell.to_string())
-            .collect();
-
-        if self.find_hs_code_column(&headers).is_none() {
+        if self.find_hs_code_column(&sheet.headers(header_row)).is_none() {
             anyhow::bail!("未找到'HS Code'列，请确保表头包含该列");
         }
 
@@ -195,34 +161,25 @@ impl ExcelProcessor {
 
     /// 获取Excel文件信息
     pub fn get_excel_info(&self, file_path: &Path) -> Result<serde_json::Value> {
-        let mut workbook: Xlsx<_> = open_workbook(file_path)?;
-
-        let sheet_name = workbook
-            .sheet_names()
-            .first()
-            .context("No sheets found")?
-            .clone();
-
-        let range = workbook.worksheet_range(&sheet_name)?;
-        let size = range.get_size();
-
-        let headers: Vec<String> = range
-            .rows()
-            .next()
-            .context("No headers")?
-            .iter()
-            .map(|cell| cell.to_string())
-            .collect();
+        let sheet = Self::read_first_sheet(file_path)?;
+        let header_row = sheet.detect_header_row();
+        let headers = sheet.headers(header_row);
 
         Ok(serde_json::json!({
             "file_name": file_path.file_name().and_then(|s| s.to_str()).unwrap_or(""),
-            "sheet_name": sheet_name,
-            "total_rows": size.0 - 1, // 减去表头
-            "total_columns": size.1,
+            "sheet_name": sheet.name,
+            "total_rows": sheet.height().saturating_sub(header_row + 1), // 减去表头
+            "total_columns": sheet.width(),
             "has_hs_code": self.find_hs_code_column(&headers).is_some(),
         }))
     }
 
+    /// 读取第一个工作表
+    fn read_first_sheet(file_path: &Path) -> Result<TabularSheet> {
+        let mut workbook = TabularWorkbook::open(file_path).context("无法打开Excel文件")?;
+        workbook.first_sheet().context("无法读取工作表")
+    }
+
     /// 生成Excel模板
     pub fn generate_template(output_path: &Path) -> Result<()> {
         let mut workbook = Workbook::new();
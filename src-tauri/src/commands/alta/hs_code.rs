@@ -0,0 +1,320 @@
+use crate::models::alta::HsCodeEntry;
+
+// ============================================================================
+// Alta HS 编码单元格分词
+// ============================================================================
+//
+// Alta 表格第一列的写法示例：
+//   "8471 30 000 0"                     → 分组书写的完整编码
+//   "из 8703 21 – 8703 24"              → 部分品目（из）+ 编码范围
+//   "0101 (за исключением 0101 21 000 0)" → 例外编码
+
+/// HS 编码最大位数
+const MAX_CODE_LEN: usize = 10;
+
+/// HS 编码最小位数（少于 4 位的数字视为普通数字，不是编码）
+const MIN_CODE_LEN: usize = 4;
+
+/// 分词结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum HsToken {
+    /// 已合并空格分组的编码
+    Code(String),
+    /// 范围连接符（-、–、—）
+    Dash,
+    /// "из" 标记：只有该品目下的部分商品受限
+    Partial,
+    /// "за исключением" / "кроме" 标记：之后的编码为例外
+    Exception,
+    /// 例外部分结束（")"、";"），之后的编码恢复为受限编码
+    ClauseEnd,
+    /// 其他分隔（逗号、括号、普通文字等）
+    Separator,
+}
+
+/// 编码区间（单个编码时 low == high）
+#[derive(Debug, Clone, PartialEq)]
+pub struct HsCodeSpan {
+    pub low: String,
+    pub high: String,
+    pub is_partial: bool,
+    pub is_exception: bool,
+}
+
+impl HsCodeSpan {
+    /// 是否为范围
+    pub fn is_range(&self) -> bool {
+        self.low != self.high
+    }
+
+    /// 转换为解析条目
+    pub fn into_entry(self, parent_raw: &str) -> HsCodeEntry {
+        let code_high = if self.is_range() {
+            Some(self.high)
+        } else {
+            None
+        };
+
+        HsCodeEntry {
+            code_4: prefix(&self.low, 4),
+            code_6: prefix(&self.low, 6),
+            code_8: prefix(&self.low, 8),
+            code: self.low,
+            code_high,
+            is_partial: self.is_partial,
+            is_exception: self.is_exception,
+            parent_raw: parent_raw.to_string(),
+        }
+    }
+}
+
+/// 文本中是否包含例外标记
+pub fn has_exception_marker(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    lower.contains("за исключением") || lower.contains("кроме")
+}
+
+/// 将 Alta 单元格文本切分为编码、范围符和标记
+pub fn tokenize(text: &str) -> Vec<HsToken> {
+    let chars: Vec<char> = text.to_lowercase().chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_ascii_digit() {
+            let (code, next) = read_code(&chars, i);
+            // 范围终点允许简写（"8703 21 – 24"）
+            let is_range_end = tokens.last() == Some(&HsToken::Dash);
+            if (MIN_CODE_LEN..=MAX_CODE_LEN).contains(&code.len())
+                || (is_range_end && code.len() <= MAX_CODE_LEN)
+            {
+                tokens.push(HsToken::Code(code));
+            } else {
+                tokens.push(HsToken::Separator);
+            }
+            i = next;
+        } else if matches!(c, '-' | '–' | '—' | '−') {
+            tokens.push(HsToken::Dash);
+            i += 1;
+        } else if c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && chars[i].is_alphabetic() {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            match word.as_str() {
+                "из" => tokens.push(HsToken::Partial),
+                "кроме" | "исключением" => tokens.push(HsToken::Exception),
+                // "за" 只是 "за исключением" 的前半部分
+                "за" => {}
+                _ => tokens.push(HsToken::Separator),
+            }
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if matches!(c, ')' | ';') {
+            tokens.push(HsToken::ClauseEnd);
+            i += 1;
+        } else {
+            tokens.push(HsToken::Separator);
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// 解析 Alta 单元格文本为编码区间列表
+pub fn parse_spans(text: &str) -> Vec<HsCodeSpan> {
+    let tokens = tokenize(text);
+    let mut spans = Vec::new();
+    let mut partial_pending = false;
+    let mut in_exception = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match &tokens[i] {
+            HsToken::Partial => partial_pending = true,
+            HsToken::Exception => in_exception = true,
+            HsToken::ClauseEnd => in_exception = false,
+            // 孤立的简写终点不是编码
+            HsToken::Code(low) if low.len() < MIN_CODE_LEN => {}
+            HsToken::Code(low) => {
+                let mut high = low.clone();
+
+                // 范围：编码 – 编码
+                if let (Some(HsToken::Dash), Some(HsToken::Code(end))) =
+                    (tokens.get(i + 1), tokens.get(i + 2))
+                {
+                    high = expand_range_end(low, end);
+                    i += 2;
+                }
+
+                spans.push(HsCodeSpan {
+                    low: low.clone(),
+                    high,
+                    is_partial: partial_pending,
+                    is_exception: in_exception,
+                });
+                partial_pending = false;
+            }
+            HsToken::Dash | HsToken::Separator => {}
+        }
+        i += 1;
+    }
+
+    spans
+}
+
+/// 判断编码是否落在 [low, high] 范围内
+///
+/// 下界按 0 补齐、上界按 9 补齐到 10 位，查询编码的两端都必须落在范围内，
+/// 因此 "8703 21 – 8703 24" 包含 8703220000，但不包含较宽的 8703。
+pub fn range_contains(low: &str, high: &str, code: &str) -> bool {
+    if code.is_empty() || low.is_empty() || high.is_empty() {
+        return false;
+    }
+
+    let low = pad(low, '0');
+    let high = pad(high, '9');
+
+    pad(code, '0') >= low && pad(code, '9') <= high
+}
+
+/// 获取编码前缀
+pub fn prefix(code: &str, len: usize) -> String {
+    if code.len() >= len {
+        code[..len].to_string()
+    } else {
+        code.to_string()
+    }
+}
+
+/// 读取一个编码：首段数字之后，紧跟的 1-3 位数字分组（仅以空白分隔）会被合并
+fn read_code(chars: &[char], start: usize) -> (String, usize) {
+    let mut i = start;
+    let mut code = String::new();
+
+    while i < chars.len() && chars[i].is_ascii_digit() {
+        code.push(chars[i]);
+        i += 1;
+    }
+
+    loop {
+        let mut j = i;
+        while j < chars.len() && (chars[j] == ' ' || chars[j] == '\u{a0}') {
+            j += 1;
+        }
+        if j == i {
+            break;
+        }
+
+        let group_start = j;
+        while j < chars.len() && chars[j].is_ascii_digit() {
+            j += 1;
+        }
+        let group_len = j - group_start;
+
+        if group_len == 0 || group_len > 3 || code.len() + group_len > MAX_CODE_LEN {
+            break;
+        }
+
+        code.extend(&chars[group_start..j]);
+        i = j;
+    }
+
+    (code, i)
+}
+
+/// 补全范围终点的简写（"8703 21 – 24" → 870324）
+///
+/// 只有 2-3 位的终点是简写，4 位及以上是完整的品目或编码（"8703 21 – 8704"）。
+fn expand_range_end(low: &str, end: &str) -> String {
+    if end.len() < low.len() && end.len() < MIN_CODE_LEN {
+        format!("{}{}", &low[..low.len() - end.len()], end)
+    } else {
+        end.to_string()
+    }
+}
+
+/// 将编码补齐到 10 位
+fn pad(code: &str, fill: char) -> String {
+    let mut padded = code.to_string();
+    while padded.len() < MAX_CODE_LEN {
+        padded.push(fill);
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contains(span: &HsCodeSpan, code: &str) -> bool {
+        range_contains(&span.low, &span.high, code)
+    }
+
+    #[test]
+    fn test_joins_spaced_groups() {
+        let spans = parse_spans("8471 30 000 0");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].low, "8471300000");
+        assert!(!spans[0].is_range());
+    }
+
+    #[test]
+    fn test_partial_range() {
+        let spans = parse_spans("из 8703 21 – 8703 24");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].low, "870321");
+        assert_eq!(spans[0].high, "870324");
+        assert!(spans[0].is_partial);
+        assert!(contains(&spans[0], "8703229000"));
+        assert!(!contains(&spans[0], "8703310000"));
+        assert!(!contains(&spans[0], "8703"));
+    }
+
+    #[test]
+    fn test_list_and_exceptions() {
+        let spans = parse_spans("0101 21 000 0, 0102; 0103 (за исключением 0103 10 000 0)");
+        let main: Vec<_> = spans.iter().filter(|s| !s.is_exception).collect();
+        let exceptions: Vec<_> = spans.iter().filter(|s| s.is_exception).collect();
+
+        assert_eq!(main.len(), 3);
+        assert_eq!(main[0].low, "0101210000");
+        assert_eq!(main[1].low, "0102");
+        assert_eq!(exceptions.len(), 1);
+        assert_eq!(exceptions[0].low, "0103100000");
+    }
+
+    #[test]
+    fn test_exception_ends_at_clause() {
+        let spans = parse_spans("8703, кроме 8703 10; 8704 (за исключением 8704 10) 8705");
+        let flags: Vec<_> = spans.iter().map(|s| (s.low.as_str(), s.is_exception)).collect();
+        assert_eq!(
+            flags,
+            vec![("8703", false), ("870310", true), ("8704", false), ("870410", true), ("8705", false)]
+        );
+    }
+
+    #[test]
+    fn test_short_range_end() {
+        let spans = parse_spans("8703 21 - 24");
+        assert_eq!(spans[0].high, "870324");
+    }
+
+    #[test]
+    fn test_range_ends_on_heading() {
+        let spans = parse_spans("8703 21 – 8704");
+        assert_eq!(spans[0].low, "870321");
+        assert_eq!(spans[0].high, "8704");
+        assert!(contains(&spans[0], "8704100000"));
+        assert!(!contains(&spans[0], "8705100000"));
+    }
+
+    #[test]
+    fn test_ignores_short_numbers() {
+        assert!(parse_spans("пункт 12 статьи 3").is_empty());
+    }
+}
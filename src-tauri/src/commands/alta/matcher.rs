@@ -55,17 +55,25 @@ impl HSCodeMatcher {
 
         // 查询数据库
         let db = self.db.lock().unwrap();
-        let results = db.search_by_hs_code(&clean_code, match_length)?;
+        let mut results = db.search_by_hs_code(&clean_code, match_length)?;
+
+        let match_type = match match_length {
+            Some(4) => "4位匹配",
+            Some(6) => "6位匹配",
+            Some(8) => "8位匹配",
+            _ => "完全匹配",
+        };
+
+        // 前缀未命中时，检查编码是否落在禁运范围内
+        let match_type = if results.is_empty() {
+            results = db.search_by_range(&clean_code)?;
+            "范围匹配"
+        } else {
+            match_type
+        };
 
         if !results.is_empty() {
-            let match_type = match match_length {
-                Some(4) => "4位匹配",
-                Some(6) => "6位匹配",
-                Some(8) => "8位匹配",
-                _ => "完全匹配",
-            };
-
-            let matched_codes: Vec<String> = results.iter().map(|item| item.hs_code.clone()).collect();
+            let matched_codes: Vec<String> = results.iter().map(|item| item.display_code()).collect();
             let descriptions: Vec<String> = results.iter().map(|item| item.description.clone()).collect();
             let raw_texts: Vec<Option<String>> = results.iter().map(|item| item.raw_text.clone()).collect();
             let has_exceptions: Vec<bool> = results.iter().map(|item| item.has_exceptions.unwrap_or(false)).collect();
@@ -201,7 +209,6 @@ impl HSCodeMatcher {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use super::database::DatabaseManager;
     use crate::models::alta::ForbiddenItem;
     use std::sync::{Arc, Mutex};
     use tempfile::NamedTempFile;
@@ -235,6 +242,8 @@ mod tests {
             created_at: None,
             raw_text: None,
             has_exceptions: None,
+            hs_code_high: None,
+            is_partial: None,
         }];
         db_manager.update_forbidden_items(items).unwrap();
 
@@ -250,4 +259,29 @@ mod tests {
         let result = matcher.match_code("999999", Some(4)).unwrap();
         assert!(!result.is_forbidden);
     }
+
+    #[test]
+    fn test_match_code_in_range() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_manager = DatabaseManager::new(temp_file.path()).unwrap();
+
+        let mut item = ForbiddenItem::new_v1(
+            "870321".to_string(),
+            "Vehicles".to_string(),
+            "Info".to_string(),
+        );
+        item.hs_code_high = Some("870324".to_string());
+        db_manager.update_forbidden_items(vec![item]).unwrap();
+
+        let db = Arc::new(Mutex::new(db_manager));
+        let matcher = HSCodeMatcher::new(db);
+
+        let result = matcher.match_code("8703 23 190 1", None).unwrap();
+        assert!(result.is_forbidden);
+        assert_eq!(result.match_type, "范围匹配");
+        assert_eq!(result.matched_codes, vec!["870321–870324".to_string()]);
+
+        let result = matcher.match_code("8703 40 000 0", None).unwrap();
+        assert!(!result.is_forbidden);
+    }
 }
@@ -1,6 +1,7 @@
 pub mod commands;
 pub mod database;
 pub mod excel;
+pub mod hs_code;
 pub mod matcher;
 pub mod scraper;
 
@@ -1,13 +1,10 @@
+use super::hs_code;
 use crate::core::html::HtmlParser;
 use crate::core::http;
 use crate::models::alta::{ForbiddenItem, HsCodeEntry};
 use anyhow::{Context, Result};
 use log::{debug, info, warn};
 use reqwest::Client;
-use regex::Regex;
-
-// 导入 lazy_static 宏
-use lazy_static::lazy_static;
 
 /// Alta.ru 禁运数据爬虫
 pub struct AltaScraper {
@@ -73,93 +70,18 @@ impl AltaScraper {
     }
 
     /// 智能解析 HS 编码条目
+    ///
+    /// 使用分词器合并空格分组的编码，并识别 "из" 标记、范围和例外
     fn parse_hs_code_entry(
         &self,
         raw_text: &str,
         _description: &str,
         _document: &str
     ) -> Vec<HsCodeEntry> {
-        lazy_static! {
-            static ref CODE_RE: Regex = Regex::new(r"\d{4,10}").unwrap();
-        }
-
-        let mut entries = Vec::new();
-
-        // 1. 判断是否有例外
-        let has_exceptions = raw_text.contains("за исключением") ||
-                            raw_text.contains("кроме");
-
-        if has_exceptions {
-            // 2.1 有例外：分离主编码和例外编码
-            if let Some(pos) = raw_text.find("за исключением") {
-                let main_part = &raw_text[..pos];
-                let exception_part = &raw_text[pos..];
-
-                // 提取主编码
-                let main_codes: Vec<String> = CODE_RE
-                    .find_iter(main_part)
-                    .map(|m| m.as_str().to_string())
-                    .collect();
-
-                // 提取例外编码
-                let exception_codes: Vec<String> = CODE_RE
-                    .find_iter(exception_part)
-                    .map(|m| m.as_str().to_string())
-                    .collect();
-
-                // 为主编码创建条目
-                for code in main_codes {
-                    entries.push(HsCodeEntry {
-                        code: code.clone(),
-                        code_4: Self::prefix(&code, 4),
-                        code_6: Self::prefix(&code, 6),
-                        code_8: Self::prefix(&code, 8),
-                        is_exception: false,
-                        parent_raw: raw_text.to_string(),
-                    });
-                }
-
-                // 为例外编码创建条目（标记为例外）
-                for code in exception_codes {
-                    entries.push(HsCodeEntry {
-                        code: code.clone(),
-                        code_4: Self::prefix(&code, 4),
-                        code_6: Self::prefix(&code, 6),
-                        code_8: Self::prefix(&code, 8),
-                        is_exception: true,
-                        parent_raw: raw_text.to_string(),
-                    });
-                }
-            }
-        } else {
-            // 2.2 无例外：所有编码都是主编码
-            let codes: Vec<String> = CODE_RE
-                .find_iter(raw_text)
-                .map(|m| m.as_str().to_string())
-                .collect();
-
-            for code in codes {
-                entries.push(HsCodeEntry {
-                    code: code.clone(),
-                    code_4: Self::prefix(&code, 4),
-                    code_6: Self::prefix(&code, 6),
-                    code_8: Self::prefix(&code, 8),
-                    is_exception: false,
-                    parent_raw: raw_text.to_string(),
-                });
-            }
-        }
-
-        entries
-    }
-
-    /// 获取编码前缀
-    fn prefix(code: &str, len: usize) -> String {
-        if code.len() >= len {
-            code[..len].to_string()
-        } else {
-            code.to_string()
-        }
+        hs_code::parse_spans(raw_text)
+            .into_iter()
+            .map(|span| span.into_entry(raw_text))
+            .collect()
     }
 
     /// 解析表格结构的数据
@@ -209,10 +131,10 @@ impl AltaScraper {
                         .filter(|e| !e.is_exception)
                         .collect();
 
+                    let has_exception = hs_code::has_exception_marker(&raw_hs_text);
+
                     // 为每个主编码创建 ForbiddenItem
                     for entry in forbidden_entries {
-                        let has_exception = raw_hs_text.contains("за исключением");
-
                         items.push(ForbiddenItem::new_v2(
                             entry,
                             raw_hs_text.clone(),
@@ -257,6 +179,18 @@ impl Default for AltaScraper {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_hs_code_entry_range() {
+        let scraper = AltaScraper::new();
+        let entries = scraper.parse_hs_code_entry("из 8703 21 – 8703 24", "", "");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].code, "870321");
+        assert_eq!(entries[0].code_4, "8703");
+        assert_eq!(entries[0].code_high.as_deref(), Some("870324"));
+        assert!(entries[0].is_partial);
+    }
+
     #[test]
     fn test_scraper_creation() {
         let scraper = AltaScraper::new();
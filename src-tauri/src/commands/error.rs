@@ -46,3 +46,21 @@ impl From<std::io::Error> for CommandError {
         CommandError::new(error.to_string(), "IO_ERROR")
     }
 }
+
+impl From<crate::core::tabular::TabularError> for CommandError {
+    fn from(error: crate::core::tabular::TabularError) -> Self {
+        CommandError::new(error.to_string(), "FILE_ERROR")
+    }
+}
+
+impl From<crate::core::output::OutputError> for CommandError {
+    fn from(error: crate::core::output::OutputError) -> Self {
+        use crate::core::output::OutputError;
+        let code = match error {
+            OutputError::InvalidTemplate(_) => "CONFIG_ERROR",
+            OutputError::Exists(_) => "OUTPUT_EXISTS",
+            OutputError::NoDirectory | OutputError::Io(_) => "FILE_ERROR",
+        };
+        CommandError::new(error.to_string(), code)
+    }
+}
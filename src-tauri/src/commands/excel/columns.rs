@@ -0,0 +1,172 @@
+// ============================================================================
+// 按表头定位列
+// ============================================================================
+//
+// 规则中配置了表头名称（ColumnRule.header / Proportional.by_header）时，
+// 在表头行中按同义词查找实际列号，找不到则报错，避免客户模板多一列时静默算错。
+// 未配置表头的规则仍按固定列号处理。
+
+use crate::commands::error::CommandError;
+use crate::models::excel::{ProcessConfig, SplitMode};
+use super::reader::ExcelSheet;
+
+/// 同义词组：表头与组内任一名称匹配即视为同一列
+///
+/// "件数" 在装箱单中常指数量，不作为箱数的同义词。
+const HEADER_SYNONYMS: &[&[&str]] = &[
+    &["毛重", "总毛重", "G.W.", "GW", "Gross Weight", "Gross Wt", "GrossWeight"],
+    &["净重", "总净重", "N.W.", "NW", "Net Weight", "Net Wt"],
+    &["箱数", "总箱数", "CTNS", "CTN", "Cartons", "Carton", "Packages"],
+    &["数量", "总数量", "QTY", "Quantity", "PCS", "Pieces"],
+    &["体积", "CBM", "Volume", "Meas."],
+];
+
+/// 表头末尾可忽略的单位（如 "毛重(KG)"、"G.W. KGS"）
+const UNIT_SUFFIXES: [&str; 5] = ["kgs", "kg", "pcs", "ctns", "m3"];
+
+/// 解析配置中的表头，返回列号全部确定的配置
+///
+/// header_row 为配置的表头行（1-based），为空时自动检测。
+pub fn resolve_columns(
+    sheet: &ExcelSheet,
+    config: &ProcessConfig,
+    logs: &mut Vec<String>,
+) -> Result<ProcessConfig, CommandError> {
+    // 图片命名列只在导出图片时需要
+    let image_name_header = config
+        .images
+        .name_header
+        .as_ref()
+        .filter(|_| config.copy_images && config.images.export_to_folder);
+    let uses_headers = image_name_header.is_some()
+        || config.column_rules.iter().any(|rule| {
+            rule.header.is_some() || matches!(rule.mode, SplitMode::Proportional { by_header: Some(_), .. })
+        });
+    if !uses_headers {
+        return Ok(config.clone());
+    }
+
+    let header_row = match config.header_row {
+        Some(row) => row.saturating_sub(1),
+        None => sheet.header_row(),
+    };
+    let headers: Vec<String> = sheet
+        .get_row(header_row)
+        .into_iter()
+        .map(|h| h.unwrap_or_default().trim().to_string())
+        .collect();
+    logs.push(format!("表头行: 第 {} 行", header_row + 1));
+
+    let mut resolved = config.clone();
+    for rule in &mut resolved.column_rules {
+        if let Some(header) = &rule.header {
+            let column = find_column(&headers, header, header_row)?;
+            if column != rule.column {
+                logs.push(format!(
+                    "表头 \"{}\" 位于第 {} 列（配置为第 {} 列）",
+                    header, column, rule.column
+                ));
+            }
+            rule.column = column;
+        }
+
+        if let SplitMode::Proportional { by_column, by_header: Some(header) } = &mut rule.mode {
+            *by_column = find_column(&headers, header, header_row)?;
+        }
+    }
+
+    if let Some(header) = image_name_header {
+        resolved.images.name_column = Some(find_column(&headers, header, header_row)?);
+    }
+
+    Ok(resolved)
+}
+
+/// 在表头中查找列（1-based），找不到时列出实际表头
+fn find_column(headers: &[String], expected: &str, header_row: u32) -> Result<usize, CommandError> {
+    let candidates = synonyms(expected);
+    headers
+        .iter()
+        .position(|header| {
+            let header = normalize(header);
+            !header.is_empty() && candidates.iter().any(|c| *c == header || strip_unit(&header) == *c)
+        })
+        .map(|idx| idx + 1)
+        .ok_or_else(|| {
+            let found: Vec<&str> = headers.iter().map(|h| h.as_str()).filter(|h| !h.is_empty()).collect();
+            CommandError::new(
+                format!(
+                    "第 {} 行找不到表头 \"{}\"（可识别: {}），实际表头: {}",
+                    header_row + 1,
+                    expected,
+                    synonym_group(expected).unwrap_or(&[expected]).join(" / "),
+                    found.join(", ")
+                ),
+                "COLUMN_NOT_FOUND",
+            )
+        })
+}
+
+/// 表头名称所在的同义词组
+fn synonym_group(name: &str) -> Option<&'static [&'static str]> {
+    let name = normalize(name);
+    HEADER_SYNONYMS
+        .iter()
+        .find(|group| group.iter().any(|s| normalize(s) == name))
+        .copied()
+}
+
+/// 表头名称及其同义词（已归一化）
+fn synonyms(name: &str) -> Vec<String> {
+    match synonym_group(name) {
+        Some(group) => group.iter().map(|s| normalize(s)).collect(),
+        None => vec![normalize(name)],
+    }
+}
+
+/// 归一化：忽略大小写、空白、标点和括号
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '.' | ':' | '：' | '(' | ')' | '（' | '）' | '/' | '_' | '-'))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// 去掉末尾的单位
+fn strip_unit(header: &str) -> &str {
+    UNIT_SUFFIXES
+        .iter()
+        .find_map(|unit| header.strip_suffix(unit).filter(|rest| !rest.is_empty()))
+        .unwrap_or(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_column_by_synonym() {
+        let row = headers(&["序号", "品名", "QTY", "G.W. (KGS)", "CTNS"]);
+        assert_eq!(find_column(&row, "毛重", 0).unwrap(), 4);
+        assert_eq!(find_column(&row, "箱数", 0).unwrap(), 5);
+        assert_eq!(find_column(&row, "数量", 0).unwrap(), 3);
+        assert_eq!(find_column(&row, "品名", 0).unwrap(), 2);
+
+        // 件数在箱数列之前时不会被当作箱数
+        let row = headers(&["序号", "件数", "箱数"]);
+        assert_eq!(find_column(&row, "箱数", 0).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_missing_header_fails() {
+        let row = headers(&["序号", "净重"]);
+        let err = find_column(&row, "毛重", 1).unwrap_err();
+        assert_eq!(err.code, "COLUMN_NOT_FOUND");
+        assert!(err.message.contains("第 2 行"));
+        assert!(err.message.contains("序号, 净重"));
+    }
+}
@@ -1,14 +1,70 @@
 use crate::commands::error::CommandError;
-use crate::models::excel::{ProcessConfig, ProcessRequest, ProcessResponse};
+use crate::commands::output;
+use crate::core::batch::{self, BatchSummary};
+use crate::models::excel::{BatchProcessRequest, ProcessConfig, ProcessProfile, ProcessRequest, ProcessResponse, ProcessType, SplitPreview};
+use crate::models::output::OutputTool;
 use super::config;
 use super::processor;
+use super::profiles::ProfileStore;
+use tauri::Emitter;
 
 /// 处理 Excel 文件
 #[tauri::command]
 pub async fn process_excel_file(
     request: ProcessRequest,
 ) -> Result<ProcessResponse, CommandError> {
-    processor::process_excel_file(&request.file_path, &request.config)
+    let rule = output::load_rule(OutputTool::ExcelSplit)?;
+    processor::process_excel_file(&request.file_path, &request.config, &request.sheets, &rule)
+}
+
+/// 批量处理文件夹或 glob 匹配的文件，每个文件完成后发送 excel-batch-progress 事件
+#[tauri::command]
+pub async fn process_excel_batch(
+    window: tauri::Window,
+    request: BatchProcessRequest,
+) -> Result<BatchSummary, CommandError> {
+    let mut inputs = batch::collect_inputs(&request.source)?;
+    // 跳过之前生成的拆分表
+    let rule = output::load_rule(OutputTool::ExcelSplit)?;
+    inputs.retain(|path| {
+        !path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| rule.is_output_name(n))
+    });
+    if inputs.is_empty() {
+        return Err(CommandError::new("没有找到要处理的文件", "FILE_ERROR"));
+    }
+
+    let mut summary = batch::run_batch(
+        &inputs,
+        request.workers,
+        |path| {
+            let response = processor::process_excel_file(&path.to_string_lossy(), &request.config, &request.sheets, &rule)
+                .map_err(|e| e.message)?;
+            Ok(response.output_path.into())
+        },
+        |progress| {
+            let _ = window.emit("excel-batch-progress", progress);
+        },
+    );
+
+    if request.zip {
+        let dir = rule
+            .configured_dir()
+            .unwrap_or_else(|| inputs[0].parent().unwrap_or(std::path::Path::new(".")).to_path_buf());
+        batch::bundle_outputs(&mut summary, &dir, "拆分表")?;
+    }
+
+    Ok(summary)
+}
+
+/// 预览拆分结果（不写入文件）
+#[tauri::command]
+pub async fn preview_excel_split(
+    request: ProcessRequest,
+) -> Result<SplitPreview, CommandError> {
+    processor::preview_excel_split(&request.file_path, &request.config, &request.sheets)
 }
 
 /// 获取指定类型的配置
@@ -22,3 +78,54 @@ pub async fn get_excel_config(process_type: String) -> Result<ProcessConfig, Com
 pub async fn save_excel_config(config: ProcessConfig) -> Result<(), CommandError> {
     config::save_config_for_type(&config)
 }
+
+/// 获取所有处理方案（内置方案在前）
+#[tauri::command]
+pub async fn list_excel_profiles() -> Result<Vec<ProcessProfile>, CommandError> {
+    ProfileStore::open()?.list()
+}
+
+/// 基于处理类型的默认配置新建方案
+#[tauri::command]
+pub async fn create_excel_profile(
+    name: String,
+    process_type: ProcessType,
+) -> Result<ProcessProfile, CommandError> {
+    ProfileStore::open()?.create(&name, process_type)
+}
+
+/// 复制方案
+#[tauri::command]
+pub async fn clone_excel_profile(id: String, name: String) -> Result<ProcessProfile, CommandError> {
+    ProfileStore::open()?.clone_profile(&id, &name)
+}
+
+/// 重命名自定义方案
+#[tauri::command]
+pub async fn rename_excel_profile(id: String, name: String) -> Result<ProcessProfile, CommandError> {
+    ProfileStore::open()?.rename(&id, &name)
+}
+
+/// 保存方案配置
+#[tauri::command]
+pub async fn save_excel_profile(id: String, config: ProcessConfig) -> Result<(), CommandError> {
+    ProfileStore::open()?.update(&id, config)
+}
+
+/// 删除自定义方案
+#[tauri::command]
+pub async fn delete_excel_profile(id: String) -> Result<(), CommandError> {
+    ProfileStore::open()?.delete(&id)
+}
+
+/// 导出方案到 JSON 文件（ids 为空时导出所有自定义方案），返回导出数量
+#[tauri::command]
+pub async fn export_excel_profiles(ids: Vec<String>, path: String) -> Result<usize, CommandError> {
+    ProfileStore::open()?.export(&ids, std::path::Path::new(&path))
+}
+
+/// 从 JSON 文件导入方案
+#[tauri::command]
+pub async fn import_excel_profiles(path: String) -> Result<Vec<ProcessProfile>, CommandError> {
+    ProfileStore::open()?.import(std::path::Path::new(&path))
+}
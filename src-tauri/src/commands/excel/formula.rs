@@ -0,0 +1,308 @@
+// ============================================================================
+// 公式引用重定位
+// ============================================================================
+//
+// 把公式从一个单元格复制到另一个单元格时，按 Excel 的规则平移相对引用：
+//   =K5*L5        从第 5 行复制到第 7 行 → =K7*L7
+//   =$K$5*L5      绝对引用保持不变       → =$K$5*L7
+//   =SUM(A5:A9)   区域两端分别平移       → =SUM(A7:A11)
+//   =SUM(B:B)     整列引用不受行平移影响
+//
+// 字符串常量、带引号的工作表名和结构化引用（[...]）原样保留。
+
+/// Excel 最大行号
+const MAX_ROW: i64 = 1_048_576;
+
+/// Excel 最大列号（XFD）
+const MAX_COL: i64 = 16_384;
+
+/// 引用由文本计算得出的函数，平移后结果无法保证
+const UNMOVABLE_FUNCTIONS: [&str; 2] = ["INDIRECT(", "OFFSET("];
+
+/// 将公式平移 row_delta 行、col_delta 列
+///
+/// 返回 None 表示公式无法安全移动（引用越界，或包含 INDIRECT / OFFSET）。
+pub fn shift_formula(formula: &str, row_delta: i64, col_delta: i64) -> Option<String> {
+    if row_delta == 0 && col_delta == 0 {
+        return Some(formula.to_string());
+    }
+
+    let upper = formula.to_uppercase();
+    if UNMOVABLE_FUNCTIONS.iter().any(|f| upper.contains(f)) {
+        return None;
+    }
+
+    let chars: Vec<char> = formula.chars().collect();
+    let mut result = String::with_capacity(formula.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            // 字符串常量、带引号的工作表名：原样复制到配对的引号（"" / '' 为转义）
+            '"' | '\'' => {
+                let end = find_closing_quote(&chars, i, c);
+                result.extend(&chars[i..end]);
+                i = end;
+            }
+            // 结构化引用或外部工作簿：原样复制到配对的 ]
+            '[' => {
+                let end = find_closing_bracket(&chars, i);
+                result.extend(&chars[i..end]);
+                i = end;
+            }
+            _ if (c == '$' || c.is_ascii_alphanumeric()) && !is_name_char(chars.get(i.wrapping_sub(1))) => {
+                match parse_reference(&chars, i) {
+                    Some((reference, end)) => {
+                        result.push_str(&reference.shift(row_delta, col_delta)?);
+                        i = end;
+                    }
+                    None => {
+                        // 不是引用（函数名、数字、工作表名等）：整个标识符原样复制
+                        let end = skip_name(&chars, i);
+                        result.extend(&chars[i..end]);
+                        i = end;
+                    }
+                }
+            }
+            _ => {
+                result.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    Some(result)
+}
+
+/// 引用的一个端点（行或列，可能带 $）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Coord {
+    value: i64,
+    absolute: bool,
+}
+
+impl Coord {
+    fn shift(self, delta: i64, max: i64) -> Option<Coord> {
+        if self.absolute {
+            return Some(self);
+        }
+        let value = self.value + delta;
+        if !(1..=max).contains(&value) {
+            return None;
+        }
+        Some(Coord { value, absolute: false })
+    }
+
+    fn prefix(&self) -> &'static str {
+        if self.absolute { "$" } else { "" }
+    }
+}
+
+/// A1 引用
+#[derive(Debug, Clone, PartialEq)]
+enum Reference {
+    /// 单元格（A1、$A$1）
+    Cell { col: Coord, row: Coord },
+    /// 整列（A:C）
+    Columns { first: Coord, last: Coord },
+    /// 整行（5:9）
+    Rows { first: Coord, last: Coord },
+}
+
+impl Reference {
+    /// 平移后重新生成引用文本，越界时返回 None
+    fn shift(&self, row_delta: i64, col_delta: i64) -> Option<String> {
+        match self {
+            Reference::Cell { col, row } => {
+                let col = col.shift(col_delta, MAX_COL)?;
+                let row = row.shift(row_delta, MAX_ROW)?;
+                Some(format!(
+                    "{}{}{}{}",
+                    col.prefix(), column_letters(col.value), row.prefix(), row.value
+                ))
+            }
+            Reference::Columns { first, last } => {
+                let first = first.shift(col_delta, MAX_COL)?;
+                let last = last.shift(col_delta, MAX_COL)?;
+                Some(format!(
+                    "{}{}:{}{}",
+                    first.prefix(), column_letters(first.value), last.prefix(), column_letters(last.value)
+                ))
+            }
+            Reference::Rows { first, last } => {
+                let first = first.shift(row_delta, MAX_ROW)?;
+                let last = last.shift(row_delta, MAX_ROW)?;
+                Some(format!(
+                    "{}{}:{}{}",
+                    first.prefix(), first.value, last.prefix(), last.value
+                ))
+            }
+        }
+    }
+}
+
+/// 尝试在 start 处解析引用，返回 (引用, 结束位置)
+fn parse_reference(chars: &[char], start: usize) -> Option<(Reference, usize)> {
+    let reference = if let Some((col, after_col)) = read_column(chars, start) {
+        match read_row(chars, after_col) {
+            Some((row, end)) => (Reference::Cell { col, row }, end),
+            None => {
+                // 整列引用 A:C
+                let after_colon = expect_char(chars, after_col, ':')?;
+                let (last, end) = read_column(chars, after_colon)?;
+                (Reference::Columns { first: col, last }, end)
+            }
+        }
+    } else {
+        // 整行引用 5:9
+        let (first, after_first) = read_row(chars, start)?;
+        let after_colon = expect_char(chars, after_first, ':')?;
+        let (last, end) = read_row(chars, after_colon)?;
+        (Reference::Rows { first, last }, end)
+    };
+
+    // 后面紧跟标识符字符、"(" 或 "!" 的不是引用（如 LOG10(、Sheet1!）
+    match chars.get(reference.1) {
+        Some(&c) if is_name_char(Some(&c)) || c == '(' || c == '!' => None,
+        _ => Some(reference),
+    }
+}
+
+/// 读取列（可带 $，1-3 个字母）
+fn read_column(chars: &[char], start: usize) -> Option<(Coord, usize)> {
+    let (absolute, mut i) = match chars.get(start) {
+        Some('$') => (true, start + 1),
+        _ => (false, start),
+    };
+
+    let letters_start = i;
+    let mut value: i64 = 0;
+    while let Some(c) = chars.get(i).filter(|c| c.is_ascii_alphabetic()) {
+        value = value * 26 + (c.to_ascii_uppercase() as i64 - 'A' as i64 + 1);
+        i += 1;
+    }
+
+    let len = i - letters_start;
+    if len == 0 || len > 3 || value > MAX_COL {
+        return None;
+    }
+    Some((Coord { value, absolute }, i))
+}
+
+/// 读取行号（可带 $）
+fn read_row(chars: &[char], start: usize) -> Option<(Coord, usize)> {
+    let (absolute, mut i) = match chars.get(start) {
+        Some('$') => (true, start + 1),
+        _ => (false, start),
+    };
+
+    let digits_start = i;
+    while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+        i += 1;
+    }
+    if i == digits_start {
+        return None;
+    }
+
+    let value: i64 = chars[digits_start..i].iter().collect::<String>().parse().ok()?;
+    if !(1..=MAX_ROW).contains(&value) {
+        return None;
+    }
+    Some((Coord { value, absolute }, i))
+}
+
+fn expect_char(chars: &[char], pos: usize, expected: char) -> Option<usize> {
+    (chars.get(pos) == Some(&expected)).then_some(pos + 1)
+}
+
+/// 标识符字符（引用不能紧挨着这些字符）
+fn is_name_char(c: Option<&char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '\\' | '$'))
+}
+
+/// 跳过一个标识符或数字
+fn skip_name(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    while is_name_char(chars.get(i)) {
+        i += 1;
+    }
+    i
+}
+
+/// 找到配对的引号（两个连续引号视为转义），返回引号之后的位置
+fn find_closing_quote(chars: &[char], start: usize, quote: char) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == quote {
+            if chars.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// 找到配对的 ]（支持嵌套），返回 ] 之后的位置
+fn find_closing_bracket(chars: &[char], start: usize) -> usize {
+    let mut depth = 0;
+    for (i, &c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    chars.len()
+}
+
+/// 列号（1-based）转列字母
+fn column_letters(mut col: i64) -> String {
+    let mut letters = Vec::new();
+    while col > 0 {
+        let rem = ((col - 1) % 26) as u8;
+        letters.push((b'A' + rem) as char);
+        col = (col - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shift_relative_and_absolute() {
+        assert_eq!(shift_formula("=K5*L5", 2, 0).as_deref(), Some("=K7*L7"));
+        assert_eq!(shift_formula("=$K$5*L$5+$M5", 2, 1).as_deref(), Some("=$K$5*M$5+$M7"));
+        assert_eq!(shift_formula("=SUM(A5:A9)/SUM(B:B)", 3, 0).as_deref(), Some("=SUM(A8:A12)/SUM(B:B)"));
+        assert_eq!(shift_formula("=SUM(5:9)", 1, 0).as_deref(), Some("=SUM(6:10)"));
+        assert_eq!(shift_formula("=Z1", 0, 1).as_deref(), Some("=AA1"));
+    }
+
+    #[test]
+    fn test_leaves_non_references_alone() {
+        assert_eq!(
+            shift_formula("=LOG10(A1)&\"A1\"&'Sheet 1'!B2&Sheet2!C3", 1, 0).as_deref(),
+            Some("=LOG10(A2)&\"A1\"&'Sheet 1'!B3&Sheet2!C4")
+        );
+        assert_eq!(shift_formula("=ROUND(A1*1.5E3,2)", 1, 0).as_deref(), Some("=ROUND(A2*1.5E3,2)"));
+        assert_eq!(shift_formula("=Table1[Qty]*A1", 1, 0).as_deref(), Some("=Table1[Qty]*A2"));
+    }
+
+    #[test]
+    fn test_unmovable_formulas() {
+        assert_eq!(shift_formula("=A1", -1, 0), None);
+        assert_eq!(shift_formula("=INDIRECT(\"A\"&ROW())", 1, 0), None);
+        // 平移量为 0 时公式不变
+        assert_eq!(shift_formula("=INDIRECT(\"A1\")", 0, 0).as_deref(), Some("=INDIRECT(\"A1\")"));
+    }
+}
@@ -0,0 +1,321 @@
+// ============================================================================
+// 图片处理：缩放、压缩、去重，以及导出到文件夹
+// ============================================================================
+//
+// 图片在读取元数据后、拆分前统一处理，处理结果按内容缓存，同一张图片只解码和编码一次。
+// 导出时按命名列（如 SKU）或行号命名文件，内容相同的图片可只导出一次。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use crate::commands::error::CommandError;
+use crate::models::excel::{ImageOptions, ProcessConfig};
+use super::merge_parser::{EmbeddedImage, SheetMetadata};
+use super::reader::ExcelSheet;
+
+/// 缩放 JPEG 但未指定质量时使用的质量
+const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+/// 导出文件名的最大长度（字符）
+const MAX_NAME_LEN: usize = 100;
+
+/// 处理结果缓存项：原图数据、处理后的数据、扩展名
+type CacheEntry = (Arc<Vec<u8>>, Arc<Vec<u8>>, String);
+
+/// 图片处理器（跨工作表缓存处理结果）
+pub struct ImageProcessor<'a> {
+    options: &'a ImageOptions,
+    cache: HashMap<u64, Vec<CacheEntry>>,
+    original_bytes: usize,
+    processed_bytes: usize,
+    duplicates: usize,
+}
+
+impl<'a> ImageProcessor<'a> {
+    pub fn new(options: &'a ImageOptions) -> Self {
+        Self {
+            options,
+            cache: HashMap::new(),
+            original_bytes: 0,
+            processed_bytes: 0,
+            duplicates: 0,
+        }
+    }
+
+    /// 按选项缩放、压缩工作表中的所有图片
+    pub fn apply(&mut self, metadata: &mut SheetMetadata) {
+        if !self.options.transforms() {
+            return;
+        }
+        for image in metadata.cell_images.values_mut() {
+            let (data, extension) = self.process(&image.data, &image.extension);
+            image.data = data;
+            image.extension = extension;
+        }
+    }
+
+    /// 处理统计（没有重新编码任何图片时为 None）
+    pub fn summary(&self) -> Option<String> {
+        if self.original_bytes == 0 {
+            return None;
+        }
+        let mut summary = format!(
+            "图片处理: {:.1} MB → {:.1} MB",
+            self.original_bytes as f64 / 1_048_576.0,
+            self.processed_bytes as f64 / 1_048_576.0
+        );
+        if self.duplicates > 0 {
+            summary.push_str(&format!("，{} 张重复图片复用处理结果", self.duplicates));
+        }
+        Some(summary)
+    }
+
+    fn process(&mut self, data: &Arc<Vec<u8>>, extension: &str) -> (Arc<Vec<u8>>, String) {
+        // 去重时按内容查找，否则只复用同一个媒体文件的结果
+        let key = if self.options.dedupe { content_hash(data) } else { Arc::as_ptr(data) as u64 };
+
+        if let Some(entries) = self.cache.get(&key) {
+            if let Some((original, processed, ext)) = entries
+                .iter()
+                .find(|(original, _, _)| Arc::ptr_eq(original, data) || original == data)
+            {
+                if !Arc::ptr_eq(original, data) {
+                    self.duplicates += 1;
+                }
+                return (processed.clone(), ext.clone());
+            }
+        }
+
+        let (processed, ext) = match transform(data, self.options) {
+            Some((bytes, ext)) => (Arc::new(bytes), ext),
+            None => (data.clone(), extension.to_string()),
+        };
+        self.original_bytes += data.len();
+        self.processed_bytes += processed.len();
+        self.cache
+            .entry(key)
+            .or_default()
+            .push((data.clone(), processed.clone(), ext.clone()));
+        (processed, ext)
+    }
+}
+
+/// 缩放和重新压缩图片，不需要处理（或重新编码后反而更大）时返回 None
+///
+/// 超出最大尺寸时按比例缩小；设置了 JPEG 质量时不透明图片重新压缩为 JPEG，透明图片保持 PNG。
+pub fn transform(data: &[u8], options: &ImageOptions) -> Option<(Vec<u8>, String)> {
+    if !options.transforms() {
+        return None;
+    }
+
+    let format = image::guess_format(data).ok()?;
+    let img = image::load_from_memory_with_format(data, format).ok()?;
+    let (width, height) = img.dimensions();
+    let max_width = options.max_width.filter(|w| *w > 0).unwrap_or(width);
+    let max_height = options.max_height.filter(|h| *h > 0).unwrap_or(height);
+
+    let resized = width > max_width || height > max_height;
+    let img = if resized {
+        img.resize(max_width.min(width), max_height.min(height), FilterType::Triangle)
+    } else {
+        img
+    };
+
+    let quality = options
+        .jpeg_quality
+        .map(|q| q.clamp(1, 100))
+        .or((resized && format == ImageFormat::Jpeg).then_some(DEFAULT_JPEG_QUALITY));
+
+    let (bytes, extension) = match quality {
+        Some(quality) if !has_transparency(&img) => (encode_jpeg(&img, quality)?, "jpg"),
+        _ if resized => (encode_png(&img)?, "png"),
+        _ => return None,
+    };
+
+    if !resized && bytes.len() >= data.len() {
+        return None;
+    }
+    Some((bytes, extension.to_string()))
+}
+
+fn has_transparency(img: &DynamicImage) -> bool {
+    img.color().has_alpha() && img.to_rgba8().pixels().any(|p| p[3] < 255)
+}
+
+fn encode_jpeg(img: &DynamicImage, quality: u8) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let rgb = DynamicImage::ImageRgb8(img.to_rgb8());
+    rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality)).ok()?;
+    Some(bytes)
+}
+
+fn encode_png(img: &DynamicImage) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).ok()?;
+    Some(bytes)
+}
+
+fn content_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 待导出的图片：文件名（不含扩展名）和图片
+pub type ImageExport = (String, EmbeddedImage);
+
+/// 收集工作表中要导出的图片，按行列顺序，文件名取命名列的值（合并单元格取合并区域的值），为空时用行号
+///
+/// prefix 用于多个工作表时区分文件名。
+pub fn collect_exports(
+    sheet: &ExcelSheet,
+    metadata: &SheetMetadata,
+    config: &ProcessConfig,
+    prefix: Option<&str>,
+) -> Vec<ImageExport> {
+    let mut positions: Vec<_> = metadata.cell_images.keys().copied().collect();
+    positions.sort_unstable();
+
+    positions
+        .into_iter()
+        .map(|(row, col)| {
+            let label = config
+                .images
+                .name_column
+                .and_then(|name_col| {
+                    let col = name_col.checked_sub(1)? as u32;
+                    let row = metadata
+                        .merged_ranges
+                        .iter()
+                        .find(|range| range.contains(row, col))
+                        .map_or(row, |range| range.start_row);
+                    sheet.get_string(row, col)
+                })
+                .map(|name| sanitize_name(&name))
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("第{}行", row + 1));
+
+            let name = match prefix {
+                Some(prefix) => format!("{}_{}", sanitize_name(prefix), label),
+                None => label,
+            };
+            (name, metadata.cell_images[&(row, col)].clone())
+        })
+        .collect()
+}
+
+/// 导出结果
+#[derive(Debug, Default)]
+pub struct ExportStats {
+    pub written: usize,
+}
+
+/// 把图片写入文件夹，重名时加序号
+///
+/// 每个名称都写一个文件，多行或多个 SKU 共用同一张图片时各自都有对应的文件。
+/// 文件夹中已有的文件（之前导出的图片）视为已占用，不会被覆盖。
+pub fn export_images(dir: &Path, images: Vec<ImageExport>) -> Result<ExportStats, CommandError> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| CommandError::new(format!("创建图片文件夹失败: {}", e), "FILE_ERROR"))?;
+
+    let mut stats = ExportStats::default();
+    let mut used_names: HashSet<String> = std::fs::read_dir(dir)
+        .map_err(|e| CommandError::new(format!("读取图片文件夹失败: {}", e), "FILE_ERROR"))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .map(|name| name.to_lowercase())
+        .collect();
+
+    for (name, image) in images {
+        let mut file_name = format!("{}.{}", name, image.extension);
+        let mut seq = 2;
+        while !used_names.insert(file_name.to_lowercase()) {
+            file_name = format!("{}_{}.{}", name, seq, image.extension);
+            seq += 1;
+        }
+
+        std::fs::write(dir.join(&file_name), image.data.as_slice())
+            .map_err(|e| CommandError::new(format!("写入图片 {} 失败: {}", file_name, e), "FILE_ERROR"))?;
+        stats.written += 1;
+    }
+
+    Ok(stats)
+}
+
+/// 去掉文件名中不允许的字符
+fn sanitize_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c.is_control() || "\\/:*?\"<>|".contains(c) { '_' } else { c })
+        .take(MAX_NAME_LEN)
+        .collect::<String>()
+        .trim_end_matches(['.', ' '])
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let img = DynamicImage::new_rgb8(width, height);
+        encode_png(&img).unwrap()
+    }
+
+    #[test]
+    fn test_transform_resizes_and_compresses() {
+        let data = png(400, 200);
+        let options = ImageOptions { max_width: Some(100), jpeg_quality: Some(80), ..Default::default() };
+
+        let (bytes, ext) = transform(&data, &options).unwrap();
+        assert_eq!(ext, "jpg");
+        let img = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(img.dimensions(), (100, 50));
+
+        // 未设置任何选项时不处理
+        assert!(transform(&data, &ImageOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_dedupe_and_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = |data: &Vec<u8>| EmbeddedImage {
+            image_id: String::new(),
+            data: Arc::new(data.clone()),
+            extension: "png".to_string(),
+        };
+        let data = png(10, 10);
+
+        // 内容相同的两个媒体文件只处理一次
+        let options = ImageOptions { max_width: Some(5), ..Default::default() };
+        let mut processor = ImageProcessor::new(&options);
+        let (first, _) = processor.process(&Arc::new(data.clone()), "png");
+        let (second, _) = processor.process(&Arc::new(data.clone()), "png");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(processor.duplicates, 1);
+
+        let exports = vec![
+            ("SKU_1".to_string(), image(&data)),
+            ("SKU-2".to_string(), image(&data)),
+            ("SKU-2".to_string(), image(&png(3, 3))),
+        ];
+        // 共用同一张图片的 SKU 也各自导出
+        let stats = export_images(dir.path(), exports).unwrap();
+        assert_eq!(stats.written, 3);
+        assert!(dir.path().join("SKU_1.png").exists());
+        assert!(dir.path().join("SKU-2.png").exists());
+        assert!(dir.path().join("SKU-2_2.png").exists());
+
+        // 再次导出到同一文件夹时不覆盖之前的图片
+        let stats = export_images(dir.path(), vec![("SKU_1".to_string(), image(&png(3, 3)))]).unwrap();
+        assert_eq!(stats.written, 1);
+        assert_eq!(std::fs::read(dir.path().join("SKU_1.png")).unwrap(), data);
+        assert!(dir.path().join("SKU_1_2.png").exists());
+        assert_eq!(sanitize_name(" SKU/1 "), "SKU_1");
+    }
+}
@@ -1,26 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, Read, Cursor};
+use std::sync::Arc;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use crate::commands::error::CommandError;
 use crate::models::excel::MergedRange;
 use image::ImageFormat;
+use super::styles::{parse_styles_xml, parse_theme_colors, StylesInfo};
 
-/// 单元格样式信息
-#[derive(Debug, Clone, Default)]
-pub struct CellStyle {
-    pub number_format: Option<String>,      // 数字格式（如 "0.00", "General" 等）
-    pub background_color: Option<String>,   // 背景颜色（RGB 十六进制）
-    pub font_color: Option<String>,         // 字体颜色
-    pub is_bold: bool,
-}
+pub use super::styles::CellStyle;
 
 /// 嵌入图片信息
 #[derive(Debug, Clone)]
 pub struct EmbeddedImage {
     pub image_id: String,          // 图片ID（如 ID_ACFEA68153BF450AAF4F180501501BB8）
-    pub data: Vec<u8>,             // 图片二进制数据
+    pub data: Arc<Vec<u8>>,        // 图片二进制数据（同一媒体文件在多个单元格间共享）
     pub extension: String,         // 文件扩展名（png, jpg 等）
 }
 
@@ -35,9 +30,14 @@ struct FloatingImageInfo {
 /// 工作表元数据（合并单元格、列宽、样式等）
 #[derive(Debug, Default)]
 pub struct SheetMetadata {
+    pub sheet_name: String,
     pub merged_ranges: Vec<MergedRange>,
     pub column_widths: HashMap<u32, f64>,
     pub default_column_width: f64,
+    pub row_heights: HashMap<u32, f64>,       // row -> 行高（磅）
+    pub default_row_height: Option<f64>,      // 默认行高（sheetFormatPr）
+    pub hidden_rows: HashSet<u32>,            // 隐藏的行
+    pub hidden_columns: HashSet<u32>,         // 隐藏的列
     pub cell_styles: HashMap<(u32, u32), CellStyle>,  // (row, col) -> 样式
     pub cell_formulas: HashMap<(u32, u32), String>,   // (row, col) -> 公式
     pub cell_images: HashMap<(u32, u32), EmbeddedImage>,  // (row, col) -> 嵌入图片
@@ -45,167 +45,273 @@ pub struct SheetMetadata {
     pub unsupported_images: Vec<String>,  // 无法处理的图片列表
 }
 
-/// 从 xlsx 文件中解析合并单元格信息
-pub fn parse_merged_cells(file_path: &str) -> Result<Vec<MergedRange>, CommandError> {
-    let metadata = parse_sheet_metadata(file_path)?;
-    Ok(metadata.merged_ranges)
+/// 工作表信息（来自 workbook.xml 及其 rels）
+#[derive(Debug, Clone)]
+pub struct SheetInfo {
+    pub name: String,  // 工作表名称
+    pub path: String,  // 工作表 XML 在压缩包中的路径（如 xl/worksheets/sheet2.xml）
 }
 
-/// 从 xlsx 文件中解析工作表元数据（合并单元格、列宽、样式和图片）
-pub fn parse_sheet_metadata(file_path: &str) -> Result<SheetMetadata, CommandError> {
-    let file = File::open(file_path)
-        .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
-
-    let reader = BufReader::new(file);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
-
-    // 1. 解析样式文件
-    let styles = if let Ok(styles_xml) = archive.by_name("xl/styles.xml") {
-        let mut styles_content = String::new();
-        BufReader::new(styles_xml).read_to_string(&mut styles_content)
-            .map_err(|e| CommandError::new(format!("读取样式 XML 失败: {}", e), "FILE_ERROR"))?;
-        parse_styles_xml(&styles_content)?
-    } else {
-        StylesInfo::default()
-    };
+/// rels 文件中的一条关系
+#[derive(Debug, Clone)]
+struct Relationship {
+    id: String,
+    target: String,
+    rel_type: String,
+}
 
-    // 重新打开文件
-    let file = File::open(file_path)
-        .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
-    let reader = BufReader::new(file);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
-
-    // 2. 读取所有媒体文件（图片）
-    let (media_files, converted_images, unsupported_images) = read_media_files(&mut archive)?;
-
-    // 重新打开文件
-    let file = File::open(file_path)
-        .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
-    let reader = BufReader::new(file);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
-
-    // 3. 解析 cellimages.xml 获取图片ID -> rId 映射
-    let id_to_rid = parse_cell_images_xml(&mut archive)?;
-
-    // 重新打开文件
-    let file = File::open(file_path)
-        .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
-    let reader = BufReader::new(file);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
-
-    // 4. 解析 cellimages.xml.rels 获取 rId -> 媒体文件 映射
-    let rid_to_file = parse_cellimages_rels(&mut archive)?;
-
-    // 重新打开文件
-    let file = File::open(file_path)
-        .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
-    let reader = BufReader::new(file);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
-
-    // 4.5 从 drawing1.xml 解析图片 ID → rId 映射 以及浮动图片位置
-    let (drawing_id_to_rid, floating_images) = parse_drawing_xml(&mut archive)?;
-
-    // 重新打开文件
-    let file = File::open(file_path)
-        .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
-    let reader = BufReader::new(file);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
-
-    // 4.6 从 drawing1.xml.rels 解析 rId → 媒体文件映射
-    let drawing_rid_to_file = parse_drawing_rels(&mut archive)?;
-
-    // 重新打开文件
-    let file = File::open(file_path)
-        .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
-    let reader = BufReader::new(file);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
-
-    // 5. 解析工作表 XML
-    let sheet_xml = archive.by_name("xl/worksheets/sheet1.xml")
-        .map_err(|e| CommandError::new(format!("找不到工作表 XML: {}", e), "FILE_ERROR"))?;
-
-    let mut xml_content = String::new();
-    BufReader::new(sheet_xml).read_to_string(&mut xml_content)
-        .map_err(|e| CommandError::new(format!("读取 XML 失败: {}", e), "FILE_ERROR"))?;
-
-    let mut metadata = parse_metadata_from_xml(&xml_content, &styles)?;
-
-    // 6. 关联图片到单元格
-    // 优先使用 cellimages 映射 (WPS 嵌入图片)，如果为空则回退到 drawings 映射
-    let final_id_to_rid = if id_to_rid.is_empty() { drawing_id_to_rid } else { id_to_rid };
-    let final_rid_to_file = if rid_to_file.is_empty() { drawing_rid_to_file.clone() } else { rid_to_file };
-
-    let image_mapping = ImageMappingInfo {
-        id_to_rid: final_id_to_rid,
-        rid_to_file: final_rid_to_file,
-    };
+/// 媒体文件缓存项：(图片数据, 扩展名)，无法处理的图片缓存为 None
+type MediaEntry = Option<(Arc<Vec<u8>>, String)>;
 
-    // 处理 DISPIMG 公式引用的图片
-    link_images_to_cells(&mut metadata, &media_files, &image_mapping);
+/// 读取媒体文件的结果，与图片一起缓存，每个用到该图片的工作表都记录到元数据中
+#[derive(Debug, Clone)]
+enum MediaNote {
+    Loaded,
+    Converted(String),                    // 如 "image1.webp (WebP->PNG)"
+    Unsupported(String),                  // 文件名
+}
 
-    // 处理浮动图片（直接通过位置关联）
-    link_floating_images(&mut metadata, &media_files, &floating_images, &drawing_rid_to_file);
+/// xlsx 压缩包句柄
+///
+/// 整个处理过程只打开一次文件：styles.xml 和 cellimages.xml 在打开时解析一次，
+/// 媒体文件只在被单元格实际引用时才读取（并按路径缓存）。
+pub struct XlsxPackage {
+    archive: zip::ZipArchive<BufReader<File>>,
+    styles: StylesInfo,
+    cell_image_paths: HashMap<String, String>,  // WPS 嵌入图片：图片ID -> 媒体文件路径
+    media_cache: HashMap<String, (MediaEntry, MediaNote)>,
+}
 
-    // 保存图片处理信息
-    metadata.converted_images = converted_images;
-    metadata.unsupported_images = unsupported_images;
+impl XlsxPackage {
+    /// 打开 xlsx 文件
+    pub fn open(file_path: &str) -> Result<Self, CommandError> {
+        let file = File::open(file_path)
+            .map_err(|e| CommandError::new(format!("打开文件失败: {}", e), "FILE_ERROR"))?;
+
+        let reader = BufReader::new(file);
+        let mut archive = zip::ZipArchive::new(reader)
+            .map_err(|e| CommandError::new(format!("解析 ZIP 文件失败: {}", e), "FILE_ERROR"))?;
+
+        // 解析主题配色（用于 theme 颜色）和样式文件
+        let theme_path = parse_rels(&mut archive, "xl/_rels/workbook.xml.rels")
+            .into_iter()
+            .find(|rel| rel.rel_type.ends_with("/theme"))
+            .map(|rel| resolve_target("xl", &rel.target))
+            .unwrap_or_else(|| "xl/theme/theme1.xml".to_string());
+        let theme_colors = read_archive_text(&mut archive, &theme_path)
+            .map(|content| parse_theme_colors(&content))
+            .unwrap_or_default();
+
+        let styles = match read_archive_text(&mut archive, "xl/styles.xml") {
+            Some(content) => parse_styles_xml(&content, theme_colors)?,
+            None => StylesInfo::default(),
+        };
+
+        // 解析 cellimages.xml（图片ID -> rId）及其 rels（rId -> 媒体文件）
+        let id_to_rid = parse_cell_images_xml(&mut archive)?;
+        let rid_to_path = parse_media_rels(&mut archive, "xl/cellimages.xml");
+        let cell_image_paths = id_to_rid
+            .into_iter()
+            .filter_map(|(id, rid)| rid_to_path.get(&rid).map(|path| (id, path.clone())))
+            .collect();
+
+        Ok(XlsxPackage {
+            archive,
+            styles,
+            cell_image_paths,
+            media_cache: HashMap::new(),
+        })
+    }
 
-    Ok(metadata)
-}
+    /// 通过 workbook.xml 和 workbook.xml.rels 列出所有工作表（按工作簿中的顺序）
+    pub fn sheets(&mut self) -> Result<Vec<SheetInfo>, CommandError> {
+        let workbook_xml = read_archive_text(&mut self.archive, "xl/workbook.xml")
+            .ok_or_else(|| CommandError::new("找不到 xl/workbook.xml", "FILE_ERROR"))?;
+        let rels = parse_rels(&mut self.archive, "xl/_rels/workbook.xml.rels");
+
+        // 解析 <sheet name="..." r:id="rIdN"/>
+        let mut sheet_refs: Vec<(String, String)> = Vec::new();
+        let mut reader = Reader::from_str(&workbook_xml);
+        reader.trim_text(true);
+
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    if e.name().local_name().as_ref() == b"sheet" {
+                        let mut name: Option<String> = None;
+                        let mut rid: Option<String> = None;
+
+                        for attr in e.attributes().flatten() {
+                            let key_local = attr.key.local_name();
+                            let raw = String::from_utf8_lossy(&attr.value).to_string();
+                            let value = quick_xml::escape::unescape(&raw)
+                                .map(|v| v.to_string())
+                                .unwrap_or(raw.clone());
+                            match key_local.as_ref() {
+                                b"name" => name = Some(value),
+                                b"id" => rid = Some(value),
+                                _ => {}
+                            }
+                        }
 
-/// 读取 xl/media/ 目录中的所有图片文件
-fn read_media_files(archive: &mut zip::ZipArchive<BufReader<File>>) -> Result<(HashMap<String, (Vec<u8>, String)>, Vec<String>, Vec<String>), CommandError> {
-    let mut media_files = HashMap::new();
-    let mut converted_images = Vec::new();
-    let mut unsupported_images = Vec::new();
+                        if let (Some(name), Some(rid)) = (name, rid) {
+                            sheet_refs.push((name, rid));
+                        }
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(CommandError::new(format!("workbook.xml 解析错误: {}", e), "PARSE_ERROR")),
+                _ => {}
+            }
+            buf.clear();
+        }
 
-    let file_names: Vec<String> = (0..archive.len())
-        .filter_map(|i| archive.by_index(i).ok().map(|f| f.name().to_string()))
-        .collect();
+        let sheets: Vec<SheetInfo> = sheet_refs
+            .into_iter()
+            .filter_map(|(name, rid)| {
+                let rel = rels.iter().find(|r| r.id == rid)?;
+                // 只保留普通工作表（跳过图表页等）
+                if !rel.rel_type.ends_with("/worksheet") {
+                    return None;
+                }
+                Some(SheetInfo {
+                    name,
+                    path: resolve_target("xl", &rel.target),
+                })
+            })
+            .collect();
+
+        if sheets.is_empty() {
+            return Err(CommandError::new("Excel 文件中没有工作表", "FILE_ERROR"));
+        }
+
+        Ok(sheets)
+    }
 
+    /// 解析指定工作表的元数据（合并单元格、列宽、样式，以及 load_images 时的图片）
+    pub fn sheet_metadata(&mut self, sheet: &SheetInfo, load_images: bool) -> Result<SheetMetadata, CommandError> {
+        let xml_content = read_archive_text(&mut self.archive, &sheet.path)
+            .ok_or_else(|| CommandError::new(format!("找不到工作表 XML: {}", sheet.path), "FILE_ERROR"))?;
 
-    for name in file_names {
-        // 检查多个可能的图片存储位置
-        if name.starts_with("xl/media/") || name.starts_with("xl/embeddings/") || name.contains("/media/") {
-            if let Ok(mut file) = archive.by_name(&name) {
-                let mut data = Vec::new();
-                if file.read_to_end(&mut data).is_ok() && data.len() >= 8 {
-                    let filename = name.rsplit('/').next().unwrap_or(&name);
-
-                    if is_valid_image(&data) {
-                        let ext = filename.rsplit('.').next().unwrap_or("png").to_string();
-                        media_files.insert(filename.to_string(), (data, ext));
-                    } else if let Some((converted_data, original_format)) = try_convert_image(&data) {
-                        // 成功转换格式
-                        media_files.insert(filename.to_string(), (converted_data, "png".to_string()));
-                        converted_images.push(format!("{} ({}->PNG)", filename, original_format));
-                    } else {
-                        unsupported_images.push(filename.to_string());
-                    }
+        let mut metadata = parse_metadata_from_xml(&xml_content, &self.styles)?;
+        metadata.sheet_name = sheet.name.clone();
+
+        // 不需要图片时，完全不读取 drawing 和媒体文件
+        if load_images {
+            self.link_images(&mut metadata, sheet)?;
+        }
+
+        Ok(metadata)
+    }
+
+    /// 关联图片到单元格（DISPIMG 公式图片和浮动图片）
+    fn link_images(&mut self, metadata: &mut SheetMetadata, sheet: &SheetInfo) -> Result<(), CommandError> {
+        // 通过工作表 rels 找到该工作表自己的 drawing
+        let drawing_path = find_sheet_drawing(&mut self.archive, &sheet.path);
+        let (drawing_id_to_rid, floating_images) = match &drawing_path {
+            Some(path) => parse_drawing_xml(&mut self.archive, path)?,
+            None => (HashMap::new(), Vec::new()),
+        };
+        let drawing_rid_to_path = match &drawing_path {
+            Some(path) => parse_media_rels(&mut self.archive, path),
+            None => HashMap::new(),
+        };
+
+        // 1. DISPIMG 公式引用的图片：优先使用 cellimages 映射 (WPS 嵌入图片)，否则回退到 drawing 映射
+        let dispimg_cells: Vec<((u32, u32), String)> = metadata.cell_formulas
+            .iter()
+            .filter_map(|(pos, formula)| extract_dispimg_id(formula).map(|id| (*pos, id)))
+            .collect();
+
+        for (pos, image_id) in dispimg_cells {
+            let media_path = self.cell_image_paths.get(&image_id).cloned().or_else(|| {
+                drawing_id_to_rid
+                    .get(&image_id)
+                    .and_then(|rid| drawing_rid_to_path.get(rid))
+                    .cloned()
+            });
+
+            if let Some(path) = media_path {
+                if let Some((data, ext)) = self.load_media(&path, metadata) {
+                    metadata.cell_images.insert(pos, EmbeddedImage {
+                        image_id,
+                        data,
+                        extension: ext,
+                    });
+                }
+            }
+        }
+
+        eprintln!("[DEBUG] 关联DISPIMG图片: {} 个", metadata.cell_images.len());
+
+        // 2. 浮动图片（直接通过位置关联）
+        let mut linked_count = 0;
+        for img_info in &floating_images {
+            // 如果该位置已经有图片（来自 DISPIMG），跳过
+            if metadata.cell_images.contains_key(&(img_info.row, img_info.col)) {
+                continue;
+            }
+
+            if let Some(path) = drawing_rid_to_path.get(&img_info.rid) {
+                if let Some((data, ext)) = self.load_media(path, metadata) {
+                    metadata.cell_images.insert((img_info.row, img_info.col), EmbeddedImage {
+                        image_id: format!("floating_{}_{}_{}", img_info.row, img_info.col, &img_info.rid),
+                        data,
+                        extension: ext,
+                    });
+                    linked_count += 1;
                 }
             }
         }
+
+        eprintln!("[DEBUG] 关联浮动图片: {} 个", linked_count);
+        Ok(())
     }
 
-    eprintln!("[DEBUG] 读取到 {} 个有效图片", media_files.len());
-    if !converted_images.is_empty() {
-        eprintln!("[INFO] 转换了 {} 个图片格式: {}",
-            converted_images.len(),
-            converted_images.join(", "));
+    /// 按需读取媒体文件（带缓存），转换/跳过的图片记录到工作表元数据中（使用缓存时同样记录）
+    fn load_media(&mut self, path: &str, metadata: &mut SheetMetadata) -> MediaEntry {
+        let archive = &mut self.archive;
+        let (entry, note) = self
+            .media_cache
+            .entry(path.to_string())
+            .or_insert_with(|| read_media_file(archive, path));
+
+        let (list, name) = match note {
+            MediaNote::Loaded => return entry.clone(),
+            MediaNote::Converted(name) => (&mut metadata.converted_images, name),
+            MediaNote::Unsupported(name) => (&mut metadata.unsupported_images, name),
+        };
+        if !list.contains(name) {
+            list.push(name.clone());
+        }
+        entry.clone()
     }
-    if !unsupported_images.is_empty() {
-        eprintln!("[警告] 跳过 {} 个无法处理的图片: {}",
-            unsupported_images.len(),
-            unsupported_images.join(", "));
+}
+
+/// 读取单个媒体文件；不受支持的格式会尝试转换为 PNG
+fn read_media_file(archive: &mut zip::ZipArchive<BufReader<File>>, path: &str) -> (MediaEntry, MediaNote) {
+    let Ok(mut file) = archive.by_name(path) else {
+        return (None, MediaNote::Loaded);
+    };
+    let mut data = Vec::new();
+    if file.read_to_end(&mut data).is_err() || data.len() < 8 {
+        return (None, MediaNote::Loaded);
+    }
+
+    let filename = path.rsplit('/').next().unwrap_or(path);
+
+    if is_valid_image(&data) {
+        let ext = filename.rsplit('.').next().unwrap_or("png").to_string();
+        (Some((Arc::new(data), ext)), MediaNote::Loaded)
+    } else if let Some((converted_data, original_format)) = try_convert_image(&data) {
+        // 成功转换格式
+        let note = MediaNote::Converted(format!("{} ({}->PNG)", filename, original_format));
+        (Some((Arc::new(converted_data), "png".to_string())), note)
+    } else {
+        eprintln!("[警告] 跳过无法处理的图片: {}", filename);
+        (None, MediaNote::Unsupported(filename.to_string()))
     }
-    Ok((media_files, converted_images, unsupported_images))
 }
 
 /// 尝试转换不支持的图片格式到 PNG
@@ -279,15 +385,6 @@ fn is_valid_image(data: &[u8]) -> bool {
     false
 }
 
-/// 图片映射信息
-#[derive(Debug, Default)]
-struct ImageMappingInfo {
-    /// 图片ID (如 ID_xxx) -> rId 的映射
-    id_to_rid: HashMap<String, String>,
-    /// rId -> 媒体文件名的映射
-    rid_to_file: HashMap<String, String>,
-}
-
 /// 解析 xl/cellimages.xml 获取图片ID到rId的映射
 fn parse_cell_images_xml(archive: &mut zip::ZipArchive<BufReader<File>>) -> Result<HashMap<String, String>, CommandError> {
     let mut image_id_map = HashMap::new();
@@ -367,18 +464,12 @@ fn parse_cell_images_xml(archive: &mut zip::ZipArchive<BufReader<File>>) -> Resu
     Ok(image_id_map)
 }
 
-/// 解析 xl/drawings/drawing1.xml 获取图片 ID (name) → rId 的映射，以及浮动图片位置信息
-fn parse_drawing_xml(archive: &mut zip::ZipArchive<BufReader<File>>) -> Result<(HashMap<String, String>, Vec<FloatingImageInfo>), CommandError> {
+/// 解析工作表的 drawing XML 获取图片 ID (name) → rId 的映射，以及浮动图片位置信息
+fn parse_drawing_xml(archive: &mut zip::ZipArchive<BufReader<File>>, drawing_path: &str) -> Result<(HashMap<String, String>, Vec<FloatingImageInfo>), CommandError> {
     let mut id_to_rid = HashMap::new();
     let mut floating_images = Vec::new();
 
-    let content = if let Ok(mut file) = archive.by_name("xl/drawings/drawing1.xml") {
-        let mut s = String::new();
-        file.read_to_string(&mut s).ok();
-        s
-    } else {
-        String::new()
-    };
+    let content = read_archive_text(archive, drawing_path).unwrap_or_default();
 
     if content.is_empty() {
         return Ok((id_to_rid, floating_images));
@@ -501,121 +592,64 @@ fn parse_drawing_xml(archive: &mut zip::ZipArchive<BufReader<File>>) -> Result<(
         buf.clear();
     }
 
-    eprintln!("[DEBUG] drawing1.xml: {} 个ID映射, {} 个浮动图片", id_to_rid.len(), floating_images.len());
+    eprintln!("[DEBUG] {}: {} 个ID映射, {} 个浮动图片", drawing_path, id_to_rid.len(), floating_images.len());
     Ok((id_to_rid, floating_images))
 }
 
-/// 解析 xl/drawings/_rels/drawing1.xml.rels 获取 rId → 媒体文件的映射
-fn parse_drawing_rels(archive: &mut zip::ZipArchive<BufReader<File>>) -> Result<HashMap<String, String>, CommandError> {
-    let mut rid_to_file = HashMap::new();
-
-    let content = if let Ok(mut file) = archive.by_name("xl/drawings/_rels/drawing1.xml.rels") {
-        let mut s = String::new();
-        file.read_to_string(&mut s).ok();
-        s
-    } else {
-        String::new()
-    };
-
-    if content.is_empty() {
-        return Ok(rid_to_file);
-    }
-
-    // 解析 rels 文件
-    let mut reader = Reader::from_str(&content);
-    reader.trim_text(true);
-
-    let mut buf = Vec::new();
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                let name_local = e.name().local_name();
-                let local_name = std::str::from_utf8(name_local.as_ref()).unwrap_or("");
-
-                if local_name == "Relationship" {
-                    let mut rid: Option<String> = None;
-                    let mut target: Option<String> = None;
-
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr {
-                            let key_local = attr.key.local_name();
-                            let key = std::str::from_utf8(key_local.as_ref()).unwrap_or("");
-                            let value = String::from_utf8_lossy(&attr.value).to_string();
-                            match key {
-                                "Id" => rid = Some(value),
-                                "Target" => target = Some(value),
-                                _ => {}
-                            }
-                        }
-                    }
+/// 解析 part（drawing 或 cellimages.xml）的 rels，获取 rId → 媒体文件路径 的映射
+fn parse_media_rels(archive: &mut zip::ZipArchive<BufReader<File>>, part_path: &str) -> HashMap<String, String> {
+    let base_dir = parent_dir(part_path);
+    let rid_to_path: HashMap<String, String> = parse_rels(archive, &rels_path_for(part_path))
+        .into_iter()
+        .map(|rel| (rel.id, resolve_target(base_dir, &rel.target)))
+        .collect();
 
-                    if let (Some(id), Some(t)) = (rid, target) {
-                        // 提取文件名
-                        let filename = t.rsplit('/').next().unwrap_or(&t).to_string();
-                        rid_to_file.insert(id, filename);
-                    }
-                }
-            }
-            Ok(Event::Eof) => break,
-            Err(_) => break,
-            _ => {}
-        }
-        buf.clear();
-    }
+    eprintln!("[DEBUG] {} rels: {} 个映射", part_path, rid_to_path.len());
+    rid_to_path
+}
 
-    eprintln!("[DEBUG] drawing1.xml.rels: {} 个映射", rid_to_file.len());
-    Ok(rid_to_file)
+/// 通过工作表的 rels 找到其 drawing 文件路径
+fn find_sheet_drawing(archive: &mut zip::ZipArchive<BufReader<File>>, sheet_path: &str) -> Option<String> {
+    let base_dir = parent_dir(sheet_path);
+    parse_rels(archive, &rels_path_for(sheet_path))
+        .into_iter()
+        .find(|rel| rel.rel_type.ends_with("/drawing"))
+        .map(|rel| resolve_target(base_dir, &rel.target))
 }
 
-/// 解析 xl/_rels/cellimages.xml.rels 获取 rId 到媒体文件的映射
-fn parse_cellimages_rels(archive: &mut zip::ZipArchive<BufReader<File>>) -> Result<HashMap<String, String>, CommandError> {
-    let mut rid_to_file = HashMap::new();
+/// 解析 rels 文件（文件不存在时返回空列表）
+fn parse_rels(archive: &mut zip::ZipArchive<BufReader<File>>, rels_path: &str) -> Vec<Relationship> {
+    let mut rels = Vec::new();
 
-    // 尝试读取 rels 文件
-    let rels_content = if let Ok(mut file) = archive.by_name("xl/_rels/cellimages.xml.rels") {
-        let mut content = String::new();
-        file.read_to_string(&mut content).ok();
-        content
-    } else {
-        String::new()
+    let content = match read_archive_text(archive, rels_path) {
+        Some(content) => content,
+        None => return rels,
     };
 
-    if rels_content.is_empty() {
-        return Ok(rid_to_file);
-    }
-
-    // 解析 rels 文件获取 Id (rId) 和 Target (媒体文件路径)
-    let mut reader = Reader::from_str(&rels_content);
+    let mut reader = Reader::from_str(&content);
     reader.trim_text(true);
 
     let mut buf = Vec::new();
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                let local_name_bytes = e.name().local_name().as_ref().to_vec();
-                let local_name = std::str::from_utf8(&local_name_bytes).unwrap_or("");
-
-                if local_name == "Relationship" {
-                    let mut rid: Option<String> = None;
+                if e.name().local_name().as_ref() == b"Relationship" {
+                    let mut id: Option<String> = None;
                     let mut target: Option<String> = None;
-
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr {
-                            let key_local_name = attr.key.local_name();
-                            let key_bytes = key_local_name.as_ref();
-                            let key = std::str::from_utf8(key_bytes).unwrap_or("");
-                            let value = String::from_utf8_lossy(&attr.value).to_string();
-                            match key {
-                                "Id" => rid = Some(value),
-                                "Target" => target = Some(value),
-                                _ => {}
-                            }
+                    let mut rel_type = String::new();
+
+                    for attr in e.attributes().flatten() {
+                        let value = String::from_utf8_lossy(&attr.value).to_string();
+                        match attr.key.local_name().as_ref() {
+                            b"Id" => id = Some(value),
+                            b"Target" => target = Some(value),
+                            b"Type" => rel_type = value,
+                            _ => {}
                         }
                     }
 
-                    if let (Some(id), Some(t)) = (rid, target) {
-                        let filename = t.rsplit('/').next().unwrap_or(&t).to_string();
-                        rid_to_file.insert(id, filename);
+                    if let (Some(id), Some(target)) = (id, target) {
+                        rels.push(Relationship { id, target, rel_type });
                     }
                 }
             }
@@ -626,87 +660,51 @@ fn parse_cellimages_rels(archive: &mut zip::ZipArchive<BufReader<File>>) -> Resu
         buf.clear();
     }
 
-    eprintln!("[DEBUG] cellimages.xml.rels: {} 个映射", rid_to_file.len());
-    Ok(rid_to_file)
+    rels
 }
 
-/// 关联图片到包含 DISPIMG 公式的单元格
-fn link_images_to_cells(
-    metadata: &mut SheetMetadata,
-    media_files: &HashMap<String, (Vec<u8>, String)>,
-    image_mapping: &ImageMappingInfo,
-) {
-    if media_files.is_empty() {
-        return;
-    }
-
-    // 收集所有带 DISPIMG 公式的单元格
-    let dispimg_cells: Vec<((u32, u32), String)> = metadata.cell_formulas
-        .iter()
-        .filter(|(_, formula)| formula.contains("DISPIMG"))
-        .map(|(k, v)| (*k, v.clone()))
-        .collect();
-
-    if dispimg_cells.is_empty() {
-        return;
-    }
+/// 读取压缩包中的文本文件
+fn read_archive_text(archive: &mut zip::ZipArchive<BufReader<File>>, name: &str) -> Option<String> {
+    let mut file = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    Some(content)
+}
 
-    // 使用映射关系：图片ID -> rId -> 媒体文件名 -> 媒体数据
-    for ((row, col), formula) in &dispimg_cells {
-        if let Some(image_id) = extract_dispimg_id(formula) {
-            if let Some(rid) = image_mapping.id_to_rid.get(&image_id) {
-                if let Some(filename) = image_mapping.rid_to_file.get(rid) {
-                    if let Some((data, ext)) = media_files.get(filename) {
-                        if !data.is_empty() && data.len() >= 8 {
-                            metadata.cell_images.insert((*row, *col), EmbeddedImage {
-                                image_id: image_id.clone(),
-                                data: data.clone(),
-                                extension: ext.to_string(),
-                            });
-                        }
-                    }
-                }
-            }
-        }
+/// 获取 part 对应的 rels 路径（xl/worksheets/sheet1.xml → xl/worksheets/_rels/sheet1.xml.rels）
+fn rels_path_for(part_path: &str) -> String {
+    let dir = parent_dir(part_path);
+    let file_name = part_path.rsplit('/').next().unwrap_or(part_path);
+    if dir.is_empty() {
+        format!("_rels/{}.rels", file_name)
+    } else {
+        format!("{}/_rels/{}.rels", dir, file_name)
     }
+}
 
-    eprintln!("[DEBUG] 关联DISPIMG图片: {} 个", metadata.cell_images.len());
+/// 获取 part 所在目录
+fn parent_dir(part_path: &str) -> &str {
+    part_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
 }
 
-/// 关联浮动图片到单元格（通过 drawing 位置信息）
-fn link_floating_images(
-    metadata: &mut SheetMetadata,
-    media_files: &HashMap<String, (Vec<u8>, String)>,
-    floating_images: &[FloatingImageInfo],
-    rid_to_file: &HashMap<String, String>,
-) {
-    if media_files.is_empty() || floating_images.is_empty() {
-        return;
+/// 将 rels 中的相对 Target 解析为压缩包内的绝对路径
+fn resolve_target(base_dir: &str, target: &str) -> String {
+    // 以 / 开头的是包内绝对路径
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
     }
 
-    let mut linked_count = 0;
-    for img_info in floating_images {
-        // 如果该位置已经有图片（来自 DISPIMG），跳过
-        if metadata.cell_images.contains_key(&(img_info.row, img_info.col)) {
-            continue;
-        }
-
-        // 通过 rId 找到媒体文件名
-        if let Some(filename) = rid_to_file.get(&img_info.rid) {
-            if let Some((data, ext)) = media_files.get(filename) {
-                if !data.is_empty() && data.len() >= 8 {
-                    metadata.cell_images.insert((img_info.row, img_info.col), EmbeddedImage {
-                        image_id: format!("floating_{}_{}_{}", img_info.row, img_info.col, &img_info.rid),
-                        data: data.clone(),
-                        extension: ext.to_string(),
-                    });
-                    linked_count += 1;
-                }
+    let mut parts: Vec<&str> = base_dir.split('/').filter(|p| !p.is_empty()).collect();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
             }
+            _ => parts.push(segment),
         }
     }
-
-    eprintln!("[DEBUG] 关联浮动图片: {} 个", linked_count);
+    parts.join("/")
 }
 
 /// 从 DISPIMG 公式中提取图片ID
@@ -718,157 +716,6 @@ fn extract_dispimg_id(formula: &str) -> Option<String> {
     Some(after_start[..end].to_string())
 }
 
-/// 样式信息（从 styles.xml 解析）
-#[derive(Debug, Default)]
-struct StylesInfo {
-    number_formats: HashMap<u32, String>,     // numFmtId -> format code
-    fills: Vec<Option<String>>,                // 填充颜色列表
-    cell_xfs: Vec<CellXf>,                     // 单元格格式索引
-}
-
-/// 单元格格式定义
-#[derive(Debug, Default, Clone)]
-struct CellXf {
-    num_fmt_id: u32,
-    fill_id: u32,
-    apply_number_format: bool,
-    apply_fill: bool,
-}
-
-/// 解析 styles.xml
-fn parse_styles_xml(xml: &str) -> Result<StylesInfo, CommandError> {
-    let mut reader = Reader::from_str(xml);
-    reader.trim_text(true);
-
-    let mut styles = StylesInfo::default();
-
-    // 添加内置数字格式
-    styles.number_formats.insert(0, "General".to_string());
-    styles.number_formats.insert(1, "0".to_string());
-    styles.number_formats.insert(2, "0.00".to_string());
-    styles.number_formats.insert(49, "@".to_string()); // 文本格式
-
-    let mut in_num_fmts = false;
-    let mut in_fills = false;
-    let mut in_cell_xfs = false;
-    let mut current_fill_color: Option<String> = None;
-
-    let mut buf = Vec::new();
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                match e.name().as_ref() {
-                    b"numFmts" => in_num_fmts = true,
-                    b"numFmt" if in_num_fmts => {
-                        let mut num_fmt_id: Option<u32> = None;
-                        let mut format_code: Option<String> = None;
-
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                match attr.key.as_ref() {
-                                    b"numFmtId" => {
-                                        num_fmt_id = String::from_utf8_lossy(&attr.value).parse().ok();
-                                    }
-                                    b"formatCode" => {
-                                        format_code = Some(String::from_utf8_lossy(&attr.value).to_string());
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-
-                        if let (Some(id), Some(code)) = (num_fmt_id, format_code) {
-                            styles.number_formats.insert(id, code);
-                        }
-                    }
-                    b"fills" => in_fills = true,
-                    b"fill" if in_fills => {
-                        current_fill_color = None;
-                    }
-                    b"patternFill" if in_fills => {
-                        // 检查 patternType
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                if attr.key.as_ref() == b"patternType" {
-                                    let pattern = String::from_utf8_lossy(&attr.value);
-                                    if pattern == "none" {
-                                        current_fill_color = None;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    b"fgColor" if in_fills => {
-                        // 前景色（通常是填充颜色）
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                match attr.key.as_ref() {
-                                    b"rgb" => {
-                                        let color = String::from_utf8_lossy(&attr.value).to_string();
-                                        // ARGB 格式，取后6位作为RGB
-                                        if color.len() >= 6 {
-                                            current_fill_color = Some(color[color.len()-6..].to_string());
-                                        }
-                                    }
-                                    b"theme" | b"indexed" => {
-                                        // 主题色或索引色，暂不支持详细解析
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                    }
-                    b"cellXfs" => in_cell_xfs = true,
-                    b"xf" if in_cell_xfs => {
-                        let mut xf = CellXf::default();
-
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                let value = String::from_utf8_lossy(&attr.value);
-                                match attr.key.as_ref() {
-                                    b"numFmtId" => {
-                                        xf.num_fmt_id = value.parse().unwrap_or(0);
-                                    }
-                                    b"fillId" => {
-                                        xf.fill_id = value.parse().unwrap_or(0);
-                                    }
-                                    b"applyNumberFormat" => {
-                                        xf.apply_number_format = value == "1" || value == "true";
-                                    }
-                                    b"applyFill" => {
-                                        xf.apply_fill = value == "1" || value == "true";
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-
-                        styles.cell_xfs.push(xf);
-                    }
-                    _ => {}
-                }
-            }
-            Ok(Event::End(e)) => {
-                match e.name().as_ref() {
-                    b"numFmts" => in_num_fmts = false,
-                    b"fill" if in_fills => {
-                        styles.fills.push(current_fill_color.take());
-                    }
-                    b"fills" => in_fills = false,
-                    b"cellXfs" => in_cell_xfs = false,
-                    _ => {}
-                }
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(CommandError::new(format!("样式 XML 解析错误: {}", e), "PARSE_ERROR")),
-            _ => {}
-        }
-        buf.clear();
-    }
-
-    Ok(styles)
-}
-
 /// 从 XML 内容中解析工作表元数据
 fn parse_metadata_from_xml(xml: &str, styles: &StylesInfo) -> Result<SheetMetadata, CommandError> {
     let mut reader = Reader::from_str(xml);
@@ -910,16 +757,23 @@ fn parse_metadata_from_xml(xml: &str, styles: &StylesInfo) -> Result<SheetMetada
                         // 解析默认列宽
                         for attr in e.attributes() {
                             if let Ok(attr) = attr {
-                                if attr.key.as_ref() == b"defaultColWidth" {
-                                    let value = String::from_utf8_lossy(&attr.value);
-                                    if let Ok(w) = value.parse::<f64>() {
-                                        metadata.default_column_width = w;
+                                let value = String::from_utf8_lossy(&attr.value);
+                                match attr.key.as_ref() {
+                                    b"defaultColWidth" => {
+                                        if let Ok(w) = value.parse::<f64>() {
+                                            metadata.default_column_width = w;
+                                        }
+                                    }
+                                    b"defaultRowHeight" => {
+                                        metadata.default_row_height = value.parse::<f64>().ok();
                                     }
+                                    _ => {}
                                 }
                             }
                         }
                     }
                     b"sheetData" => in_sheet_data = true,
+                    b"row" if in_sheet_data => parse_row_attributes(&e, &mut metadata),
                     b"c" if in_sheet_data => {
                         // 开始解析单元格
                         in_cell = true;
@@ -967,6 +821,7 @@ fn parse_metadata_from_xml(xml: &str, styles: &StylesInfo) -> Result<SheetMetada
                         let mut min_col: Option<u32> = None;
                         let mut max_col: Option<u32> = None;
                         let mut width: Option<f64> = None;
+                        let mut hidden = false;
 
                         for attr in e.attributes() {
                             if let Ok(attr) = attr {
@@ -976,29 +831,42 @@ fn parse_metadata_from_xml(xml: &str, styles: &StylesInfo) -> Result<SheetMetada
                                     b"min" => min_col = value.parse().ok(),
                                     b"max" => max_col = value.parse().ok(),
                                     b"width" => width = value.parse().ok(),
+                                    b"hidden" => hidden = value == "1" || value == "true",
                                     _ => {}
                                 }
                             }
                         }
 
-                        if let (Some(min), Some(max), Some(w)) = (min_col, max_col, width) {
+                        if let (Some(min), Some(max)) = (min_col, max_col) {
                             for col in min..=max {
-                                metadata.column_widths.insert(col - 1, w);
+                                if let Some(w) = width {
+                                    metadata.column_widths.insert(col - 1, w);
+                                }
+                                if hidden {
+                                    metadata.hidden_columns.insert(col - 1);
+                                }
                             }
                         }
                     }
                     b"sheetFormatPr" => {
                         for attr in e.attributes() {
                             if let Ok(attr) = attr {
-                                if attr.key.as_ref() == b"defaultColWidth" {
-                                    let value = String::from_utf8_lossy(&attr.value);
-                                    if let Ok(w) = value.parse::<f64>() {
-                                        metadata.default_column_width = w;
+                                let value = String::from_utf8_lossy(&attr.value);
+                                match attr.key.as_ref() {
+                                    b"defaultColWidth" => {
+                                        if let Ok(w) = value.parse::<f64>() {
+                                            metadata.default_column_width = w;
+                                        }
+                                    }
+                                    b"defaultRowHeight" => {
+                                        metadata.default_row_height = value.parse::<f64>().ok();
                                     }
+                                    _ => {}
                                 }
                             }
                         }
                     }
+                    b"row" if in_sheet_data => parse_row_attributes(&e, &mut metadata),
                     b"c" if in_sheet_data => {
                         // 空单元格（只有样式没有内容）
                         let mut cell_ref: Option<String> = None;
@@ -1020,10 +888,7 @@ fn parse_metadata_from_xml(xml: &str, styles: &StylesInfo) -> Result<SheetMetada
 
                         if let (Some(ref_str), Some(s_idx)) = (cell_ref, style_idx) {
                             if let Some((row, col)) = parse_cell_reference(&ref_str) {
-                                let cell_style = resolve_cell_style(s_idx, styles);
-                                if cell_style.number_format.is_some() || cell_style.background_color.is_some() {
-                                    metadata.cell_styles.insert((row, col), cell_style);
-                                }
+                                metadata.cell_styles.insert((row, col), styles.resolve_cell_style(s_idx));
                             }
                         }
                     }
@@ -1047,10 +912,7 @@ fn parse_metadata_from_xml(xml: &str, styles: &StylesInfo) -> Result<SheetMetada
                             if let Some((row, col)) = parse_cell_reference(ref_str) {
                                 // 保存样式
                                 if let Some(s_idx) = current_style_idx {
-                                    let cell_style = resolve_cell_style(s_idx, styles);
-                                    if cell_style.number_format.is_some() || cell_style.background_color.is_some() {
-                                        metadata.cell_styles.insert((row, col), cell_style);
-                                    }
+                                    metadata.cell_styles.insert((row, col), styles.resolve_cell_style(s_idx));
                                 }
                                 // 保存公式
                                 if let Some(formula) = current_formula.take() {
@@ -1081,25 +943,30 @@ fn parse_metadata_from_xml(xml: &str, styles: &StylesInfo) -> Result<SheetMetada
     Ok(metadata)
 }
 
-/// 根据样式索引解析单元格样式
-fn resolve_cell_style(style_idx: u32, styles: &StylesInfo) -> CellStyle {
-    let mut cell_style = CellStyle::default();
-
-    if let Some(xf) = styles.cell_xfs.get(style_idx as usize) {
-        // 数字格式
-        if let Some(fmt) = styles.number_formats.get(&xf.num_fmt_id) {
-            cell_style.number_format = Some(fmt.clone());
-        } else if xf.num_fmt_id == 0 {
-            cell_style.number_format = Some("General".to_string());
+/// 解析 <row> 的行高和隐藏属性
+fn parse_row_attributes(e: &quick_xml::events::BytesStart, metadata: &mut SheetMetadata) {
+    let mut row: Option<u32> = None;
+    let mut height: Option<f64> = None;
+    let mut hidden = false;
+
+    for attr in e.attributes().flatten() {
+        let value = String::from_utf8_lossy(&attr.value);
+        match attr.key.as_ref() {
+            b"r" => row = value.parse::<u32>().ok().and_then(|r| r.checked_sub(1)),
+            b"ht" => height = value.parse().ok(),
+            b"hidden" => hidden = value == "1" || value == "true",
+            _ => {}
         }
+    }
 
-        // 填充颜色
-        if let Some(color) = styles.fills.get(xf.fill_id as usize) {
-            cell_style.background_color = color.clone();
+    if let Some(row) = row {
+        if let Some(h) = height {
+            metadata.row_heights.insert(row, h);
+        }
+        if hidden {
+            metadata.hidden_rows.insert(row);
         }
     }
-
-    cell_style
 }
 
 /// 解析单元格范围字符串（例如 "A1:B3"）
@@ -1151,6 +1018,77 @@ mod tests {
         assert_eq!(parse_cell_reference("AA1"), Some((0, 26)));
     }
 
+    #[test]
+    fn test_resolve_target() {
+        assert_eq!(resolve_target("xl", "worksheets/sheet2.xml"), "xl/worksheets/sheet2.xml");
+        assert_eq!(resolve_target("xl", "/xl/worksheets/sheet1.xml"), "xl/worksheets/sheet1.xml");
+        assert_eq!(resolve_target("xl/worksheets", "../drawings/drawing3.xml"), "xl/drawings/drawing3.xml");
+        assert_eq!(rels_path_for("xl/drawings/drawing3.xml"), "xl/drawings/_rels/drawing3.xml.rels");
+    }
+
+    #[test]
+    fn test_list_sheets_and_second_sheet_metadata() {
+        use rust_xlsxwriter::{Format, Workbook};
+
+        let temp_file = tempfile::Builder::new().suffix(".xlsx").tempfile().unwrap();
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().set_name("封面").unwrap();
+        let packing = workbook.add_worksheet().set_name("装箱单").unwrap();
+        packing.merge_range(1, 12, 3, 12, "30", &Format::new()).unwrap();
+        workbook.save(temp_file.path()).unwrap();
+
+        let mut package = XlsxPackage::open(temp_file.path().to_str().unwrap()).unwrap();
+        let sheets = package.sheets().unwrap();
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(sheets[1].name, "装箱单");
+        assert_eq!(sheets[1].path, "xl/worksheets/sheet2.xml");
+
+        let metadata = package.sheet_metadata(&sheets[1], true).unwrap();
+        assert_eq!(metadata.sheet_name, "装箱单");
+        assert_eq!(metadata.merged_ranges.len(), 1);
+        assert_eq!(metadata.merged_ranges[0].start_col, 12);
+    }
+
+    #[test]
+    fn test_images_loaded_only_when_requested() {
+        use rust_xlsxwriter::{Image, Workbook};
+
+        let mut png = Vec::new();
+        image::RgbImage::new(4, 4)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let temp_file = tempfile::Builder::new().suffix(".xlsx").tempfile().unwrap();
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "SKU").unwrap();
+        worksheet.insert_image(1, 3, &Image::new_from_buffer(&png).unwrap()).unwrap();
+        workbook.save(temp_file.path()).unwrap();
+
+        let mut package = XlsxPackage::open(temp_file.path().to_str().unwrap()).unwrap();
+        let sheets = package.sheets().unwrap();
+
+        let metadata = package.sheet_metadata(&sheets[0], false).unwrap();
+        assert!(metadata.cell_images.is_empty());
+        assert!(package.media_cache.is_empty());
+
+        let metadata = package.sheet_metadata(&sheets[0], true).unwrap();
+        assert_eq!(metadata.cell_images.len(), 1);
+        assert!(metadata.cell_images.contains_key(&(1, 3)));
+        assert_eq!(package.media_cache.len(), 1);
+
+        // 使用缓存的工作表同样记录无法处理的图片
+        package
+            .media_cache
+            .insert("xl/media/image9.bin".to_string(), (None, MediaNote::Unsupported("image9.bin".to_string())));
+        for _ in 0..2 {
+            let mut metadata = SheetMetadata::default();
+            assert!(package.load_media("xl/media/image9.bin", &mut metadata).is_none());
+            assert!(package.load_media("xl/media/image9.bin", &mut metadata).is_none());
+            assert_eq!(metadata.unsupported_images, vec!["image9.bin"]);
+        }
+    }
+
     #[test]
     fn test_parse_cell_range() {
         let range = parse_cell_range("A1:B3").unwrap();
@@ -1,8 +1,13 @@
+pub mod columns;
 pub mod commands;
 pub mod config;
+pub mod formula;
+pub mod images;
 pub mod merge_parser;
 pub mod processor;
+pub mod profiles;
 pub mod reader;
+pub mod styles;
 pub mod writer;
 
 pub use commands::*;
@@ -1,55 +1,109 @@
 use crate::commands::error::CommandError;
-use crate::models::excel::{MergedRange, ProcessConfig, ProcessResponse};
-use super::merge_parser::{self, CellStyle, SheetMetadata, EmbeddedImage};
+use crate::commands::output;
+use crate::core::output::OutputRule;
+use crate::models::output::OutputTool;
+use crate::models::excel::{ColumnDistribution, FormulaMode, MergedBlockPreview, MergedRange, ProcessConfig, ProcessResponse, RemainderMode, SheetPreview, SheetSelection, SplitMode, SplitPreview};
+use crate::core::tabular;
+use super::columns;
+use super::formula;
+use super::images::{self, ImageProcessor};
+use super::merge_parser::{self, CellStyle, SheetInfo, SheetMetadata, EmbeddedImage, XlsxPackage};
 use super::reader::{ExcelWorkbook, ExcelSheet};
-use super::writer::{ExcelWriter, CellValue, StyledCellValue, create_format_with_style, create_weight_format, write_cell, set_row_height, set_column_width, embed_image_to_cell};
+use super::writer::{ExcelWriter, CellValue, StyledCellValue, create_format_with_style, create_decimal_format, write_cell, merge_range, set_row_height, set_row_hidden, set_column_width, set_column_hidden, embed_image_to_cell_with_size};
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
-/// 处理 Excel 文件的主函数
+/// Excel 默认行高（磅）
+const DEFAULT_ROW_HEIGHT: f64 = 15.0;
+
+/// 处理 Excel 文件的主函数（输出文件按 rule 命名）
 pub fn process_excel_file(
     file_path: &str,
     config: &ProcessConfig,
+    selection: &SheetSelection,
+    rule: &OutputRule,
 ) -> Result<ProcessResponse, CommandError> {
     let mut logs = Vec::new();
     logs.push(format!("开始处理文件: {}", file_path));
     logs.push(format!("处理类型: {:?}", config.process_type));
 
-    // 1. 打开源文件
-    let mut workbook = ExcelWorkbook::open(file_path)?;
-    logs.push("成功打开 Excel 文件".to_string());
+    // 1. 打开源文件，按请求选择工作表
+    let (mut workbook, mut package, sheets) = open_source(file_path, selection, &mut logs)?;
+    logs.push(format!(
+        "待处理工作表: {}",
+        sheets.iter().map(|s| s.name.as_str()).collect::<Vec<_>>().join(", ")
+    ));
 
-    // 2. 读取第一个工作表
-    let sheet = workbook.get_first_sheet()?;
-    logs.push(format!("读取工作表，共 {} 行 {} 列", sheet.row_count(), sheet.col_count()));
+    let mut writer = ExcelWriter::new()?;
+    let mut image_processor = ImageProcessor::new(&config.images);
+    let mut image_exports = Vec::new();
 
-    // 2.5. 读取工作表元数据（合并单元格、列宽等）
-    let metadata = merge_parser::parse_sheet_metadata(file_path)?;
-    logs.push(format!("检测到 {} 个合并单元格区域", metadata.merged_ranges.len()));
-    if metadata.cell_images.len() > 0 {
-        logs.push(format!("检测到 {} 个图片", metadata.cell_images.len()));
-    }
-    if !metadata.converted_images.is_empty() {
-        logs.push(format!("✓ 自动转换了 {} 个图片格式: {}",
-            metadata.converted_images.len(),
-            metadata.converted_images.join(", ")));
-    }
-    if !metadata.unsupported_images.is_empty() {
-        logs.push(format!("⚠️ 跳过 {} 个无法处理的图片: {}",
-            metadata.unsupported_images.len(),
-            metadata.unsupported_images.join(", ")));
+    for sheet_info in &sheets {
+        logs.push(format!("===== 工作表: {} =====", sheet_info.name));
+
+        let sheet = workbook.get_sheet(&sheet_info.name)?;
+        logs.push(format!("读取工作表，共 {} 行 {} 列", sheet.row_count(), sheet.col_count()));
+
+        // 按表头确定规则对应的列
+        let config = &columns::resolve_columns(&sheet, config, &mut logs)?;
+
+        // 2. 读取工作表元数据（合并单元格、列宽等），仅在需要复制图片时读取图片
+        let mut metadata = read_metadata(package.as_mut(), sheet_info, &sheet, config.copy_images)?;
+        logs.push(format!("检测到 {} 个合并单元格区域", metadata.merged_ranges.len()));
+        if metadata.cell_images.len() > 0 {
+            logs.push(format!("检测到 {} 个图片", metadata.cell_images.len()));
+        }
+        if !metadata.converted_images.is_empty() {
+            logs.push(format!("✓ 自动转换了 {} 个图片格式: {}",
+                metadata.converted_images.len(),
+                metadata.converted_images.join(", ")));
+        }
+        if !metadata.unsupported_images.is_empty() {
+            logs.push(format!("⚠️ 跳过 {} 个无法处理的图片: {}",
+                metadata.unsupported_images.len(),
+                metadata.unsupported_images.join(", ")));
+        }
+
+        // 缩放、压缩图片，需要导出时先收集（不嵌入时从元数据中移除）
+        if config.copy_images {
+            image_processor.apply(&mut metadata);
+            if config.images.export_to_folder {
+                let prefix = (sheets.len() > 1).then_some(sheet_info.name.as_str());
+                image_exports.extend(images::collect_exports(&sheet, &metadata, config, prefix));
+                if !config.images.embed {
+                    metadata.cell_images.clear();
+                }
+            }
+        }
+
+        // 3. 处理数据
+        let processed_data = process_sheet(&sheet, &metadata, config, &mut logs)?;
+        validate_distributions(&sheet, &metadata.merged_ranges, config, &processed_data, &mut logs);
+
+        // 4. 写入拆分表（包含列宽信息）
+        write_processed_data(&mut writer, &sheet_info.name, &processed_data, &metadata, config, &mut logs)?;
     }
 
-    // 3. 处理数据
-    let processed_data = process_sheet(&sheet, &metadata, config, &mut logs)?;
+    // 5. 选定输出文件（输出始终为 xlsx，xls / csv 等输入无法按原格式写出）
+    let output = output::claim_output_with(
+        OutputTool::ExcelSplit,
+        rule,
+        Path::new(file_path),
+        &config.process_type.to_string(),
+    )?;
+    logs.push(format!("输出文件路径: {}", output.path().display()));
 
-    // 4. 生成输出文件路径
-    let output_path = generate_output_path(file_path)?;
-    logs.push(format!("输出文件路径: {}", output_path.display()));
+    logs.push("保存文件...".to_string());
+    writer.save(output.path())?;
+    let output_path = output.keep();
 
-    // 5. 写入新文件（包含列宽信息）
-    write_processed_data(&output_path, &processed_data, &metadata, config, &mut logs)?;
-    logs.push("成功写入处理后的文件".to_string());
+    if let Some(summary) = image_processor.summary() {
+        logs.push(summary);
+    }
+    if !image_exports.is_empty() {
+        export_images(&output_path, image_exports, &mut logs);
+    }
+    logs.push(format!("成功写入处理后的文件（{} 个工作表）", sheets.len()));
 
     Ok(ProcessResponse {
         success: true,
@@ -59,6 +113,173 @@ pub fn process_excel_file(
     })
 }
 
+/// 预览拆分结果：检测表头、合并区域、分配值和图片，汇总异常，不写入文件
+pub fn preview_excel_split(
+    file_path: &str,
+    config: &ProcessConfig,
+    selection: &SheetSelection,
+) -> Result<SplitPreview, CommandError> {
+    let mut logs = Vec::new();
+    let (mut workbook, mut package, sheets) = open_source(file_path, selection, &mut logs)?;
+
+    let mut previews = Vec::new();
+    for sheet_info in &sheets {
+        let sheet = workbook.get_sheet(&sheet_info.name)?;
+        let config = &columns::resolve_columns(&sheet, config, &mut logs)?;
+        let metadata = read_metadata(package.as_mut(), sheet_info, &sheet, config.copy_images)?;
+        previews.push(preview_sheet(&sheet, &metadata, config));
+    }
+
+    Ok(SplitPreview { sheets: previews })
+}
+
+/// 生成单个工作表的预览
+fn preview_sheet(sheet: &ExcelSheet, metadata: &SheetMetadata, config: &ProcessConfig) -> SheetPreview {
+    let mut warnings = Vec::new();
+    let mut blocks = compute_distributions(sheet, &metadata.merged_ranges, config);
+
+    let merged_blocks = metadata
+        .merged_ranges
+        .iter()
+        .map(|range| {
+            let mut distributions: Vec<ColumnDistribution> = blocks
+                .iter_mut()
+                .filter(|b| b.range.start_row == range.start_row && b.range.start_col == range.start_col)
+                .map(|b| {
+                    warnings.append(&mut b.warnings);
+                    ColumnDistribution {
+                        column: b.column,
+                        total: b.total,
+                        weights: std::mem::take(&mut b.weights),
+                        values: b.values.iter().copied().map(Some).collect(),
+                    }
+                })
+                .collect();
+            if !config.keeps_merged(range) {
+                distributions.extend(copied_columns(sheet, range, config));
+            }
+            distributions.sort_by_key(|d| d.column);
+
+            MergedBlockPreview {
+                start_row: range.start_row + 1,
+                end_row: range.end_row + 1,
+                start_col: range.start_col + 1,
+                end_col: range.end_col + 1,
+                keep_merged: config.keeps_merged(range),
+                distributions,
+            }
+        })
+        .collect();
+
+    // 图片：DISPIMG 公式找不到对应图片、图片格式无法处理
+    if config.copy_images {
+        let mut missing: Vec<_> = metadata
+            .cell_formulas
+            .iter()
+            .filter(|(cell, f)| f.contains("DISPIMG") && !metadata.cell_images.contains_key(cell))
+            .map(|((row, col), _)| (*row, *col))
+            .collect();
+        missing.sort_unstable();
+        warnings.extend(missing.into_iter().map(|(row, col)| {
+            format!("第 {} 行第 {} 列: 引用的图片未找到", row + 1, col + 1)
+        }));
+        warnings.extend(
+            metadata.unsupported_images.iter().map(|name| format!("图片 {} 格式无法处理，将被跳过", name)),
+        );
+    }
+
+    SheetPreview {
+        sheet_name: metadata.sheet_name.clone(),
+        header_row: sheet.header_row() + 1,
+        data_rows: data_end_row(sheet),
+        merged_blocks,
+        image_count: metadata.cell_images.len(),
+        warnings,
+    }
+}
+
+/// 打开源文件并按请求选择工作表
+///
+/// xlsx / xlsm 同时打开压缩包（整个处理过程只打开一次）以读取样式、图片等信息；
+/// 其他格式（xls / ods / csv）只有单元格值和合并区域。
+fn open_source(
+    file_path: &str,
+    selection: &SheetSelection,
+    logs: &mut Vec<String>,
+) -> Result<(ExcelWorkbook, Option<XlsxPackage>, Vec<SheetInfo>), CommandError> {
+    let workbook = ExcelWorkbook::open(file_path)?;
+    logs.push("成功打开 Excel 文件".to_string());
+
+    let mut package = if tabular::is_xlsx_package(file_path) {
+        Some(XlsxPackage::open(file_path)?)
+    } else {
+        logs.push("非 xlsx 格式，不包含样式和图片信息".to_string());
+        None
+    };
+    let all_sheets = match package.as_mut() {
+        Some(package) => package.sheets()?,
+        None => workbook
+            .sheet_names()
+            .into_iter()
+            .map(|name| SheetInfo { name, path: String::new() })
+            .collect(),
+    };
+
+    let sheets = select_sheets(all_sheets, selection)?;
+    Ok((workbook, package, sheets))
+}
+
+/// 读取工作表元数据；非 xlsx 格式只有合并区域
+fn read_metadata(
+    package: Option<&mut XlsxPackage>,
+    sheet_info: &SheetInfo,
+    sheet: &ExcelSheet,
+    load_images: bool,
+) -> Result<SheetMetadata, CommandError> {
+    match package {
+        Some(package) => package.sheet_metadata(sheet_info, load_images),
+        None => Ok(SheetMetadata {
+            sheet_name: sheet_info.name.clone(),
+            merged_ranges: sheet.get_merged_ranges(),
+            default_column_width: 8.43,
+            ..Default::default()
+        }),
+    }
+}
+
+/// 导出图片到输出文件旁的 `{输出文件名}_图片` 文件夹；导出失败不影响已生成的拆分表
+fn export_images(output_path: &Path, exports: Vec<images::ImageExport>, logs: &mut Vec<String>) {
+    let stem = output_path.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+    let dir = output_path.with_file_name(format!("{}_图片", stem));
+
+    match images::export_images(&dir, exports) {
+        Ok(stats) => logs.push(format!("导出 {} 张图片到: {}", stats.written, dir.display())),
+        Err(e) => logs.push(format!("⚠️ 导出图片失败: {}", e.message)),
+    }
+}
+
+/// 根据请求选择要处理的工作表
+fn select_sheets(
+    sheets: Vec<SheetInfo>,
+    selection: &SheetSelection,
+) -> Result<Vec<SheetInfo>, CommandError> {
+    match selection {
+        SheetSelection::First => Ok(sheets.into_iter().take(1).collect()),
+        SheetSelection::All => Ok(sheets),
+        SheetSelection::Named(name) => {
+            let available = sheets.iter().map(|s| s.name.clone()).collect::<Vec<_>>().join(", ");
+            sheets
+                .into_iter()
+                .find(|s| &s.name == name)
+                .map(|s| vec![s])
+                .ok_or_else(|| CommandError::new(
+                    format!("找不到工作表 \"{}\"，可用工作表: {}", name, available),
+                    "SHEET_NOT_FOUND",
+                ))
+        }
+    }
+}
+
 /// 处理工作表数据
 fn process_sheet(
     sheet: &ExcelSheet,
@@ -71,13 +292,13 @@ fn process_sheet(
     let col_count = sheet.col_count();
     let merged_ranges = &metadata.merged_ranges;
 
-    // 预计算所有重量列合并区域的分配值
-    let weight_col = config.weight_column as u32 - 1;
-    let weight_distributions = precompute_weight_distributions(sheet, merged_ranges, weight_col, logs);
+    // 预计算所有数值分配列（按比例 / 平均）合并区域的分配值
+    let distributions = precompute_distributions(sheet, merged_ranges, config, logs);
 
+    let data_end = data_end_row(sheet);
     for row_idx in 0..row_count {
-        // 检查第一列是否为空，如果为空则跳过
-        if sheet.is_empty(row_idx, 0) {
+        // 第一列为空时停止处理（表格上方的空行除外）
+        if row_idx >= data_end {
             logs.push(format!("第 {} 行第一列为空，停止处理", row_idx + 1));
             break;
         }
@@ -103,7 +324,7 @@ fn process_sheet(
                     col_idx,
                     merged_range,
                     config,
-                    &weight_distributions,
+                    &distributions,
                     style,
                     formula,
                     image.clone(),
@@ -120,12 +341,12 @@ fn process_sheet(
                     if f.contains("DISPIMG") {
                         row_data.push(StyledCellValue::new(CellValue::Empty, style));
                     } else {
-                        row_data.push(StyledCellValue::new(CellValue::Formula(f), style));
+                        let cell = (row_idx, col_idx);
+                        row_data.push(formula_cell(sheet, &f, cell, cell, config, style, logs));
                     }
                 } else {
                     // 否则使用值
-                    let value = sheet.get_string(row_idx, col_idx);
-                    row_data.push(StyledCellValue::new(CellValue::from_string(value), style));
+                    row_data.push(StyledCellValue::new(sheet.get_value(row_idx, col_idx), style));
                 }
             }
         }
@@ -137,55 +358,244 @@ fn process_sheet(
     Ok(result)
 }
 
-/// 预计算所有重量列合并区域的分配值
-/// 返回 HashMap: (row, col) -> 分配的重量值
-fn precompute_weight_distributions(
+/// 处理范围的结束行（不含）：跳过表格上方的空行后，第一列第一个为空的行
+fn data_end_row(sheet: &ExcelSheet) -> u32 {
+    let row_count = sheet.row_count();
+    let first = (0..row_count).find(|&r| !sheet.is_empty(r, 0)).unwrap_or(0);
+    (first..row_count).find(|&r| sheet.is_empty(r, 0)).unwrap_or(row_count)
+}
+
+/// 一个合并区域在某个数值分配列上的分配结果
+struct BlockDistribution {
+    column: usize,          // 列号（1-based）
+    range: MergedRange,
+    total: Option<f64>,     // 合并单元格的原值，非数字时为 None
+    weights: Vec<f64>,      // 每一行的权重
+    values: Vec<f64>,       // 每一行的分配值
+    warnings: Vec<String>,
+}
+
+/// 计算所有数值分配列合并区域的分配值，并收集异常（非数字的合计或数量、数量合计为 0）
+fn compute_distributions(
+    sheet: &ExcelSheet,
+    merged_ranges: &[MergedRange],
+    config: &ProcessConfig,
+) -> Vec<BlockDistribution> {
+    let mut blocks = Vec::new();
+
+    for rule in config.column_rules.iter().filter(|r| r.mode.is_distribution()) {
+        let col = rule.column as u32 - 1;
+
+        // 找出所有包含该列的合并区域
+        for merged_range in merged_ranges {
+            if !(merged_range.start_col <= col && col <= merged_range.end_col) {
+                continue;
+            }
+
+            let location = format!(
+                "第 {} 列 第 {}-{} 行",
+                rule.column, merged_range.start_row + 1, merged_range.end_row + 1
+            );
+            let mut warnings = Vec::new();
+
+            // 获取合并单元格的总值
+            let total = sheet.get_float(merged_range.start_row, merged_range.start_col);
+            if total.is_none() {
+                if let Some(text) = sheet.get_string(merged_range.start_row, merged_range.start_col) {
+                    warnings.push(format!("{}: 合并值 \"{}\" 不是数字，按 0 处理", location, text));
+                }
+            }
+
+            // 计算每一行的分配权重
+            let weights: Vec<f64> = (merged_range.start_row..=merged_range.end_row)
+                .map(|r| match &rule.mode {
+                    SplitMode::Proportional { by_column, .. } => {
+                        let by_col = *by_column as u32 - 1;
+                        sheet.get_float(r, by_col).unwrap_or_else(|| {
+                            if let Some(text) = sheet.get_string(r, by_col) {
+                                warnings.push(format!(
+                                    "第 {} 行第 {} 列: 数量 \"{}\" 不是数字，按 0 处理",
+                                    r + 1, by_column, text
+                                ));
+                            }
+                            0.0
+                        })
+                    }
+                    _ => 1.0,
+                })
+                .collect();
+
+            let total_value = total.unwrap_or(0.0);
+            if total_value != 0.0 && weights.iter().sum::<f64>() == 0.0 {
+                warnings.push(format!("{}: 数量合计为 0，所有行都分配为 0", location));
+            }
+
+            let values = distribute(total_value, &weights, rule.decimals, rule.remainder);
+            blocks.push(BlockDistribution {
+                column: rule.column,
+                range: merged_range.clone(),
+                total,
+                weights,
+                values,
+                warnings,
+            });
+        }
+    }
+
+    blocks
+}
+
+/// 合并区域内首行保留、复制、清空列的每行输出值（与 process_merged_cell 写入的值一致）
+fn copied_columns(sheet: &ExcelSheet, range: &MergedRange, config: &ProcessConfig) -> Vec<ColumnDistribution> {
+    (range.start_col..=range.end_col)
+        .filter_map(|col| {
+            let rule = config.rule_for_column(col)?;
+            let total = sheet.get_float(range.start_row, col);
+            let rest = match rule.mode {
+                SplitMode::FirstRowOnly => Some(0.0),
+                SplitMode::Copy => total,
+                SplitMode::Clear => None,
+                _ => return None,
+            };
+            let values = (range.start_row..=range.end_row)
+                .map(|row| if row == range.start_row { total } else { rest })
+                .collect();
+            Some(ColumnDistribution { column: rule.column, total, weights: Vec::new(), values })
+        })
+        .collect()
+}
+
+/// 预计算所有数值分配列合并区域的分配值
+/// 返回 HashMap: (row, col) -> 分配的数值
+fn precompute_distributions(
     sheet: &ExcelSheet,
     merged_ranges: &[MergedRange],
-    weight_col: u32,
-    _logs: &mut Vec<String>,
+    config: &ProcessConfig,
+    logs: &mut Vec<String>,
 ) -> HashMap<(u32, u32), f64> {
     let mut distributions = HashMap::new();
 
-    // 找出所有包含重量列的合并区域
-    for merged_range in merged_ranges {
-        if merged_range.start_col <= weight_col && weight_col <= merged_range.end_col {
-            // 获取合并单元格的总重量
-            let total_weight = sheet
-                .get_float(merged_range.start_row, merged_range.start_col)
-                .unwrap_or(0.0);
+    for block in compute_distributions(sheet, merged_ranges, config) {
+        logs.extend(block.warnings.into_iter().map(|w| format!("⚠️ {}", w)));
+        let col = block.column as u32 - 1;
+        for (offset, value) in block.values.into_iter().enumerate() {
+            distributions.insert((block.range.start_row + offset as u32, col), value);
+        }
+    }
+
+    distributions
+}
+
+/// 按权重分配总值，并按规则处理舍入误差
+/// 权重合计为 0 时所有行都分配 0
+fn distribute(total: f64, weights: &[f64], decimals: u32, remainder: RemainderMode) -> Vec<f64> {
+    let weight_sum: f64 = weights.iter().sum();
+    if weight_sum == 0.0 {
+        return vec![0.0; weights.len()];
+    }
 
-            // 计算数量列（重量列的前一列）
-            let quantity_col = weight_col - 1;
+    let factor = 10f64.powi(decimals as i32);
+    let round = |v: f64| (v * factor).round() / factor;
 
-            // 统计所有行的总数量
-            let mut total_quantity = 0.0;
-            for r in merged_range.start_row..=merged_range.end_row {
-                let qty = sheet.get_float(r, quantity_col).unwrap_or(0.0);
-                total_quantity += qty;
+    match remainder {
+        RemainderMode::LargestRemainder => {
+            // 以最小精度单位（如 0.01）为整数计算，先向下取整，
+            // 剩余的单位按小数部分从大到小逐个补给各行，保证合计与原值完全一致
+            let total_units = (total * factor).round() as i64;
+            let shares: Vec<f64> = weights
+                .iter()
+                .map(|w| total_units as f64 * w / weight_sum)
+                .collect();
+            let mut units: Vec<i64> = shares.iter().map(|s| s.floor() as i64).collect();
+            let remaining = total_units - units.iter().sum::<i64>();
+
+            let mut order: Vec<usize> = (0..shares.len()).collect();
+            order.sort_by(|&a, &b| {
+                let frac_a = shares[a] - shares[a].floor();
+                let frac_b = shares[b] - shares[b].floor();
+                frac_b.partial_cmp(&frac_a).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            for &i in order.iter().cycle().take(remaining.max(0) as usize) {
+                units[i] += 1;
             }
 
-            if total_quantity == 0.0 {
-                // 所有行都设为0
-                for r in merged_range.start_row..=merged_range.end_row {
-                    distributions.insert((r, weight_col), 0.0);
-                }
+            units.into_iter().map(|u| u as f64 / factor).collect()
+        }
+        RemainderMode::LastRow => {
+            let mut values: Vec<f64> = weights
+                .iter()
+                .map(|w| round(total * w / weight_sum))
+                .collect();
+            if let Some(last) = values.len().checked_sub(1) {
+                let others: f64 = values[..last].iter().sum();
+                values[last] = round(round(total) - others);
+            }
+            values
+        }
+        RemainderMode::PerRow => weights
+            .iter()
+            .map(|w| round(total * w / weight_sum))
+            .collect(),
+    }
+}
+
+/// 校验数值分配列：逐个合并区域比较拆分前后的合计，并写入日志
+fn validate_distributions(
+    sheet: &ExcelSheet,
+    merged_ranges: &[MergedRange],
+    config: &ProcessConfig,
+    data: &[Vec<StyledCellValue>],
+    logs: &mut Vec<String>,
+) {
+    let mut checked = 0;
+    let mut mismatched = 0;
+
+    for rule in config.column_rules.iter().filter(|r| r.mode.is_distribution()) {
+        let col = rule.column as u32 - 1;
+        let factor = 10f64.powi(rule.decimals as i32);
+
+        for merged_range in merged_ranges {
+            if !(merged_range.start_col <= col && col <= merged_range.end_col) {
                 continue;
             }
 
-            // 计算单位重量
-            let unit_weight = total_weight / total_quantity;
+            let before = sheet
+                .get_float(merged_range.start_row, merged_range.start_col)
+                .unwrap_or(0.0);
+            let after: f64 = (merged_range.start_row..=merged_range.end_row)
+                .filter_map(|r| data.get(r as usize)?.get(col as usize))
+                .map(|cell| match cell.value {
+                    CellValue::Number(v) => v,
+                    CellValue::Integer(v) => v as f64,
+                    _ => 0.0,
+                })
+                .sum();
 
-            // 为每一行计算分配的重量
-            for r in merged_range.start_row..=merged_range.end_row {
-                let current_quantity = sheet.get_float(r, quantity_col).unwrap_or(0.0);
-                let row_weight = (unit_weight * current_quantity * 100.0).round() / 100.0;
-                distributions.insert((r, weight_col), row_weight);
+            checked += 1;
+            let precision = rule.decimals as usize;
+            if (before * factor).round() as i64 == (after * factor).round() as i64 {
+                logs.push(format!(
+                    "✓ 第 {} 列 第 {}-{} 行: 拆分前 {:.*}，拆分后 {:.*}",
+                    rule.column, merged_range.start_row + 1, merged_range.end_row + 1,
+                    precision, before, precision, after
+                ));
+            } else {
+                mismatched += 1;
+                logs.push(format!(
+                    "⚠️ 第 {} 列 第 {}-{} 行合计不一致: 拆分前 {:.*}，拆分后 {:.*}，差额 {:.*}",
+                    rule.column, merged_range.start_row + 1, merged_range.end_row + 1,
+                    precision, before, precision, after, precision, after - before
+                ));
             }
         }
     }
 
-    distributions
+    if checked > 0 {
+        logs.push(format!(
+            "合计校验完成: 共 {} 个合并区域，{} 个不一致",
+            checked, mismatched
+        ));
+    }
 }
 
 /// 查找单元格所在的合并区域
@@ -200,42 +610,57 @@ fn process_merged_cell(
     col_idx: u32,
     merged_range: &MergedRange,
     config: &ProcessConfig,
-    weight_distributions: &HashMap<(u32, u32), f64>,
+    distributions: &HashMap<(u32, u32), f64>,
     current_style: Option<CellStyle>,
     current_formula: Option<String>,
     current_image: Option<EmbeddedImage>,
     metadata: &SheetMetadata,
-    _logs: &mut Vec<String>,
+    logs: &mut Vec<String>,
 ) -> Result<StyledCellValue, CommandError> {
-    let weight_col = config.weight_column as u32 - 1; // 转换为从0开始的索引
-    let box_col = config.box_column as u32 - 1;
-
     // 获取合并区域起始单元格的样式（作为默认样式）
     let merge_start_style = metadata.cell_styles
         .get(&(merged_range.start_row, merged_range.start_col))
         .cloned()
         .or(current_style.clone());
 
-    // 重量列：使用预计算的分配值，并强制使用0.00格式
-    if col_idx == weight_col {
-        if let Some(&weight) = weight_distributions.get(&(row_idx, col_idx)) {
-            return Ok(StyledCellValue::weight(weight, merge_start_style));
-        }
-        // 如果没有预计算值，使用原值
-        let value = sheet.get_float(row_idx, col_idx).unwrap_or(0.0);
-        return Ok(StyledCellValue::weight(value, merge_start_style));
-    }
-
-    // 箱子列：第一行保留原值，其他行为0
-    if col_idx == box_col {
-        let style = metadata.cell_styles.get(&(merged_range.start_row, col_idx)).cloned();
-        if row_idx == merged_range.start_row {
-            // 从合并单元格的原始位置读取箱子数量
-            let value = sheet.get_float(merged_range.start_row, col_idx);
-            return Ok(StyledCellValue::new(CellValue::from_string(value.map(|v| v.to_string())), style));
-        } else {
-            // 其他行为0
-            return Ok(StyledCellValue::new(CellValue::Integer(0), style));
+    let is_first_row = row_idx == merged_range.start_row;
+
+    // 保持合并：只有左上角单元格写入内容（写入时重新合并），其余单元格留空
+    if config.keeps_merged(merged_range)
+        && (row_idx, col_idx) != (merged_range.start_row, merged_range.start_col)
+    {
+        return Ok(StyledCellValue::new(CellValue::Empty, merge_start_style));
+    }
+
+    if let Some(rule) = config.rule_for_column(col_idx) {
+        match &rule.mode {
+            // 数值分配列：使用预计算的分配值，并强制使用固定小数位格式
+            SplitMode::Proportional { .. } | SplitMode::Even => {
+                if let Some(&value) = distributions.get(&(row_idx, col_idx)) {
+                    return Ok(StyledCellValue::distributed(value, rule.decimals, merge_start_style));
+                }
+                // 如果没有预计算值，使用原值
+                let value = sheet.get_float(row_idx, col_idx).unwrap_or(0.0);
+                return Ok(StyledCellValue::distributed(value, rule.decimals, merge_start_style));
+            }
+            // 第一行保留原值，其他行为0
+            SplitMode::FirstRowOnly => {
+                let style = metadata.cell_styles.get(&(merged_range.start_row, col_idx)).cloned();
+                if is_first_row {
+                    // 从合并单元格的原始位置读取数值
+                    let value = sheet.get_float(merged_range.start_row, col_idx);
+                    return Ok(StyledCellValue::new(CellValue::from_string(value.map(|v| v.to_string())), style));
+                } else {
+                    return Ok(StyledCellValue::new(CellValue::Integer(0), style));
+                }
+            }
+            // 第一行保留原值，其他行留空
+            SplitMode::Clear => {
+                if !is_first_row {
+                    return Ok(StyledCellValue::new(CellValue::Empty, merge_start_style));
+                }
+            }
+            SplitMode::Copy | SplitMode::KeepMerged => {}
         }
     }
 
@@ -252,32 +677,75 @@ fn process_merged_cell(
     // 其他列：检查公式（但跳过 DISPIMG）
     if let Some(formula) = current_formula {
         if !formula.contains("DISPIMG") {
-            return Ok(StyledCellValue::new(CellValue::Formula(formula), current_style));
+            let cell = (row_idx, col_idx);
+            return Ok(formula_cell(sheet, &formula, cell, cell, config, current_style, logs));
         }
     }
 
     // 检查合并区域起始单元格是否有公式
+    // 复制到其他行时平移公式的相对引用
     if let Some(start_formula) = metadata.cell_formulas.get(&(merged_range.start_row, merged_range.start_col)) {
         if !start_formula.contains("DISPIMG") {
-            return Ok(StyledCellValue::new(CellValue::Formula(start_formula.clone()), merge_start_style));
+            let source = (merged_range.start_row, merged_range.start_col);
+            return Ok(formula_cell(
+                sheet, start_formula, source, (row_idx, col_idx), config, merge_start_style, logs,
+            ));
         }
     }
 
     // 使用合并单元格的值
-    let value = sheet.get_string(merged_range.start_row, merged_range.start_col);
-    Ok(StyledCellValue::new(CellValue::from_string(value), merge_start_style))
+    let value = sheet.get_value(merged_range.start_row, merged_range.start_col);
+    Ok(StyledCellValue::new(value, merge_start_style))
 }
 
-/// 写入处理后的数据
+/// 生成公式单元格：把 source 处的公式平移到 target，或写入 source 的计算结果
+fn formula_cell(
+    sheet: &ExcelSheet,
+    formula: &str,
+    source: (u32, u32),
+    target: (u32, u32),
+    config: &ProcessConfig,
+    style: Option<CellStyle>,
+    logs: &mut Vec<String>,
+) -> StyledCellValue {
+    // 计算结果取自原表中缓存的值
+    let cached_value = || sheet.get_value(source.0, source.1);
+
+    if config.formula_mode == FormulaMode::Value {
+        return StyledCellValue::new(cached_value(), style);
+    }
+
+    let row_delta = target.0 as i64 - source.0 as i64;
+    let col_delta = target.1 as i64 - source.1 as i64;
+    if let Some(shifted) = formula::shift_formula(formula, row_delta, col_delta) {
+        return StyledCellValue::new(CellValue::Formula(shifted), style);
+    }
+
+    if config.formula_mode == FormulaMode::RebaseOrValue {
+        logs.push(format!(
+            "⚠️ 第 {} 行第 {} 列的公式 {} 无法平移，已写入计算结果",
+            target.0 + 1, target.1 + 1, formula
+        ));
+        StyledCellValue::new(cached_value(), style)
+    } else {
+        logs.push(format!(
+            "⚠️ 第 {} 行第 {} 列的公式 {} 无法平移，保留原公式",
+            target.0 + 1, target.1 + 1, formula
+        ));
+        StyledCellValue::new(CellValue::Formula(formula.to_string()), style)
+    }
+}
+
+/// 将处理后的数据写入一个新的工作表
 fn write_processed_data(
-    output_path: &Path,
+    writer: &mut ExcelWriter,
+    sheet_name: &str,
     data: &[Vec<StyledCellValue>],
     metadata: &merge_parser::SheetMetadata,
-    _config: &ProcessConfig,
+    config: &ProcessConfig,
     logs: &mut Vec<String>,
 ) -> Result<(), CommandError> {
-    let mut writer = ExcelWriter::new()?;
-    let worksheet = writer.add_worksheet("Sheet1")?;
+    let worksheet = writer.add_worksheet(sheet_name)?;
 
     // 统计数据中有多少个单元格包含图片
     let image_count: usize = data.iter()
@@ -292,18 +760,53 @@ fn write_processed_data(
     // 设置列宽
     let col_count = data.first().map(|r| r.len()).unwrap_or(0);
     for col in 0..col_count {
-        let width = metadata.column_widths
-            .get(&(col as u32))
-            .copied()
-            .unwrap_or(metadata.default_column_width);
-        set_column_width(worksheet, col as u16, width)?;
+        set_column_width(worksheet, col as u16, column_width(metadata, col as u32))?;
+        if metadata.hidden_columns.contains(&(col as u32)) {
+            set_column_hidden(worksheet, col as u16)?;
+        }
+    }
+
+    // 默认行高沿用原表
+    if let Some(height) = metadata.default_row_height {
+        worksheet.set_default_row_height(height);
+    }
+
+    // 重新合并保持合并的区域（使用左上角单元格的格式），左上角的值在下面逐格写入时覆盖
+    let kept_ranges = kept_merged_ranges(&metadata.merged_ranges, config, data.len(), col_count);
+    for range in &kept_ranges {
+        let top_left = &data[range.start_row as usize][range.start_col as usize];
+        let format = create_format_with_style(top_left.style.as_ref());
+        merge_range(
+            worksheet,
+            range.start_row,
+            range.start_col as u16,
+            range.end_row,
+            range.end_col as u16,
+            &format,
+        )?;
+    }
+    if !kept_ranges.is_empty() {
+        logs.push(format!("保持 {} 个合并区域", kept_ranges.len()));
     }
 
     for (row_idx, row_data) in data.iter().enumerate() {
-        // 设置行高为 20
-        set_row_height(worksheet, row_idx as u32, 20.0)?;
+        // 拆分表与原表逐行对应，沿用原表的行高和隐藏行
+        if let Some(&height) = metadata.row_heights.get(&(row_idx as u32)) {
+            set_row_height(worksheet, row_idx as u32, height)?;
+        }
+        if metadata.hidden_rows.contains(&(row_idx as u32)) {
+            set_row_hidden(worksheet, row_idx as u32)?;
+        }
 
         for (col_idx, styled_value) in row_data.iter().enumerate() {
+            let (row, col) = (row_idx as u32, col_idx as u32);
+
+            // 保持合并区域内除左上角外的单元格已由 merge_range 写入
+            let kept_range = kept_ranges.iter().find(|range| range.contains(row, col));
+            if kept_range.is_some_and(|range| (row, col) != (range.start_row, range.start_col)) {
+                continue;
+            }
+
             // 如果有图片，嵌入图片
             if let Some(ref image) = styled_value.image {
                 // 验证图片数据
@@ -311,9 +814,28 @@ fn write_processed_data(
                     continue;
                 }
 
+                // 按实际单元格（保持合并时为整个合并区域）尺寸缩放并居中
+                // 列宽按每字符约 7 像素，行高磅转像素
+                let anchor = kept_range.cloned().unwrap_or(MergedRange {
+                    start_row: row,
+                    start_col: col,
+                    end_row: row,
+                    end_col: col,
+                });
+                let cell_width: f64 = (anchor.start_col..=anchor.end_col)
+                    .map(|c| column_width(metadata, c) * 7.0 + 5.0)
+                    .sum();
+                let cell_height: f64 = (anchor.start_row..=anchor.end_row)
+                    .map(|r| row_height(metadata, r) * 96.0 / 72.0)
+                    .sum();
+                let img_size = (cell_width.min(cell_height) - 4.0).max(8.0);
+
                 // 使用 catch_unwind 防止 panic
                 let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    embed_image_to_cell(worksheet, row_idx as u32, col_idx as u16, image)
+                    embed_image_to_cell_with_size(
+                        worksheet, row, col as u16, image,
+                        img_size, cell_width, cell_height,
+                    )
                 }));
 
                 if let Ok(Err(e)) = result {
@@ -322,44 +844,285 @@ fn write_processed_data(
                 continue;
             }
 
-            // 根据是否是重量单元格选择格式
-            let format = if styled_value.is_weight_cell {
-                create_weight_format(styled_value.style.as_ref())
-            } else {
-                create_format_with_style(styled_value.style.as_ref())
+            // 数值拆分的单元格使用固定小数位格式
+            let format = match styled_value.fixed_decimals {
+                Some(decimals) => create_decimal_format(styled_value.style.as_ref(), decimals),
+                None => create_format_with_style(styled_value.style.as_ref()),
             };
 
             write_cell(
                 worksheet,
-                row_idx as u32,
-                col_idx as u16,
+                row,
+                col as u16,
                 &styled_value.value,
                 &format,
             )?;
         }
     }
 
-    logs.push("保存文件...".to_string());
-    writer.save(output_path)?;
+    logs.push(format!("工作表 {} 写入完成", sheet_name));
 
     Ok(())
 }
 
-/// 生成输出文件路径
-fn generate_output_path(input_path: &str) -> Result<PathBuf, CommandError> {
-    let path = Path::new(input_path);
+/// 需要在拆分表中保持合并的区域（截断到已处理的行和列，截断后只剩一个单元格的不再合并）
+fn kept_merged_ranges(
+    merged_ranges: &[MergedRange],
+    config: &ProcessConfig,
+    row_count: usize,
+    col_count: usize,
+) -> Vec<MergedRange> {
+    merged_ranges
+        .iter()
+        .filter(|range| config.keeps_merged(range))
+        .filter(|range| (range.start_row as usize) < row_count && (range.start_col as usize) < col_count)
+        .map(|range| MergedRange {
+            start_row: range.start_row,
+            start_col: range.start_col,
+            end_row: range.end_row.min(row_count as u32 - 1),
+            end_col: range.end_col.min(col_count as u32 - 1),
+        })
+        .filter(|range| range.start_row != range.end_row || range.start_col != range.end_col)
+        .collect()
+}
+
+/// 列宽（字符数）
+fn column_width(metadata: &SheetMetadata, col: u32) -> f64 {
+    metadata.column_widths
+        .get(&col)
+        .copied()
+        .unwrap_or(metadata.default_column_width)
+}
+
+/// 行高（磅）
+fn row_height(metadata: &SheetMetadata, row: u32) -> f64 {
+    metadata.row_heights
+        .get(&row)
+        .copied()
+        .or(metadata.default_row_height)
+        .unwrap_or(DEFAULT_ROW_HEIGHT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::excel::{ImageOptions, ProcessType};
+
+    /// 输出到临时目录的拆分表规则（不读取用户的输出配置）
+    fn output_rule(dir: &tempfile::TempDir) -> OutputRule {
+        OutputRule {
+            output_dir: Some(dir.path().to_string_lossy().to_string()),
+            ..OutputTool::ExcelSplit.default_rule()
+        }
+    }
+
+    #[test]
+    fn test_distribute_proportional_and_even() {
+        assert_eq!(distribute(10.0, &[1.0, 3.0], 2, RemainderMode::PerRow), vec![2.5, 7.5]);
+        assert_eq!(distribute(5.0, &[0.0, 0.0], 2, RemainderMode::PerRow), vec![0.0, 0.0]);
+
+        let values = distribute(10.0, &[1.0, 1.0, 1.0], 2, RemainderMode::LastRow);
+        assert_eq!(values, vec![3.33, 3.33, 3.34]);
+    }
+
+    #[test]
+    fn test_largest_remainder_preserves_total() {
+        // 逐行四舍五入：3.33 + 3.33 + 3.33 = 9.99
+        let per_row = distribute(10.0, &[1.0, 1.0, 1.0], 2, RemainderMode::PerRow);
+        assert_eq!(per_row, vec![3.33, 3.33, 3.33]);
+
+        // 最大余数法：余下的 0.01 给小数部分最大的行（并列时取靠前的行）
+        let values = distribute(10.0, &[1.0, 1.0, 1.0], 2, RemainderMode::LargestRemainder);
+        assert_eq!(values, vec![3.34, 3.33, 3.33]);
+
+        let values = distribute(12.35, &[3.0, 5.0, 7.0, 11.0], 2, RemainderMode::LargestRemainder);
+        let units: i64 = values.iter().map(|v| (v * 100.0).round() as i64).sum();
+        assert_eq!(units, 1235);
+
+        let values = distribute(10.0, &[1.0, 2.0, 4.0], 2, RemainderMode::LargestRemainder);
+        assert_eq!(values, vec![1.43, 2.86, 5.71]);
+    }
+
+    #[test]
+    fn test_legacy_config_maps_to_rules() {
+        let json = r#"{"process_type":"air-freight","weight_column":15,"box_column":13,"copy_images":true}"#;
+        let config: ProcessConfig = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(config.process_type, ProcessType::AirFreight));
+        assert_eq!(
+            config.rule_for_column(14).map(|r| r.mode.clone()),
+            Some(SplitMode::proportional(14))
+        );
+        assert_eq!(config.rule_for_column(12).map(|r| r.mode.clone()), Some(SplitMode::FirstRowOnly));
+        assert!(config.rule_for_column(0).is_none());
+    }
+
+    #[test]
+    fn test_keep_merged_columns_are_re_merged() {
+        use crate::models::excel::ColumnRule;
+        use rust_xlsxwriter::{Format, Workbook};
 
-    let parent = path.parent()
-        .ok_or_else(|| CommandError::new("无法获取文件目录", "FILE_ERROR"))?;
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("装箱单.xlsx");
 
-    let stem = path.file_stem()
-        .and_then(|s| s.to_str())
-        .ok_or_else(|| CommandError::new("无法获取文件名", "FILE_ERROR"))?;
+        // A: 序号，B: 品名（合并），C: 重量（合并），D: 数量
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        for row in 0..3u32 {
+            sheet.write_number(row, 0, (row + 1) as f64).unwrap();
+            sheet.write_number(row, 3, (row + 1) as f64).unwrap();
+        }
+        sheet.merge_range(0, 1, 2, 1, "Widget", &Format::new()).unwrap();
+        sheet.merge_range(0, 2, 2, 2, "", &Format::new()).unwrap();
+        sheet.write_number(0, 2, 9.0).unwrap();
+        workbook.save(&input).unwrap();
+
+        let config = ProcessConfig {
+            process_type: ProcessType::SeaRailNoImage,
+            header_row: None,
+            column_rules: vec![
+                ColumnRule::new(2, SplitMode::KeepMerged),
+                ColumnRule::new(3, SplitMode::proportional(4)),
+            ],
+            formula_mode: FormulaMode::default(),
+            copy_images: false,
+            images: ImageOptions::default(),
+        };
+        let rule = output_rule(&dir);
+        let response = process_excel_file(input.to_str().unwrap(), &config, &SheetSelection::First, &rule).unwrap();
+
+        let mut package = XlsxPackage::open(&response.output_path).unwrap();
+        let sheets = package.sheets().unwrap();
+        let merged = package.sheet_metadata(&sheets[0], false).unwrap().merged_ranges;
+        assert_eq!(merged.len(), 1);
+        assert_eq!((merged[0].start_col, merged[0].start_row, merged[0].end_row), (1, 0, 2));
+
+        let mut output = ExcelWorkbook::open(&response.output_path).unwrap();
+        let sheet = output.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.get_string(0, 1).as_deref(), Some("Widget"));
+        let weights: Vec<f64> = (0..3).map(|r| sheet.get_float(r, 2).unwrap()).collect();
+        assert_eq!(weights, vec![1.5, 3.0, 4.5]);
+    }
+
+    #[test]
+    fn test_merged_formula_is_rebased_per_row() {
+        use rust_xlsxwriter::{Format, Formula, Workbook};
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("公式.xlsx");
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        for row in 0..3u32 {
+            sheet.write_number(row, 0, (row + 1) as f64).unwrap();
+        }
+        sheet.merge_range(0, 1, 2, 1, "", &Format::new()).unwrap();
+        sheet.write_formula(0, 1, Formula::new("=A1*2").set_result("2")).unwrap();
+        sheet.merge_range(0, 2, 2, 2, "", &Format::new()).unwrap();
+        sheet.write_formula(0, 2, Formula::new("=INDIRECT(\"A1\")").set_result("1")).unwrap();
+        workbook.save(&input).unwrap();
+
+        let mut config = ProcessConfig::default_for_type(ProcessType::SeaRailNoImage);
+        config.column_rules.clear();
+        config.formula_mode = FormulaMode::RebaseOrValue;
+        let rule = output_rule(&dir);
+        let response = process_excel_file(input.to_str().unwrap(), &config, &SheetSelection::First, &rule).unwrap();
+        assert!(response.logs.iter().any(|log| log.contains("无法平移")));
+
+        let mut package = XlsxPackage::open(&response.output_path).unwrap();
+        let sheets = package.sheets().unwrap();
+        let metadata = package.sheet_metadata(&sheets[0], false).unwrap();
+        assert_eq!(metadata.cell_formulas.get(&(0, 1)).map(String::as_str), Some("=A1*2"));
+        assert_eq!(metadata.cell_formulas.get(&(2, 1)).map(String::as_str), Some("=A3*2"));
+        // INDIRECT 无法平移：第一行保留公式，其余行写入计算结果
+        assert!(metadata.cell_formulas.contains_key(&(0, 2)));
+        assert!(!metadata.cell_formulas.contains_key(&(1, 2)));
+    }
+
+    #[test]
+    fn test_preview_reports_blocks_and_warnings() {
+        use crate::models::excel::ColumnRule;
+        use rust_xlsxwriter::{Format, Workbook};
 
-    let extension = path.extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("xlsx");
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("装箱单.xlsx");
 
-    let output_name = format!("{}_拆分表.{}", stem, extension);
-    Ok(parent.join(output_name))
+        // A: 序号，B: 重量（合并），C: 数量（第二个区域的数量全部为 0），D: 箱数（合并）
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        for row in 0..4u32 {
+            sheet.write_number(row, 0, (row + 1) as f64).unwrap();
+            sheet.write_number(row, 2, if row < 2 { (row + 1) as f64 } else { 0.0 }).unwrap();
+        }
+        sheet.merge_range(0, 1, 1, 1, "", &Format::new()).unwrap();
+        sheet.write_number(0, 1, 6.0).unwrap();
+        sheet.merge_range(2, 1, 3, 1, "", &Format::new()).unwrap();
+        sheet.write_number(2, 1, 4.0).unwrap();
+        sheet.merge_range(0, 3, 1, 3, "", &Format::new()).unwrap();
+        sheet.write_number(0, 3, 2.0).unwrap();
+        workbook.save(&input).unwrap();
+
+        let config = ProcessConfig {
+            process_type: ProcessType::SeaRailNoImage,
+            header_row: None,
+            column_rules: vec![
+                ColumnRule::new(2, SplitMode::proportional(3)),
+                ColumnRule::new(4, SplitMode::FirstRowOnly),
+            ],
+            formula_mode: FormulaMode::default(),
+            copy_images: false,
+            images: ImageOptions::default(),
+        };
+        let preview = preview_excel_split(input.to_str().unwrap(), &config, &SheetSelection::First).unwrap();
+
+        let sheet = &preview.sheets[0];
+        assert_eq!(sheet.data_rows, 4);
+        assert_eq!(sheet.merged_blocks.len(), 3);
+        let values = |block: usize| &sheet.merged_blocks[block].distributions[0].values;
+        assert_eq!(values(0), &vec![Some(2.0), Some(4.0)]);
+        assert_eq!(values(1), &vec![Some(0.0), Some(0.0)]);
+        // 箱数列：首行为合计，其他行为 0
+        assert_eq!(sheet.merged_blocks[2].start_col, 4);
+        assert_eq!(values(2), &vec![Some(2.0), Some(0.0)]);
+        assert_eq!(sheet.warnings, vec!["第 2 列 第 3-4 行: 数量合计为 0，所有行都分配为 0"]);
+
+        // 预览不生成输出文件
+        assert!(!dir.path().join("装箱单_拆分表.xlsx").exists());
+    }
+
+    #[test]
+    fn test_preset_columns_are_found_by_header() {
+        use rust_xlsxwriter::{Format, Workbook};
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("客户模板.xlsx");
+
+        // 第 1 行为标题，第 2 行为表头；毛重、数量不在预设的列号上
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_string(0, 0, "PACKING LIST").unwrap();
+        for (col, header) in ["序号", "品名", "备注", "QTY", "G.W.(KG)", "CTNS"].iter().enumerate() {
+            sheet.write_string(1, col as u16, *header).unwrap();
+        }
+        for row in 2..4u32 {
+            sheet.write_number(row, 0, (row - 1) as f64).unwrap();
+            sheet.write_number(row, 3, (row - 1) as f64).unwrap();
+        }
+        sheet.merge_range(2, 4, 3, 4, "", &Format::new()).unwrap();
+        sheet.write_number(2, 4, 9.0).unwrap();
+        workbook.save(&input).unwrap();
+
+        let config = ProcessConfig::default_for_type(ProcessType::SeaRailNoImage);
+        let preview = preview_excel_split(input.to_str().unwrap(), &config, &SheetSelection::First).unwrap();
+        let block = &preview.sheets[0].merged_blocks[0];
+        assert_eq!(block.distributions[0].column, 5);
+        assert_eq!(block.distributions[0].values, vec![Some(3.0), Some(6.0)]);
+
+        // 配置的表头不存在时报错
+        let mut config = config;
+        config.column_rules[0].header = Some("净重".to_string());
+        let rule = output_rule(&dir);
+        let err = process_excel_file(input.to_str().unwrap(), &config, &SheetSelection::First, &rule).unwrap_err();
+        assert_eq!(err.code, "COLUMN_NOT_FOUND");
+    }
 }
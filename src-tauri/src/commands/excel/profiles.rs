@@ -0,0 +1,264 @@
+// ============================================================================
+// 处理方案
+// ============================================================================
+//
+// 内置方案即三种处理类型，配置仍保存在 excel_configs.json；
+// 自定义方案保存在 excel_profiles.json，支持新建、复制、重命名、删除和导入导出。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::commands::error::CommandError;
+use crate::models::excel::{ProcessConfig, ProcessProfile, ProcessType, ProfileExport};
+use super::config;
+
+/// 导出文件格式版本
+const EXPORT_VERSION: u32 = 1;
+
+/// 自定义方案存储
+pub struct ProfileStore {
+    path: PathBuf,
+    custom: Vec<ProcessProfile>,
+}
+
+impl ProfileStore {
+    /// 打开配置目录中的方案文件
+    pub fn open() -> Result<Self, CommandError> {
+        let path = config::get_config_path()?.with_file_name("excel_profiles.json");
+        Self::open_at(path)
+    }
+
+    pub fn open_at(path: PathBuf) -> Result<Self, CommandError> {
+        let custom = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| CommandError::new(format!("读取方案文件失败: {}", e), "CONFIG_ERROR"))?;
+            serde_json::from_str(&content)
+                .map_err(|e| CommandError::new(format!("解析方案文件失败: {}", e), "CONFIG_ERROR"))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self { path, custom })
+    }
+
+    fn save(&self) -> Result<(), CommandError> {
+        let content = serde_json::to_string_pretty(&self.custom)
+            .map_err(|e| CommandError::new(format!("序列化方案失败: {}", e), "CONFIG_ERROR"))?;
+        fs::write(&self.path, content)
+            .map_err(|e| CommandError::new(format!("写入方案文件失败: {}", e), "CONFIG_ERROR"))
+    }
+
+    /// 所有方案：内置方案在前，自定义方案按创建顺序
+    pub fn list(&self) -> Result<Vec<ProcessProfile>, CommandError> {
+        let mut profiles = ProcessType::ALL
+            .iter()
+            .map(builtin_profile)
+            .collect::<Result<Vec<_>, _>>()?;
+        profiles.extend(self.custom.iter().cloned());
+        Ok(profiles)
+    }
+
+    pub fn get(&self, id: &str) -> Result<ProcessProfile, CommandError> {
+        if let Some(process_type) = ProcessType::from_string(id) {
+            return builtin_profile(&process_type);
+        }
+        self.custom
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// 基于处理类型的默认配置新建方案
+    pub fn create(&mut self, name: &str, process_type: ProcessType) -> Result<ProcessProfile, CommandError> {
+        let name = self.check_name(name, None)?;
+        self.insert(name, ProcessConfig::default_for_type(process_type))
+    }
+
+    /// 复制已有方案（包括内置方案的当前配置）
+    pub fn clone_profile(&mut self, id: &str, name: &str) -> Result<ProcessProfile, CommandError> {
+        let source = self.get(id)?;
+        let name = self.check_name(name, None)?;
+        self.insert(name, source.config)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<ProcessProfile, CommandError> {
+        let name = self.check_name(name, Some(id))?;
+        let profile = self.custom_mut(id)?;
+        profile.name = name;
+        let profile = profile.clone();
+        self.save()?;
+        Ok(profile)
+    }
+
+    /// 保存方案配置（名称通过 rename 修改）
+    pub fn update(&mut self, id: &str, config: ProcessConfig) -> Result<(), CommandError> {
+        if let Some(process_type) = ProcessType::from_string(id) {
+            // 内置方案的处理类型固定
+            return config::save_config_for_type(&ProcessConfig { process_type, ..config });
+        }
+        self.custom_mut(id)?.config = config;
+        self.save()
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), CommandError> {
+        self.custom_mut(id)?;
+        self.custom.retain(|p| p.id != id);
+        self.save()
+    }
+
+    /// 导出方案到 JSON 文件（ids 为空时导出所有自定义方案）
+    pub fn export(&self, ids: &[String], path: &Path) -> Result<usize, CommandError> {
+        let profiles = if ids.is_empty() {
+            self.custom.clone()
+        } else {
+            ids.iter().map(|id| self.get(id)).collect::<Result<Vec<_>, _>>()?
+        };
+
+        let export = ProfileExport { version: EXPORT_VERSION, profiles };
+        let content = serde_json::to_string_pretty(&export)
+            .map_err(|e| CommandError::new(format!("序列化方案失败: {}", e), "CONFIG_ERROR"))?;
+        fs::write(path, content)?;
+        Ok(export.profiles.len())
+    }
+
+    /// 从 JSON 文件导入方案，全部作为新的自定义方案，重名时自动加序号
+    pub fn import(&mut self, path: &Path) -> Result<Vec<ProcessProfile>, CommandError> {
+        let content = fs::read_to_string(path)?;
+        let export: ProfileExport = serde_json::from_str(&content)
+            .map_err(|e| CommandError::new(format!("无法识别的方案文件: {}", e), "CONFIG_ERROR"))?;
+        if export.version > EXPORT_VERSION {
+            return Err(CommandError::new(
+                format!("方案文件版本 {} 高于当前支持的版本 {}，请升级软件", export.version, EXPORT_VERSION),
+                "CONFIG_ERROR",
+            ));
+        }
+
+        let mut imported = Vec::new();
+        for profile in export.profiles {
+            let name = self.available_name(profile.name.trim());
+            imported.push(self.insert_unsaved(name, profile.config));
+        }
+        self.save()?;
+        Ok(imported)
+    }
+
+    fn insert(&mut self, name: String, config: ProcessConfig) -> Result<ProcessProfile, CommandError> {
+        let profile = self.insert_unsaved(name, config);
+        self.save()?;
+        Ok(profile)
+    }
+
+    fn insert_unsaved(&mut self, name: String, config: ProcessConfig) -> ProcessProfile {
+        let profile = ProcessProfile {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            name,
+            builtin: false,
+            config,
+        };
+        self.custom.push(profile.clone());
+        profile
+    }
+
+    fn custom_mut(&mut self, id: &str) -> Result<&mut ProcessProfile, CommandError> {
+        if ProcessType::from_string(id).is_some() {
+            return Err(CommandError::new("内置方案不能重命名或删除，可以复制后修改", "PROFILE_BUILTIN"));
+        }
+        self.custom
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| not_found(id))
+    }
+
+    fn name_taken(&self, name: &str, except_id: Option<&str>) -> bool {
+        ProcessType::ALL.iter().any(|t| t.label() == name)
+            || self.custom.iter().any(|p| p.name == name && Some(p.id.as_str()) != except_id)
+    }
+
+    fn check_name(&self, name: &str, except_id: Option<&str>) -> Result<String, CommandError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CommandError::new("方案名称不能为空", "INVALID_NAME"));
+        }
+        if self.name_taken(name, except_id) {
+            return Err(CommandError::new(format!("方案 \"{}\" 已存在", name), "PROFILE_EXISTS"));
+        }
+        Ok(name.to_string())
+    }
+
+    fn available_name(&self, name: &str) -> String {
+        let base = if name.is_empty() { "导入的方案" } else { name };
+        let mut candidate = base.to_string();
+        let mut seq = 2;
+        while self.name_taken(&candidate, None) {
+            candidate = format!("{}({})", base, seq);
+            seq += 1;
+        }
+        candidate
+    }
+}
+
+fn builtin_profile(process_type: &ProcessType) -> Result<ProcessProfile, CommandError> {
+    Ok(ProcessProfile {
+        id: process_type.to_string(),
+        name: process_type.label().to_string(),
+        builtin: true,
+        config: config::load_config_for_type(&process_type.to_string())?,
+    })
+}
+
+fn not_found(id: &str) -> CommandError {
+    CommandError::new(format!("找不到处理方案: {}", id), "PROFILE_NOT_FOUND")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_profile_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let mut store = ProfileStore::open_at(path.clone()).unwrap();
+
+        let created = store.create(" 客户A ", ProcessType::AirFreight).unwrap();
+        assert_eq!(created.name, "客户A");
+        assert_eq!(store.create("客户A", ProcessType::AirFreight).unwrap_err().code, "PROFILE_EXISTS");
+        assert_eq!(store.create("空运数据", ProcessType::AirFreight).unwrap_err().code, "PROFILE_EXISTS");
+
+        let copy = store.clone_profile(&created.id, "客户B").unwrap();
+        let mut config = copy.config.clone();
+        config.copy_images = false;
+        store.update(&copy.id, config).unwrap();
+        store.rename(&copy.id, "客户B-无图").unwrap();
+
+        // 重新打开后内容保持
+        let mut store = ProfileStore::open_at(path).unwrap();
+        let copy = store.get(&copy.id).unwrap();
+        assert_eq!(copy.name, "客户B-无图");
+        assert!(!copy.config.copy_images);
+
+        assert_eq!(store.delete("air-freight").unwrap_err().code, "PROFILE_BUILTIN");
+        store.delete(&created.id).unwrap();
+        assert_eq!(store.get(&created.id).unwrap_err().code, "PROFILE_NOT_FOUND");
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = ProfileStore::open_at(dir.path().join("profiles.json")).unwrap();
+        let profile = store.create("客户A", ProcessType::SeaRailNoImage).unwrap();
+
+        let export_path = dir.path().join("export.json");
+        assert_eq!(store.export(&[], &export_path).unwrap(), 1);
+
+        // 导入到同一个存储时重名自动加序号
+        let imported = store.import(&export_path).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name, "客户A(2)");
+        assert_ne!(imported[0].id, profile.id);
+        assert_eq!(imported[0].config.column_rules.len(), profile.config.column_rules.len());
+
+        fs::write(&export_path, r#"{"version":99,"profiles":[]}"#).unwrap();
+        assert!(store.import(&export_path).is_err());
+    }
+}
@@ -0,0 +1,596 @@
+use std::collections::HashMap;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use crate::commands::error::CommandError;
+
+// ============================================================================
+// styles.xml / theme1.xml 解析
+// ============================================================================
+//
+// 颜色统一解析为 RGB 十六进制字符串（如 "FF0000"）：
+//   rgb="FFFF0000"            → 直接取后 6 位
+//   theme="4" tint="0.3999"   → 主题色（theme1.xml）按 tint 调整亮度
+//   indexed="10"              → 索引色（styles.xml 自定义调色板或 Excel 默认调色板）
+
+/// 单元格样式信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellStyle {
+    pub number_format: Option<String>,      // 数字格式（如 "0.00", "General" 等）
+    pub background_color: Option<String>,   // 背景颜色（RGB 十六进制）
+    pub font_color: Option<String>,         // 字体颜色
+    pub is_bold: bool,
+    pub is_italic: bool,
+    pub is_strikethrough: bool,
+    pub underline: Option<String>,          // 下划线类型（single / double / singleAccounting / doubleAccounting）
+    pub font_name: Option<String>,          // 字体名称
+    pub font_size: Option<f64>,             // 字号（磅）
+    pub borders: CellBorders,
+    pub alignment: CellAlignment,
+}
+
+/// 单边边框
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BorderSide {
+    pub style: String,              // 边框样式（thin、medium、dashed 等）
+    pub color: Option<String>,      // 边框颜色
+}
+
+/// 四边边框
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellBorders {
+    pub left: Option<BorderSide>,
+    pub right: Option<BorderSide>,
+    pub top: Option<BorderSide>,
+    pub bottom: Option<BorderSide>,
+}
+
+/// 对齐方式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellAlignment {
+    pub horizontal: Option<String>,  // left / center / right / fill / justify / centerContinuous / distributed
+    pub vertical: Option<String>,    // top / center / bottom / justify / distributed
+    pub wrap_text: bool,
+    pub shrink_to_fit: bool,
+    pub indent: u8,
+    pub rotation: i16,
+}
+
+/// 颜色引用（调色板在 styles.xml 末尾，因此先记录引用，使用时再解析）
+#[derive(Debug, Clone, PartialEq)]
+enum ColorRef {
+    Rgb(String),
+    Theme(usize, f64),
+    Indexed(usize, f64),
+}
+
+/// 字体定义
+#[derive(Debug, Default, Clone)]
+struct FontInfo {
+    name: Option<String>,
+    size: Option<f64>,
+    bold: bool,
+    italic: bool,
+    strikethrough: bool,
+    underline: Option<String>,
+    color: Option<ColorRef>,
+}
+
+/// 边框定义（解析阶段）
+#[derive(Debug, Default, Clone)]
+struct BorderInfo {
+    sides: [Option<(String, Option<ColorRef>)>; 4],  // left, right, top, bottom
+}
+
+/// 单元格格式定义
+#[derive(Debug, Default, Clone)]
+struct CellXf {
+    num_fmt_id: u32,
+    font_id: u32,
+    fill_id: u32,
+    border_id: u32,
+    alignment: CellAlignment,
+}
+
+/// 样式信息（从 styles.xml 解析）
+#[derive(Debug, Default)]
+pub(super) struct StylesInfo {
+    number_formats: HashMap<u32, String>,     // numFmtId -> format code
+    fonts: Vec<FontInfo>,                      // 字体列表
+    fills: Vec<Option<ColorRef>>,              // 填充颜色列表
+    borders: Vec<BorderInfo>,                  // 边框列表
+    cell_xfs: Vec<CellXf>,                     // 单元格格式索引
+    indexed_colors: Vec<String>,               // 自定义索引调色板（为空时使用默认调色板）
+    theme_colors: Vec<String>,                 // 主题色（按 styles.xml 中的 theme 索引顺序）
+}
+
+/// Excel 默认索引调色板（indexed 0-63）
+const DEFAULT_INDEXED_COLORS: [&str; 64] = [
+    "000000", "FFFFFF", "FF0000", "00FF00", "0000FF", "FFFF00", "FF00FF", "00FFFF",
+    "000000", "FFFFFF", "FF0000", "00FF00", "0000FF", "FFFF00", "FF00FF", "00FFFF",
+    "800000", "008000", "000080", "808000", "800080", "008080", "C0C0C0", "808080",
+    "9999FF", "993366", "FFFFCC", "CCFFFF", "660066", "FF8080", "0066CC", "CCCCFF",
+    "000080", "FF00FF", "FFFF00", "00FFFF", "800080", "800000", "008080", "0000FF",
+    "00CCFF", "CCFFFF", "CCFFCC", "FFFF99", "99CCFF", "FF99CC", "CC99FF", "FFCC99",
+    "3366FF", "33CCCC", "99CC00", "FFCC00", "FF9900", "FF6600", "666699", "969696",
+    "003366", "339966", "003300", "333300", "993300", "993366", "333399", "333333",
+];
+
+impl StylesInfo {
+    /// 根据样式索引解析单元格样式
+    pub(super) fn resolve_cell_style(&self, style_idx: u32) -> CellStyle {
+        let mut cell_style = CellStyle::default();
+
+        let xf = match self.cell_xfs.get(style_idx as usize) {
+            Some(xf) => xf,
+            None => return cell_style,
+        };
+
+        // 数字格式
+        if let Some(fmt) = self.number_formats.get(&xf.num_fmt_id) {
+            cell_style.number_format = Some(fmt.clone());
+        } else if xf.num_fmt_id == 0 {
+            cell_style.number_format = Some("General".to_string());
+        }
+
+        // 填充颜色
+        if let Some(Some(color)) = self.fills.get(xf.fill_id as usize) {
+            cell_style.background_color = self.resolve_color(color);
+        }
+
+        // 字体
+        if let Some(font) = self.fonts.get(xf.font_id as usize) {
+            cell_style.font_name = font.name.clone();
+            cell_style.font_size = font.size;
+            cell_style.is_bold = font.bold;
+            cell_style.is_italic = font.italic;
+            cell_style.is_strikethrough = font.strikethrough;
+            cell_style.underline = font.underline.clone();
+            cell_style.font_color = font.color.as_ref().and_then(|c| self.resolve_color(c));
+        }
+
+        // 边框
+        if let Some(border) = self.borders.get(xf.border_id as usize) {
+            let [left, right, top, bottom] = border.sides.clone().map(|side| {
+                side.map(|(style, color)| BorderSide {
+                    style,
+                    color: color.as_ref().and_then(|c| self.resolve_color(c)),
+                })
+            });
+            cell_style.borders = CellBorders { left, right, top, bottom };
+        }
+
+        cell_style.alignment = xf.alignment.clone();
+
+        cell_style
+    }
+
+    /// 将颜色引用解析为 RGB 十六进制字符串
+    fn resolve_color(&self, color: &ColorRef) -> Option<String> {
+        match color {
+            ColorRef::Rgb(rgb) => Some(rgb.clone()),
+            ColorRef::Theme(idx, tint) => {
+                let base = self.theme_colors.get(*idx)?;
+                Some(apply_tint(base, *tint))
+            }
+            ColorRef::Indexed(idx, tint) => {
+                let base = if self.indexed_colors.is_empty() {
+                    DEFAULT_INDEXED_COLORS.get(*idx).map(|c| c.to_string())
+                } else {
+                    self.indexed_colors.get(*idx).cloned()
+                }?;
+                Some(apply_tint(&base, *tint))
+            }
+        }
+    }
+}
+
+/// 解析 styles.xml
+///
+/// theme_colors 来自 theme1.xml（见 `parse_theme_colors`），用于解析 theme 颜色。
+pub(super) fn parse_styles_xml(xml: &str, theme_colors: Vec<String>) -> Result<StylesInfo, CommandError> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut styles = StylesInfo {
+        theme_colors,
+        ..StylesInfo::default()
+    };
+
+    // 添加内置数字格式
+    styles.number_formats.insert(0, "General".to_string());
+    styles.number_formats.insert(1, "0".to_string());
+    styles.number_formats.insert(2, "0.00".to_string());
+    styles.number_formats.insert(3, "#,##0".to_string());
+    styles.number_formats.insert(4, "#,##0.00".to_string());
+    styles.number_formats.insert(9, "0%".to_string());
+    styles.number_formats.insert(10, "0.00%".to_string());
+    styles.number_formats.insert(14, "yyyy/m/d".to_string());
+    styles.number_formats.insert(49, "@".to_string()); // 文本格式
+
+    let mut in_num_fmts = false;
+    let mut in_fonts = false;
+    let mut in_fills = false;
+    let mut in_borders = false;
+    let mut in_cell_xfs = false;
+    let mut in_indexed_colors = false;
+    let mut current_font = FontInfo::default();
+    let mut current_fill_color: Option<ColorRef> = None;
+    let mut current_border = BorderInfo::default();
+    let mut current_border_side: Option<usize> = None;
+
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf);
+        let is_empty = matches!(event, Ok(Event::Empty(_)));
+        match event {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                match e.name().as_ref() {
+                    b"numFmts" => in_num_fmts = true,
+                    b"numFmt" if in_num_fmts => {
+                        let id = attr_value(&e, b"numFmtId").and_then(|v| v.parse().ok());
+                        let code = attr_value(&e, b"formatCode");
+                        if let (Some(id), Some(code)) = (id, code) {
+                            styles.number_formats.insert(id, code);
+                        }
+                    }
+
+                    b"fonts" => in_fonts = true,
+                    b"font" if in_fonts => {
+                        current_font = FontInfo::default();
+                        if is_empty {
+                            styles.fonts.push(current_font.clone());
+                        }
+                    }
+                    b"name" if in_fonts => current_font.name = attr_value(&e, b"val"),
+                    b"sz" if in_fonts => {
+                        current_font.size = attr_value(&e, b"val").and_then(|v| v.parse().ok());
+                    }
+                    b"b" if in_fonts => current_font.bold = attr_flag(&e),
+                    b"i" if in_fonts => current_font.italic = attr_flag(&e),
+                    b"strike" if in_fonts => current_font.strikethrough = attr_flag(&e),
+                    b"u" if in_fonts => {
+                        let kind = attr_value(&e, b"val").unwrap_or_else(|| "single".to_string());
+                        current_font.underline = if kind == "none" { None } else { Some(kind) };
+                    }
+                    b"color" if in_fonts => current_font.color = parse_color_ref(&e),
+
+                    b"fills" => in_fills = true,
+                    b"fill" if in_fills => {
+                        current_fill_color = None;
+                        if is_empty {
+                            styles.fills.push(None);
+                        }
+                    }
+                    b"fgColor" if in_fills => {
+                        // 前景色（通常是填充颜色）
+                        current_fill_color = parse_color_ref(&e);
+                    }
+
+                    b"borders" => in_borders = true,
+                    b"border" if in_borders => {
+                        current_border = BorderInfo::default();
+                        if is_empty {
+                            styles.borders.push(BorderInfo::default());
+                        }
+                    }
+                    side @ (b"left" | b"right" | b"top" | b"bottom" | b"start" | b"end") if in_borders => {
+                        let idx = match side {
+                            b"left" | b"start" => 0,
+                            b"right" | b"end" => 1,
+                            b"top" => 2,
+                            _ => 3,
+                        };
+                        current_border_side = None;
+                        if let Some(style) = attr_value(&e, b"style").filter(|s| s != "none") {
+                            current_border.sides[idx] = Some((style, None));
+                            if !is_empty {
+                                current_border_side = Some(idx);
+                            }
+                        }
+                    }
+                    b"color" if in_borders => {
+                        if let Some(idx) = current_border_side {
+                            if let Some(side) = current_border.sides[idx].as_mut() {
+                                side.1 = parse_color_ref(&e);
+                            }
+                        }
+                    }
+
+                    b"cellXfs" => in_cell_xfs = true,
+                    b"xf" if in_cell_xfs => {
+                        let id = |key: &[u8]| {
+                            attr_value(&e, key).and_then(|v| v.parse().ok()).unwrap_or(0)
+                        };
+                        styles.cell_xfs.push(CellXf {
+                            num_fmt_id: id(b"numFmtId"),
+                            font_id: id(b"fontId"),
+                            fill_id: id(b"fillId"),
+                            border_id: id(b"borderId"),
+                            alignment: CellAlignment::default(),
+                        });
+                    }
+                    b"alignment" if in_cell_xfs => {
+                        if let Some(xf) = styles.cell_xfs.last_mut() {
+                            xf.alignment = CellAlignment {
+                                horizontal: attr_value(&e, b"horizontal"),
+                                vertical: attr_value(&e, b"vertical"),
+                                wrap_text: attr_value(&e, b"wrapText").is_some_and(|v| v == "1" || v == "true"),
+                                shrink_to_fit: attr_value(&e, b"shrinkToFit").is_some_and(|v| v == "1" || v == "true"),
+                                indent: attr_value(&e, b"indent").and_then(|v| v.parse().ok()).unwrap_or(0),
+                                rotation: attr_value(&e, b"textRotation").and_then(|v| v.parse().ok()).unwrap_or(0),
+                            };
+                        }
+                    }
+
+                    b"indexedColors" => in_indexed_colors = true,
+                    b"rgbColor" if in_indexed_colors => {
+                        if let Some(rgb) = attr_value(&e, b"rgb") {
+                            styles.indexed_colors.push(rgb_suffix(&rgb));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(e)) => {
+                match e.name().as_ref() {
+                    b"numFmts" => in_num_fmts = false,
+                    b"font" if in_fonts => styles.fonts.push(std::mem::take(&mut current_font)),
+                    b"fonts" => in_fonts = false,
+                    b"fill" if in_fills => styles.fills.push(current_fill_color.take()),
+                    b"fills" => in_fills = false,
+                    b"left" | b"right" | b"top" | b"bottom" | b"start" | b"end" => current_border_side = None,
+                    b"border" if in_borders => styles.borders.push(std::mem::take(&mut current_border)),
+                    b"borders" => in_borders = false,
+                    b"cellXfs" => in_cell_xfs = false,
+                    b"indexedColors" => in_indexed_colors = false,
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(CommandError::new(format!("样式 XML 解析错误: {}", e), "PARSE_ERROR")),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(styles)
+}
+
+/// 解析 theme1.xml 的配色方案
+///
+/// clrScheme 中的顺序为 dk1, lt1, dk2, lt2, accent1-6, hlink, folHlink，
+/// 而 styles.xml 的 theme 索引中 0/1、2/3 是互换的（0 = lt1, 1 = dk1）。
+pub(super) fn parse_theme_colors(xml: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut colors = Vec::new();
+    let mut in_clr_scheme = false;
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                match e.name().local_name().as_ref() {
+                    b"clrScheme" => in_clr_scheme = true,
+                    b"srgbClr" if in_clr_scheme => {
+                        if let Some(val) = attr_value(&e, b"val") {
+                            colors.push(val);
+                        }
+                    }
+                    b"sysClr" if in_clr_scheme => {
+                        if let Some(val) = attr_value(&e, b"lastClr") {
+                            colors.push(val);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(e)) if e.name().local_name().as_ref() == b"clrScheme" => break,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if colors.len() >= 4 {
+        colors.swap(0, 1);
+        colors.swap(2, 3);
+    }
+
+    colors
+}
+
+/// 读取颜色元素（fgColor / color）的 rgb、theme、indexed 和 tint 属性
+fn parse_color_ref(e: &BytesStart) -> Option<ColorRef> {
+    let tint = attr_value(e, b"tint").and_then(|v| v.parse().ok()).unwrap_or(0.0);
+
+    if let Some(rgb) = attr_value(e, b"rgb") {
+        if rgb.len() >= 6 {
+            return Some(ColorRef::Rgb(rgb_suffix(&rgb)));
+        }
+    }
+    if let Some(theme) = attr_value(e, b"theme").and_then(|v| v.parse().ok()) {
+        return Some(ColorRef::Theme(theme, tint));
+    }
+    // indexed 64 为系统前景色（自动），不输出颜色
+    if let Some(indexed) = attr_value(e, b"indexed").and_then(|v| v.parse().ok()) {
+        if indexed < 64 {
+            return Some(ColorRef::Indexed(indexed, tint));
+        }
+    }
+    None
+}
+
+/// ARGB 取后 6 位作为 RGB
+fn rgb_suffix(argb: &str) -> String {
+    argb[argb.len().saturating_sub(6)..].to_uppercase()
+}
+
+/// 按 tint 调整颜色亮度（Excel 在 HSL 空间中调整亮度）
+fn apply_tint(rgb: &str, tint: f64) -> String {
+    if tint == 0.0 || rgb.len() < 6 {
+        return rgb.to_uppercase();
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&rgb[i..i + 2], 16).unwrap_or(0) as f64 / 255.0;
+    let (h, s, l) = rgb_to_hsl(channel(0), channel(2), channel(4));
+
+    let l = if tint < 0.0 {
+        l * (1.0 + tint)
+    } else {
+        l * (1.0 - tint) + tint
+    };
+
+    let (r, g, b) = hsl_to_rgb(h, s, l.clamp(0.0, 1.0));
+    format!(
+        "{:02X}{:02X}{:02X}",
+        (r * 255.0).round() as u8,
+        (g * 255.0).round() as u8,
+        (b * 255.0).round() as u8
+    )
+}
+
+fn rgb_to_hsl(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+
+    if max == min {
+        return (0.0, 0.0, l);
+    }
+
+    let d = max - min;
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+    let h = if max == r {
+        (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+
+    (h / 6.0, s, l)
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    if s == 0.0 {
+        return (l, l, l);
+    }
+
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let hue = |mut t: f64| {
+        if t < 0.0 {
+            t += 1.0;
+        }
+        if t > 1.0 {
+            t -= 1.0;
+        }
+        if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        }
+    };
+
+    (hue(h + 1.0 / 3.0), hue(h), hue(h - 1.0 / 3.0))
+}
+
+/// 读取属性值
+fn attr_value(e: &BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == key)
+        .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+}
+
+/// 读取开关属性（如 <b/>、<b val="0"/>），无 val 时视为开启
+fn attr_flag(e: &BytesStart) -> bool {
+    attr_value(e, b"val").is_none_or(|v| v != "0" && v != "false")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <fonts count="2">
+    <font><sz val="11"/><name val="宋体"/></font>
+    <font><b/><i/><u val="double"/><sz val="14"/><color theme="4" tint="-0.5"/><name val="Arial"/></font>
+  </fonts>
+  <fills count="3">
+    <fill><patternFill patternType="none"/></fill>
+    <fill><patternFill patternType="gray125"/></fill>
+    <fill><patternFill patternType="solid"><fgColor indexed="10"/></patternFill></fill>
+  </fills>
+  <borders count="2">
+    <border><left/><right/><top/><bottom/><diagonal/></border>
+    <border><left style="thin"><color rgb="FF00FF00"/></left><right/><top/><bottom style="double"/></border>
+  </borders>
+  <cellXfs count="2">
+    <xf numFmtId="0" fontId="0" fillId="0" borderId="0"/>
+    <xf numFmtId="4" fontId="1" fillId="2" borderId="1" applyAlignment="1">
+      <alignment horizontal="right" vertical="top" wrapText="1" indent="2"/>
+    </xf>
+  </cellXfs>
+</styleSheet>"#;
+
+    #[test]
+    fn test_resolve_full_style() {
+        let theme = vec![
+            "FFFFFF".to_string(), "000000".to_string(), "EEECE1".to_string(), "1F497D".to_string(),
+            "4F81BD".to_string(),
+        ];
+        let styles = parse_styles_xml(STYLES_XML, theme).unwrap();
+
+        let plain = styles.resolve_cell_style(0);
+        assert_eq!(plain.font_name.as_deref(), Some("宋体"));
+        assert_eq!(plain.font_size, Some(11.0));
+        assert!(plain.background_color.is_none());
+        assert_eq!(plain.borders, CellBorders::default());
+
+        let styled = styles.resolve_cell_style(1);
+        assert_eq!(styled.number_format.as_deref(), Some("#,##0.00"));
+        assert_eq!(styled.font_name.as_deref(), Some("Arial"));
+        assert!(styled.is_bold && styled.is_italic);
+        assert_eq!(styled.underline.as_deref(), Some("double"));
+        assert_eq!(styled.font_color.as_deref(), Some("254061"));
+        assert_eq!(styled.background_color.as_deref(), Some("FF0000"));
+        assert_eq!(
+            styled.borders.left,
+            Some(BorderSide { style: "thin".to_string(), color: Some("00FF00".to_string()) })
+        );
+        assert_eq!(styled.borders.bottom.as_ref().map(|b| b.style.as_str()), Some("double"));
+        assert!(styled.borders.top.is_none());
+        assert_eq!(styled.alignment.horizontal.as_deref(), Some("right"));
+        assert!(styled.alignment.wrap_text);
+        assert_eq!(styled.alignment.indent, 2);
+    }
+
+    #[test]
+    fn test_parse_theme_colors_swaps_light_and_dark() {
+        let xml = r#"<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><a:themeElements><a:clrScheme name="Office">
+            <a:dk1><a:sysClr val="windowText" lastClr="000000"/></a:dk1>
+            <a:lt1><a:sysClr val="window" lastClr="FFFFFF"/></a:lt1>
+            <a:dk2><a:srgbClr val="1F497D"/></a:dk2>
+            <a:lt2><a:srgbClr val="EEECE1"/></a:lt2>
+            <a:accent1><a:srgbClr val="4F81BD"/></a:accent1>
+        </a:clrScheme></a:themeElements></a:theme>"#;
+
+        let colors = parse_theme_colors(xml);
+        assert_eq!(colors, vec!["FFFFFF", "000000", "EEECE1", "1F497D", "4F81BD"]);
+    }
+
+    #[test]
+    fn test_apply_tint() {
+        assert_eq!(apply_tint("4F81BD", 0.0), "4F81BD");
+        assert_eq!(apply_tint("000000", 0.5), "808080");
+        assert_eq!(apply_tint("FFFFFF", -0.5), "808080");
+    }
+}
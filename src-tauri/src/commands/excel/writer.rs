@@ -1,4 +1,4 @@
-use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Image, Workbook, Worksheet};
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, FormatUnderline, Image, Workbook, Worksheet};
 use std::path::Path;
 use crate::commands::error::CommandError;
 use super::merge_parser::{CellStyle, EmbeddedImage};
@@ -45,52 +45,175 @@ pub fn create_default_format() -> Format {
 }
 
 /// 根据原始样式创建格式
+///
+/// 没有样式信息的单元格在原表中就是默认格式，因此输出也使用默认格式。
 pub fn create_format_with_style(style: Option<&CellStyle>) -> Format {
-    let mut format = Format::new()
-        .set_align(FormatAlign::Center)
-        .set_align(FormatAlign::VerticalCenter)
-        .set_text_wrap()
-        .set_border(FormatBorder::Thin);
-
-    if let Some(cell_style) = style {
-        // 应用数字格式
-        if let Some(ref num_fmt) = cell_style.number_format {
-            // 只有非 General 格式才设置
-            if num_fmt != "General" {
-                format = format.set_num_format(num_fmt);
-            }
+    let cell_style = match style {
+        Some(cell_style) => cell_style,
+        None => return Format::new(),
+    };
+
+    let mut format = Format::new();
+
+    // 数字格式（只有非 General 格式才设置）
+    if let Some(ref num_fmt) = cell_style.number_format {
+        if num_fmt != "General" {
+            format = format.set_num_format(num_fmt);
         }
+    }
 
-        // 应用背景颜色
-        if let Some(ref bg_color) = cell_style.background_color {
-            if let Some(color) = parse_color(bg_color) {
-                format = format.set_background_color(color);
-            }
+    // 背景颜色
+    if let Some(color) = cell_style.background_color.as_deref().and_then(parse_color) {
+        format = format.set_background_color(color);
+    }
+
+    // 字体
+    if let Some(ref name) = cell_style.font_name {
+        format = format.set_font_name(name);
+    }
+    if let Some(size) = cell_style.font_size {
+        format = format.set_font_size(size);
+    }
+    if let Some(color) = cell_style.font_color.as_deref().and_then(parse_color) {
+        format = format.set_font_color(color);
+    }
+    if cell_style.is_bold {
+        format = format.set_bold();
+    }
+    if cell_style.is_italic {
+        format = format.set_italic();
+    }
+    if cell_style.is_strikethrough {
+        format = format.set_font_strikethrough();
+    }
+    if let Some(ref underline) = cell_style.underline {
+        format = format.set_underline(match underline.as_str() {
+            "double" => FormatUnderline::Double,
+            "singleAccounting" => FormatUnderline::SingleAccounting,
+            "doubleAccounting" => FormatUnderline::DoubleAccounting,
+            _ => FormatUnderline::Single,
+        });
+    }
+
+    // 边框（逐边）
+    let borders = &cell_style.borders;
+    if let Some(ref side) = borders.left {
+        format = format.set_border_left(border_style(&side.style));
+        if let Some(color) = side.color.as_deref().and_then(parse_color) {
+            format = format.set_border_left_color(color);
+        }
+    }
+    if let Some(ref side) = borders.right {
+        format = format.set_border_right(border_style(&side.style));
+        if let Some(color) = side.color.as_deref().and_then(parse_color) {
+            format = format.set_border_right_color(color);
+        }
+    }
+    if let Some(ref side) = borders.top {
+        format = format.set_border_top(border_style(&side.style));
+        if let Some(color) = side.color.as_deref().and_then(parse_color) {
+            format = format.set_border_top_color(color);
+        }
+    }
+    if let Some(ref side) = borders.bottom {
+        format = format.set_border_bottom(border_style(&side.style));
+        if let Some(color) = side.color.as_deref().and_then(parse_color) {
+            format = format.set_border_bottom_color(color);
         }
     }
 
+    // 对齐
+    let alignment = &cell_style.alignment;
+    if let Some(align) = alignment.horizontal.as_deref().and_then(horizontal_align) {
+        format = format.set_align(align);
+    }
+    if let Some(align) = alignment.vertical.as_deref().and_then(vertical_align) {
+        format = format.set_align(align);
+    }
+    if alignment.wrap_text {
+        format = format.set_text_wrap();
+    }
+    if alignment.shrink_to_fit {
+        format = format.set_shrink();
+    }
+    if alignment.indent > 0 {
+        format = format.set_indent(alignment.indent);
+    }
+    if alignment.rotation != 0 {
+        format = format.set_rotation(text_rotation(alignment.rotation));
+    }
+
     format
 }
 
-/// 创建用于重量拆分的数字格式（保留两位小数）
-pub fn create_weight_format(style: Option<&CellStyle>) -> Format {
-    let mut format = Format::new()
-        .set_align(FormatAlign::Center)
-        .set_align(FormatAlign::VerticalCenter)
-        .set_text_wrap()
-        .set_border(FormatBorder::Thin)
-        .set_num_format("0.00");
+/// 创建用于数值拆分的数字格式（保留原样式，按规则覆盖小数位，如 2 位为 "0.00"）
+pub fn create_decimal_format(style: Option<&CellStyle>, decimals: u32) -> Format {
+    create_format_with_style(style).set_num_format(decimal_num_format(decimals))
+}
 
-    // 保留背景颜色
-    if let Some(cell_style) = style {
-        if let Some(ref bg_color) = cell_style.background_color {
-            if let Some(color) = parse_color(bg_color) {
-                format = format.set_background_color(color);
-            }
-        }
+/// 将 styles.xml 的边框样式名映射为 FormatBorder
+fn border_style(style: &str) -> FormatBorder {
+    match style {
+        "medium" => FormatBorder::Medium,
+        "dashed" => FormatBorder::Dashed,
+        "dotted" => FormatBorder::Dotted,
+        "thick" => FormatBorder::Thick,
+        "double" => FormatBorder::Double,
+        "hair" => FormatBorder::Hair,
+        "mediumDashed" => FormatBorder::MediumDashed,
+        "dashDot" => FormatBorder::DashDot,
+        "mediumDashDot" => FormatBorder::MediumDashDot,
+        "dashDotDot" => FormatBorder::DashDotDot,
+        "mediumDashDotDot" => FormatBorder::MediumDashDotDot,
+        "slantDashDot" => FormatBorder::SlantDashDot,
+        _ => FormatBorder::Thin,
     }
+}
 
-    format
+/// 水平对齐
+fn horizontal_align(value: &str) -> Option<FormatAlign> {
+    match value {
+        "left" => Some(FormatAlign::Left),
+        "center" => Some(FormatAlign::Center),
+        "right" => Some(FormatAlign::Right),
+        "fill" => Some(FormatAlign::Fill),
+        "justify" => Some(FormatAlign::Justify),
+        "centerContinuous" => Some(FormatAlign::CenterAcross),
+        "distributed" => Some(FormatAlign::Distributed),
+        _ => None,
+    }
+}
+
+/// 垂直对齐
+fn vertical_align(value: &str) -> Option<FormatAlign> {
+    match value {
+        "top" => Some(FormatAlign::Top),
+        "center" => Some(FormatAlign::VerticalCenter),
+        "bottom" => Some(FormatAlign::Bottom),
+        "justify" => Some(FormatAlign::VerticalJustify),
+        "distributed" => Some(FormatAlign::VerticalDistributed),
+        _ => None,
+    }
+}
+
+/// 将 styles.xml 的 textRotation 转为 set_rotation 接受的角度
+///
+/// OOXML 中 91-180 表示向下旋转 1-90 度，255 表示竖排文字；set_rotation 只接受 -90..=90 和 270。
+fn text_rotation(value: i16) -> i16 {
+    match value {
+        91..=180 => -(value - 90),
+        255 => 270,
+        _ => value,
+    }
+}
+
+/// 生成固定小数位的数字格式字符串
+fn decimal_num_format(decimals: u32) -> String {
+    if decimals == 0 {
+        "0".to_string()
+    } else {
+        format!("0.{}", "0".repeat(decimals as usize))
+    }
 }
 
 /// 解析十六进制颜色字符串为 Color
@@ -138,20 +261,20 @@ pub enum CellValue {
 pub struct StyledCellValue {
     pub value: CellValue,
     pub style: Option<CellStyle>,
-    pub is_weight_cell: bool,  // 是否是重量拆分的单元格（需要强制使用0.00格式）
+    pub fixed_decimals: Option<u32>,  // 数值拆分的单元格（需要强制使用固定小数位格式）
     pub image: Option<EmbeddedImage>,  // 嵌入的图片
 }
 
 impl StyledCellValue {
     pub fn new(value: CellValue, style: Option<CellStyle>) -> Self {
-        Self { value, style, is_weight_cell: false, image: None }
+        Self { value, style, fixed_decimals: None, image: None }
     }
 
-    pub fn weight(value: f64, style: Option<CellStyle>) -> Self {
+    pub fn distributed(value: f64, decimals: u32, style: Option<CellStyle>) -> Self {
         Self {
             value: CellValue::Number(value),
             style,
-            is_weight_cell: true,
+            fixed_decimals: Some(decimals),
             image: None,
         }
     }
@@ -160,7 +283,7 @@ impl StyledCellValue {
         Self {
             value,
             style,
-            is_weight_cell: false,
+            fixed_decimals: None,
             image: Some(image),
         }
     }
@@ -229,6 +352,22 @@ pub fn set_column_width(
         .map_err(|e| CommandError::new(format!("设置列宽失败: {}", e), "WRITE_ERROR"))
 }
 
+/// 隐藏行
+pub fn set_row_hidden(worksheet: &mut Worksheet, row: u32) -> Result<(), CommandError> {
+    worksheet
+        .set_row_hidden(row)
+        .map(|_| ())
+        .map_err(|e| CommandError::new(format!("隐藏行失败: {}", e), "WRITE_ERROR"))
+}
+
+/// 隐藏列
+pub fn set_column_hidden(worksheet: &mut Worksheet, col: u16) -> Result<(), CommandError> {
+    worksheet
+        .set_column_hidden(col)
+        .map(|_| ())
+        .map_err(|e| CommandError::new(format!("隐藏列失败: {}", e), "WRITE_ERROR"))
+}
+
 /// 设置行高
 pub fn set_row_height(
     worksheet: &mut Worksheet,
@@ -241,63 +380,21 @@ pub fn set_row_height(
         .map_err(|e| CommandError::new(format!("设置行高失败: {}", e), "WRITE_ERROR"))
 }
 
-/// 合并单元格
+/// 合并单元格（区域内所有单元格使用同一格式，左上角的值需随后单独写入）
 pub fn merge_range(
     worksheet: &mut Worksheet,
     first_row: u32,
     first_col: u16,
     last_row: u32,
     last_col: u16,
+    format: &Format,
 ) -> Result<(), CommandError> {
     worksheet
-        .merge_range(first_row, first_col, last_row, last_col, "", &Format::new())
+        .merge_range(first_row, first_col, last_row, last_col, "", format)
         .map(|_| ())
         .map_err(|e| CommandError::new(format!("合并单元格失败: {}", e), "WRITE_ERROR"))
 }
 
-/// 嵌入图片到单元格（使用 insert_image_with_offset 实现居中）
-pub fn embed_image_to_cell(
-    worksheet: &mut Worksheet,
-    row: u32,
-    col: u16,
-    image_data: &EmbeddedImage,
-) -> Result<(), CommandError> {
-    // 检查图片数据是否有效
-    if image_data.data.is_empty() {
-        return Err(CommandError::new("图片数据为空", "IMAGE_ERROR"));
-    }
-
-    // 检查图片数据最小长度（至少需要几个字节来识别格式）
-    if image_data.data.len() < 8 {
-        return Err(CommandError::new("图片数据太短，无法识别格式", "IMAGE_ERROR"));
-    }
-
-    // 从图片数据创建 Image 对象
-    let mut image = Image::new_from_buffer(&image_data.data)
-        .map_err(|e| CommandError::new(format!("创建图片对象失败: {}", e), "IMAGE_ERROR"))?;
-
-    // 设置图片大小适应单元格
-    // 目标尺寸适合默认行高约 20 点的单元格
-    let target_size = 18.0; // 像素，略小于行高以便居中
-
-    image = image
-        .set_scale_to_size(target_size, target_size, true)
-        .set_object_movement(rust_xlsxwriter::ObjectMovement::MoveAndSizeWithCells);
-
-    // 计算居中偏移（假设列宽约 60 像素，行高约 26 像素）
-    // 水平偏移 = (列宽 - 图片宽) / 2 ≈ (60 - 18) / 2 = 21
-    // 垂直偏移 = (行高 - 图片高) / 2 ≈ (26 - 18) / 2 = 4
-    let x_offset: u32 = 21;
-    let y_offset: u32 = 4;
-
-    // 使用 insert_image_with_offset 插入图片（带偏移，实现居中）
-    worksheet
-        .insert_image_with_offset(row, col, &image, x_offset, y_offset)
-        .map_err(|e| CommandError::new(format!("插入图片失败: {}", e), "IMAGE_ERROR"))?;
-
-    Ok(())
-}
-
 /// 嵌入图片到单元格（带自定义尺寸和单元格尺寸用于居中）
 pub fn embed_image_to_cell_with_size(
     worksheet: &mut Worksheet,
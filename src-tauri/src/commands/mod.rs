@@ -3,6 +3,7 @@ pub mod error;
 pub mod excel;
 pub mod icon_extractor;
 pub mod installed_apps;
+pub mod output;
 pub mod tax;
 pub mod ups_dpd;
 pub mod updater;
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::commands::error::CommandError;
+use crate::core::output::{OutputError, OutputFile, OutputRule};
+use crate::models::output::OutputTool;
+
+// 输出文件位置与命名配置（各工具共用）
+
+/// 获取配置文件路径
+fn get_config_path() -> Result<PathBuf, CommandError> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| CommandError::new("无法获取配置目录", "CONFIG_ERROR"))?;
+
+    let app_config_dir = config_dir.join("liao-tools");
+
+    // 确保目录存在
+    if !app_config_dir.exists() {
+        fs::create_dir_all(&app_config_dir)
+            .map_err(|e| CommandError::new(format!("创建配置目录失败: {}", e), "CONFIG_ERROR"))?;
+    }
+
+    Ok(app_config_dir.join("output_rules.json"))
+}
+
+/// 加载已保存的规则
+fn load_saved_rules() -> Result<HashMap<String, OutputRule>, CommandError> {
+    let config_path = get_config_path()?;
+
+    if !config_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| CommandError::new(format!("读取输出配置失败: {}", e), "CONFIG_ERROR"))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| CommandError::new(format!("解析输出配置失败: {}", e), "CONFIG_ERROR"))
+}
+
+/// 加载指定工具的输出规则，未配置时返回默认规则
+pub fn load_rule(tool: OutputTool) -> Result<OutputRule, CommandError> {
+    Ok(load_saved_rules()?
+        .remove(tool.key())
+        .unwrap_or_else(|| tool.default_rule()))
+}
+
+/// 按已保存的规则为输入文件选定输出文件（xlsx）
+pub fn claim_output(tool: OutputTool, input: &Path, type_name: &str) -> Result<OutputFile, CommandError> {
+    claim_output_with(tool, &load_rule(tool)?, input, type_name)
+}
+
+/// 按指定规则为输入文件选定输出文件（xlsx），不会选中输入文件本身
+pub fn claim_output_with(
+    tool: OutputTool,
+    rule: &OutputRule,
+    input: &Path,
+    type_name: &str,
+) -> Result<OutputFile, CommandError> {
+    let dir = output_dir(tool, rule, input)?;
+    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+
+    Ok(rule.claim(&dir, stem, type_name, "xlsx", Some(input))?)
+}
+
+/// 输出目录
+///
+/// 未配置输出目录时：UPS/DPD 依次尝试桌面、文档、用户目录，其他工具使用输入文件所在目录。
+pub fn output_dir(tool: OutputTool, rule: &OutputRule, input: &Path) -> Result<PathBuf, CommandError> {
+    match rule.configured_dir() {
+        Some(dir) => Ok(dir),
+        None => Ok(default_dir(tool, input).ok_or(OutputError::NoDirectory)?),
+    }
+}
+
+fn default_dir(tool: OutputTool, input: &Path) -> Option<PathBuf> {
+    let input_dir = input
+        .parent()
+        .map(|p| if p.as_os_str().is_empty() { PathBuf::from(".") } else { p.to_path_buf() });
+
+    match tool {
+        OutputTool::UpsDpd => dirs::desktop_dir()
+            .filter(|dir| dir.is_dir())
+            .or_else(|| dirs::document_dir().filter(|dir| dir.is_dir()))
+            .or_else(dirs::home_dir)
+            .or(input_dir),
+        _ => input_dir,
+    }
+}
+
+/// 获取所有工具的输出规则
+#[tauri::command]
+pub async fn get_output_rules() -> Result<HashMap<String, OutputRule>, CommandError> {
+    let mut saved = load_saved_rules()?;
+    Ok(OutputTool::ALL
+        .iter()
+        .map(|tool| {
+            let rule = saved.remove(tool.key()).unwrap_or_else(|| tool.default_rule());
+            (tool.key().to_string(), rule)
+        })
+        .collect())
+}
+
+/// 保存指定工具的输出规则
+#[tauri::command]
+pub async fn save_output_rule(tool: OutputTool, rule: OutputRule) -> Result<(), CommandError> {
+    rule.validate()?;
+
+    let mut rules = load_saved_rules()?;
+    rules.insert(tool.key().to_string(), rule);
+
+    let content = serde_json::to_string_pretty(&rules)
+        .map_err(|e| CommandError::new(format!("序列化输出配置失败: {}", e), "CONFIG_ERROR"))?;
+    fs::write(get_config_path()?, content)
+        .map_err(|e| CommandError::new(format!("写入输出配置失败: {}", e), "CONFIG_ERROR"))?;
+
+    Ok(())
+}
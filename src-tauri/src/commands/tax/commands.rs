@@ -1,13 +1,14 @@
+use crate::commands::output;
 use crate::commands::tax::database::TaxDatabase;
 use crate::commands::tax::downloader::TaxDataDownloader;
 use crate::commands::tax::excel::TaxExcelProcessor;
 use crate::commands::tax::query::TaxQuery;
 use crate::commands::tax::scraper::TaxScraper;
+use crate::models::output::OutputTool;
 use crate::models::tax::{BatchResult, TaxTariff, TaxVersionInfo, UpdateResult};
 use tauri::Emitter;
 use tauri_plugin_opener::OpenerExt;
 use log::{info, warn};
-use chrono;
 
 /// 精确查询税率
 #[tauri::command]
@@ -51,24 +52,10 @@ pub async fn tax_batch_query(
     // 检查数据库是否有数据
     db.ensure_has_data().map_err(|e| e.to_string())?;
     
-    // 生成输出文件路径（加上年月日时分）
-    let input_path_obj = std::path::Path::new(&input_path);
-    let now = chrono::Local::now();
-    let timestamp = now.format("%Y%m%d_%H%M").to_string();
-    let output_filename = format!(
-        "{}_查询结果_{}.xlsx",
-        input_path_obj
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("tax_query"),
-        timestamp
-    );
-    let output_path = input_path_obj
-        .parent()
-        .unwrap_or(std::path::Path::new("."))
-        .join(&output_filename);
-    
-    let output_path_str = output_path.to_string_lossy().to_string();
+    // 选定输出文件
+    let output = output::claim_output(OutputTool::Tax, std::path::Path::new(&input_path), "查询结果")
+        .map_err(|e| e.message)?;
+    let output_path_str = output.path().to_string_lossy().to_string();
     
     // 处理批量查询
     let result = TaxExcelProcessor::process_batch(&db, &input_path, &output_path_str, |current, total| {
@@ -82,6 +69,7 @@ pub async fn tax_batch_query(
         );
     })
     .map_err(|e| e.to_string())?;
+    output.keep();
     
     Ok(result)
 }
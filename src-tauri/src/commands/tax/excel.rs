@@ -1,8 +1,8 @@
 use crate::commands::tax::database::TaxDatabase;
 use crate::commands::tax::query::TaxQuery;
+use crate::core::tabular::TabularWorkbook;
 use crate::models::tax::BatchResult;
 use anyhow::{Context, Result};
-use calamine::{open_workbook, DataType, Reader, Xlsx};
 use rust_xlsxwriter::{Format, Workbook};
 
 /// Excel批量处理器
@@ -19,27 +19,17 @@ impl TaxExcelProcessor {
     where
         F: FnMut(usize, usize),
     {
-        // 读取输入文件
-        let mut workbook: Xlsx<_> = open_workbook(input_path)
+        // 读取输入文件（xlsx / xls / ods / csv）
+        let mut workbook = TabularWorkbook::open(input_path)
             .context("Failed to open input Excel file")?;
-        
-        let sheet_name = workbook
-            .sheet_names()
-            .first()
-            .context("No sheets found in workbook")?
-            .clone();
-        
-        let range = workbook
-            .worksheet_range(&sheet_name)
-            .context("Failed to read sheet")?;
-        
-        // 收集所有编码（跳过标题行）
+        let sheet = workbook.first_sheet().context("Failed to read sheet")?;
+
+        // 收集所有编码（跳过标题行），记录原始行号用于错误提示
+        let header_row = sheet.detect_header_row();
         let mut codes = Vec::new();
-        for row in range.rows().skip(1) {
-            if let Some(cell) = row.first() {
-                if let Some(code) = cell.as_string() {
-                    codes.push(code.trim().to_string());
-                }
+        for (row_idx, row) in sheet.rows().enumerate().skip(header_row + 1) {
+            if let Some(cell) = row.first().filter(|cell| !cell.is_empty()) {
+                codes.push((row_idx + 1, cell.as_text().trim().to_string()));
             }
         }
         
@@ -49,7 +39,7 @@ impl TaxExcelProcessor {
         let mut results: Vec<(String, Option<crate::models::tax::TaxTariff>)> = Vec::new();
         
         // 查询每个编码
-        for (index, code) in codes.iter().enumerate() {
+        for (index, (row_number, code)) in codes.iter().enumerate() {
             progress_callback(index + 1, total);
             
             match TaxQuery::exact_search(db, code) {
@@ -59,11 +49,11 @@ impl TaxExcelProcessor {
                 }
                 Ok(None) => {
                     results.push((code.to_string(), None));
-                    errors.push(format!("第{}行：编码 {} 未找到", index + 2, code));
+                    errors.push(format!("第{}行：编码 {} 未找到", row_number, code));
                 }
                 Err(e) => {
                     results.push((code.to_string(), None));
-                    errors.push(format!("第{}行：查询失败 - {}", index + 2, e));
+                    errors.push(format!("第{}行：查询失败 - {}", row_number, e));
                 }
             }
         }
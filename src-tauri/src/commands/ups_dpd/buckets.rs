@@ -0,0 +1,245 @@
+// ============================================================================
+// 总结单分桶统计：按国家、邮编把件数归入模板中的固定列
+// ============================================================================
+//
+// 分桶在模板描述中配置（见 SummaryBuckets），DPD 调整分拨中心时只需修改描述文件。
+
+use std::collections::HashSet;
+use crate::commands::ups_dpd::descriptor::parse_column;
+use crate::commands::ups_dpd::weights::WeightTotals;
+use crate::models::ups_dpd::{Bucket, ExcelDataFrame, SummaryBuckets};
+
+/// 分桶统计结果（件数及重量）
+#[derive(Debug, Default, PartialEq)]
+pub struct BucketCounts {
+    /// 与 SummaryBuckets::buckets 一一对应
+    pub buckets: Vec<WeightTotals>,
+    pub other: WeightTotals,
+    pub total: WeightTotals,
+}
+
+/// 分桶写入的单元格
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetCell {
+    pub label: String,
+    /// A1 形式的位置，用于提示
+    pub reference: String,
+    /// （列, 行），从 1 开始
+    pub position: (u32, u32),
+}
+
+impl Bucket {
+    /// 日志中显示的名称
+    pub fn display_name(&self) -> String {
+        if let Some(label) = &self.label {
+            return label.clone();
+        }
+        match self.postcodes.as_slice() {
+            [postcode] => format!("{} 邮编 {}", self.country, postcode),
+            _ => self.country.clone(),
+        }
+    }
+
+    /// 国家相同，且满足任一邮编条件（没有邮编条件时整个国家都归入）
+    pub fn matches(&self, country: &str, postcode: &str) -> bool {
+        if !self.country.trim().eq_ignore_ascii_case(country) {
+            return false;
+        }
+        if self.postcodes.is_empty() && self.prefixes.is_empty() && self.ranges.is_empty() {
+            return true;
+        }
+
+        let postcode = postcode.trim();
+        self.postcodes.iter().any(|p| same_postcode(p, postcode))
+            || self.prefixes.iter().any(|p| has_prefix(postcode, p.trim()))
+            || postcode_number(postcode)
+                .is_some_and(|n| self.ranges.iter().any(|r| (r.from..=r.to).contains(&n)))
+    }
+}
+
+/// 邮编比较忽略首尾空白和大小写，纯数字时忽略前导零（Excel 会把 04347 读成 4347）
+fn same_postcode(a: &str, b: &str) -> bool {
+    match (postcode_number(a), postcode_number(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim().eq_ignore_ascii_case(b.trim()),
+    }
+}
+
+/// 前缀匹配忽略大小写（英国邮编常见小写录入，如 bt1 1aa）
+fn has_prefix(postcode: &str, prefix: &str) -> bool {
+    !prefix.is_empty()
+        && postcode
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+fn postcode_number(postcode: &str) -> Option<u32> {
+    let postcode = postcode.trim();
+    if !postcode.is_empty() && postcode.chars().all(|c| c.is_ascii_digit()) {
+        postcode.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// 统计每个分桶的件数和重量，每行归入第一个匹配的分桶，都不匹配时计入 other；国家为空的行不计入
+pub fn count(layout: &SummaryBuckets, data: &ExcelDataFrame) -> BucketCounts {
+    let mut counts = BucketCounts {
+        buckets: vec![WeightTotals::default(); layout.buckets.len()],
+        ..Default::default()
+    };
+
+    for row in &data.rows {
+        let country = row.get("国家二字码")
+            .map(|v| v.to_string().trim().to_uppercase())
+            .unwrap_or_default();
+        if country.is_empty() {
+            continue;
+        }
+
+        let postcode = row.get("收件人邮编").map(|v| v.to_string()).unwrap_or_default();
+        let totals = WeightTotals::of_row(row);
+
+        match layout.buckets.iter().position(|b| b.matches(&country, &postcode)) {
+            Some(i) => counts.buckets[i].add(&totals),
+            None => counts.other.add(&totals),
+        }
+        counts.total.add(&totals);
+    }
+
+    counts
+}
+
+/// 检查分桶配置：数据行、列字母有效且各列不重复，返回错误原因
+pub fn validate(layout: &SummaryBuckets) -> Result<(), String> {
+    if layout.data_row == 0 {
+        return Err("分桶数据行从 1 开始".to_string());
+    }
+
+    let mut used = HashSet::new();
+    for (label, column) in columns(layout) {
+        let col = parse_column(column).ok_or_else(|| format!("{} 的列 \"{}\" 无效", label, column))?;
+        if !used.insert(col) {
+            return Err(format!("列 {} 被多个分桶使用", column));
+        }
+    }
+
+    for bucket in &layout.buckets {
+        if bucket.country.trim().is_empty() {
+            return Err(format!("列 {} 的分桶没有国家", bucket.column));
+        }
+        if let Some(range) = bucket.ranges.iter().find(|r| r.from > r.to) {
+            return Err(format!("{} 的邮编区间 {}-{} 无效", bucket.display_name(), range.from, range.to));
+        }
+    }
+
+    Ok(())
+}
+
+/// 写入的单元格：各分桶、other、total（顺序与 BucketCounts 一致）
+pub fn target_cells(layout: &SummaryBuckets) -> Vec<TargetCell> {
+    columns(layout)
+        .into_iter()
+        .filter_map(|(label, column)| {
+            let col = parse_column(column)?;
+            Some(TargetCell {
+                label,
+                reference: format!("{}{}", column.trim().to_ascii_uppercase(), layout.data_row),
+                position: (col, layout.data_row),
+            })
+        })
+        .collect()
+}
+
+fn columns(layout: &SummaryBuckets) -> Vec<(String, &str)> {
+    layout
+        .buckets
+        .iter()
+        .map(|b| (b.display_name(), b.column.as_str()))
+        .chain([
+            ("Other".to_string(), layout.other_column.as_str()),
+            ("总计".to_string(), layout.total_column.as_str()),
+        ])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ups_dpd::{CellValue, ExcelRow, PostcodeRange};
+
+    fn bucket(column: &str, country: &str, postcodes: &[&str]) -> Bucket {
+        Bucket {
+            column: column.to_string(),
+            country: country.to_string(),
+            label: None,
+            postcodes: postcodes.iter().map(|p| p.to_string()).collect(),
+            prefixes: Vec::new(),
+            ranges: Vec::new(),
+        }
+    }
+
+    fn row(country: &str, postcode: CellValue, pieces: i64) -> ExcelRow {
+        let mut row = ExcelRow::new();
+        row.set("国家二字码".to_string(), CellValue::String(country.to_string()));
+        row.set("收件人邮编".to_string(), postcode);
+        row.set("件数".to_string(), CellValue::Integer(pieces));
+        row
+    }
+
+    #[test]
+    fn test_count_by_bucket() {
+        let mut ranged = bucket("H", "DE", &[]);
+        ranged.ranges.push(PostcodeRange { from: 90000, to: 90999 });
+        let layout = SummaryBuckets {
+            data_row: 4,
+            buckets: vec![bucket("F", "DE", &["04347"]), bucket("G", "FR", &[]), ranged],
+            other_column: "Z".to_string(),
+            total_column: "AA".to_string(),
+        };
+
+        let mut data = ExcelDataFrame::new(Vec::new());
+        data.add_row(row("de", CellValue::Number(4347.0), 2));
+        data.add_row(row("FR", CellValue::String("75001".to_string()), 3));
+        data.add_row(row("DE", CellValue::String("90451".to_string()), 4));
+        data.add_row(row("DE", CellValue::String("10115".to_string()), 5));
+        data.add_row(row("US", CellValue::Empty, 6));
+        data.add_row(row("", CellValue::Empty, 100));
+
+        let counts = count(&layout, &data);
+        let pieces: Vec<i64> = counts.buckets.iter().map(|t| t.pieces).collect();
+        assert_eq!(pieces, vec![2, 3, 4]);
+        assert_eq!((counts.other.pieces, counts.total.pieces), (11, 20));
+
+        let cells = target_cells(&layout);
+        assert_eq!(cells.len(), 5);
+        assert_eq!(cells[4].reference, "AA4");
+        assert_eq!(cells[4].position, (27, 4));
+        assert!(validate(&layout).is_ok());
+    }
+
+    #[test]
+    fn test_prefix_ignores_case() {
+        let mut highlands = bucket("B", "GB", &[]);
+        highlands.prefixes = vec!["HS".to_string(), "IV".to_string()];
+
+        assert!(highlands.matches("GB", "iv2 3ab"));
+        assert!(highlands.matches("GB", " HS1 2AA"));
+        assert!(!highlands.matches("GB", "H"));
+        assert!(!highlands.matches("IE", "IV2 3AB"));
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_columns() {
+        let layout = SummaryBuckets {
+            data_row: 4,
+            buckets: vec![bucket("F", "DE", &["4347"]), bucket("f", "FR", &[])],
+            other_column: "Z".to_string(),
+            total_column: "AA".to_string(),
+        };
+        assert!(validate(&layout).is_err());
+
+        let layout = SummaryBuckets { buckets: vec![bucket("F1", "DE", &[])], ..layout };
+        assert!(validate(&layout).is_err());
+    }
+}
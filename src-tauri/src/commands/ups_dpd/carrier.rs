@@ -0,0 +1,414 @@
+// ============================================================================
+// 承运商模板填充：公共流程与工具
+// ============================================================================
+//
+// 各承运商只需实现 CarrierProcessor::fill，说明哪些工作表填入哪些数据；
+// 查找工作表、定位表头、逐行写入、分桶统计等由 TemplateFiller 按模板描述完成。
+// 只按描述填表的承运商直接使用 ManifestProcessor，新增承运商只需模板和描述文件。
+
+use std::collections::HashMap;
+use std::path::Path;
+use umya_spreadsheet::*;
+use crate::commands::error::CommandError;
+use crate::commands::ups_dpd::buckets;
+use crate::commands::ups_dpd::descriptor::HeaderIndex;
+use crate::commands::ups_dpd::dpd_processor::DpdProcessor;
+use crate::commands::ups_dpd::manifest_processor::ManifestProcessor;
+use crate::commands::ups_dpd::ups_processor::UpsProcessor;
+use crate::commands::ups_dpd::weights::{self, WeightReport};
+use crate::models::ups_dpd::{
+    CellValue, ColumnMapping, ExcelDataFrame, ExcelRow, SheetDescriptor, TemplateDescriptor, TemplateType,
+};
+
+/// 承运商处理器
+pub trait CarrierProcessor {
+    /// 把数据填入已加载的模板
+    fn fill(
+        &self,
+        filler: &mut TemplateFiller,
+        workbook: &mut Spreadsheet,
+        main_data: &ExcelDataFrame,
+        detail_data: Option<&ExcelDataFrame>,
+    ) -> Result<(), CommandError>;
+}
+
+/// 模板类型对应的处理器
+pub fn processor_for(template_type: &TemplateType) -> Box<dyn CarrierProcessor> {
+    match template_type {
+        TemplateType::Ups => Box::new(UpsProcessor),
+        TemplateType::Dpd => Box::new(DpdProcessor),
+        TemplateType::Gls | TemplateType::Fedex | TemplateType::RoyalMail => Box::new(ManifestProcessor),
+    }
+}
+
+/// 加载模板、填充数据并保存，返回处理日志
+pub fn process(
+    template_type: &TemplateType,
+    descriptor: TemplateDescriptor,
+    main_data: &ExcelDataFrame,
+    detail_data: Option<&ExcelDataFrame>,
+    template_path: &Path,
+    output_path: &Path,
+) -> Result<Vec<String>, CommandError> {
+    let mut filler = TemplateFiller::new(descriptor);
+    filler.log(format!("开始处理 {} 数据（使用模板）", template_type.label()));
+
+    let mut workbook = reader::xlsx::read(template_path)
+        .map_err(|e| CommandError::new(format!("无法加载模板: {}", e), "ERROR"))?;
+    filler.log(format!("成功加载模板: {:?}", template_path));
+
+    processor_for(template_type).fill(&mut filler, &mut workbook, main_data, detail_data)?;
+
+    writer::xlsx::write(&workbook, output_path)
+        .map_err(|e| CommandError::new(format!("保存文件失败: {}", e), "ERROR"))?;
+    filler.log(format!("{} 数据处理完成: {:?}", template_type.label(), output_path));
+
+    Ok(filler.into_logs())
+}
+
+/// 按模板描述填充工作表，并收集处理日志
+pub struct TemplateFiller {
+    descriptor: TemplateDescriptor,
+    logs: Vec<String>,
+}
+
+impl TemplateFiller {
+    pub fn new(descriptor: TemplateDescriptor) -> Self {
+        Self { descriptor, logs: Vec::new() }
+    }
+
+    pub fn descriptor(&self) -> &TemplateDescriptor {
+        &self.descriptor
+    }
+
+    pub fn log(&mut self, message: String) {
+        println!("{}", message);
+        self.logs.push(message);
+    }
+
+    pub fn into_logs(self) -> Vec<String> {
+        self.logs
+    }
+
+    /// 按列映射逐行填充（从表头下方第一个空行开始）
+    ///
+    /// lookup 为关联的主数据：描述中的 lookup_columns 按 lookup_key 从中匹配后填入同一行。
+    pub fn fill_rows(
+        &mut self,
+        workbook: &mut Spreadsheet,
+        key: &str,
+        data: &ExcelDataFrame,
+        lookup: Option<&ExcelDataFrame>,
+    ) -> Result<(), CommandError> {
+        let layout = self.descriptor.sheet(key)?.clone();
+        let sheet = self.find_sheet(workbook, &layout)?;
+        let sheet_name = sheet.get_name().to_string();
+        let headers = header_index(sheet, layout.header_row);
+        let first_empty_row = find_first_empty_row(sheet, layout.header_row);
+
+        let columns = self.resolve_columns(&sheet_name, &layout, &headers, &layout.columns);
+        let lookup_columns = match lookup {
+            Some(_) => self.resolve_columns(&sheet_name, &layout, &headers, &layout.lookup_columns),
+            None => Vec::new(),
+        };
+        let lookup_key = layout.lookup_key.clone().unwrap_or_default();
+
+        // 建立主数据的关联键索引
+        let mut lookup_index: HashMap<String, &ExcelRow> = HashMap::new();
+        if let (Some(main_data), false) = (lookup, lookup_columns.is_empty()) {
+            for row in &main_data.rows {
+                if let Some(value) = row.get(&lookup_key) {
+                    lookup_index.insert(value.to_string().trim().to_string(), row);
+                }
+            }
+        }
+
+        for (row_idx, row_data) in data.rows.iter().enumerate() {
+            let target_row = first_empty_row + row_idx as u32;
+            let sheet_mut = workbook.get_sheet_by_name_mut(&sheet_name)
+                .ok_or_else(|| CommandError::new("工作表不存在", "ERROR"))?;
+
+            write_mapped_row(sheet_mut, &layout, &columns, row_data, target_row)?;
+
+            if lookup_columns.is_empty() {
+                continue;
+            }
+            if let Some(value) = row_data.get(&lookup_key) {
+                let value = value.to_string().trim().to_string();
+                match lookup_index.get(&value) {
+                    Some(main_row) => write_mapped_row(sheet_mut, &layout, &lookup_columns, main_row, target_row)?,
+                    None => self.log(format!("警告: 未找到{} {} 的匹配行", lookup_key, value)),
+                }
+            }
+        }
+
+        self.log(format!("{} 填充完成，共 {} 行（从第 {} 行开始）", sheet_name, data.rows.len(), first_empty_row));
+        Ok(())
+    }
+
+    /// 按描述中的分桶统计件数，写入固定单元格（写入前检查单元格是否存在）
+    pub fn fill_buckets(
+        &mut self,
+        workbook: &mut Spreadsheet,
+        key: &str,
+        data: &ExcelDataFrame,
+    ) -> Result<(), CommandError> {
+        let layout = self.descriptor.sheet(key)?.clone();
+        let bucket_layout = layout.buckets.as_ref().ok_or_else(|| {
+            CommandError::new(format!("模板描述的工作表 {} 缺少分桶配置 (buckets)", key), "CONFIG_ERROR")
+        })?;
+        let sheet = self.find_sheet(workbook, &layout)?;
+        let sheet_name = sheet.get_name().to_string();
+
+        // 1. 写入前检查模板中的目标单元格
+        let cells = buckets::target_cells(bucket_layout);
+        let missing: Vec<String> = cells
+            .iter()
+            .filter(|cell| sheet.get_cell(cell.position).is_none())
+            .map(|cell| format!("{}（{}）", cell.reference, cell.label))
+            .collect();
+        if !missing.is_empty() {
+            return Err(CommandError::new(
+                format!(
+                    "模板工作表 {} 中找不到单元格 {}，请检查模板或模板描述中的分桶配置",
+                    sheet_name,
+                    missing.join("、")
+                ),
+                "TEMPLATE_ERROR",
+            ));
+        }
+
+        // 2. 按分桶统计件数
+        let counts = buckets::count(bucket_layout, data);
+        let values = counts.buckets.iter().chain([&counts.other, &counts.total]).map(|t| t.pieces);
+
+        // 3. 填充各分桶、Other 和总计
+        let sheet_mut = workbook.get_sheet_by_name_mut(&sheet_name)
+            .ok_or_else(|| CommandError::new("工作表不存在", "ERROR"))?;
+
+        for (cell, count) in cells.iter().zip(values) {
+            let (col, row) = cell.position;
+            write_number(sheet_mut, row, col, count as f64)?;
+            self.log(format!("{}: {} 件", cell.label, count));
+        }
+
+        Ok(())
+    }
+
+    /// 写入重量汇总与对账工作表，各分组合计写入日志，对账不一致时记录警告
+    pub fn write_weight_report(&mut self, workbook: &mut Spreadsheet, report: &WeightReport) -> Result<(), CommandError> {
+        weights::write_sheet(workbook, report)?;
+
+        for check in report.checks.iter().filter(|c| c.metric == "件数") {
+            self.log(format!("{} 合计 {} 件", check.scope, check.summarized));
+        }
+        for check in report.mismatches() {
+            self.log(format!(
+                "警告: 对账不一致 {} {}: 输入合计 {:.2}，汇总合计 {:.2}，差异 {:.2}",
+                check.scope,
+                check.metric,
+                check.expected,
+                check.summarized,
+                check.difference()
+            ));
+        }
+        self.log(format!("重量汇总与对账已写入工作表 {}", weights::REPORT_SHEET));
+        Ok(())
+    }
+
+    /// 查找描述中的工作表，都没找到时使用第一个工作表并记录警告
+    fn find_sheet<'a>(&mut self, workbook: &'a Spreadsheet, layout: &SheetDescriptor) -> Result<&'a Worksheet, CommandError> {
+        let sheet = find_sheet(workbook, &layout.names)?;
+        if !layout.names.iter().any(|name| name == sheet.get_name()) {
+            self.log(format!("警告: 找不到工作表 {:?}，使用第一个工作表 {}", layout.names, sheet.get_name()));
+        }
+        Ok(sheet)
+    }
+
+    /// 解析列映射（数据列 -> 列索引），模板中找不到的表头记录警告
+    fn resolve_columns(
+        &mut self,
+        sheet_name: &str,
+        layout: &SheetDescriptor,
+        headers: &HeaderIndex,
+        mappings: &[ColumnMapping],
+    ) -> Vec<(String, u32)> {
+        let (found, missing) = headers.resolve(mappings);
+        if !missing.is_empty() {
+            self.log(format!(
+                "警告: {} 第 {} 行找不到表头 {:?}，对应数据不会填入",
+                sheet_name, layout.header_row, missing
+            ));
+        }
+        found
+            .into_iter()
+            .map(|(mapping, col)| (mapping.source.clone(), col))
+            .collect()
+    }
+}
+
+// ==================== 工具函数 ====================
+
+/// 查找工作表（支持多个可能的名称），都没找到时使用第一个工作表
+pub fn find_sheet<'a>(workbook: &'a Spreadsheet, possible_names: &[String]) -> Result<&'a Worksheet, CommandError> {
+    for name in possible_names {
+        if let Some(sheet) = workbook.get_sheet_by_name(name) {
+            return Ok(sheet);
+        }
+    }
+
+    workbook.get_sheet(&0)
+        .ok_or_else(|| CommandError::new("找不到工作表", "ERROR"))
+}
+
+/// 表头索引（读取表头行的前50列）
+pub fn header_index(sheet: &Worksheet, header_row: u32) -> HeaderIndex {
+    HeaderIndex::new((1..=50).filter_map(|col_idx| {
+        sheet
+            .get_cell((col_idx, header_row))
+            .map(|cell| (col_idx, cell.get_value().to_string()))
+    }))
+}
+
+/// 查找表头下方的第一个空行
+pub fn find_first_empty_row(sheet: &Worksheet, header_row: u32) -> u32 {
+    let max_row = sheet.get_highest_row();
+    let max_col = sheet.get_highest_column();
+
+    for row in header_row + 1..=max_row + 10 {
+        let is_empty = (1..=max_col).all(|col| {
+            sheet
+                .get_cell((col, row))
+                .is_none_or(|cell| cell.get_value().trim().is_empty())
+        });
+
+        if is_empty {
+            return row;
+        }
+    }
+
+    max_row + 1
+}
+
+/// 按列映射写入一行数据，单号等列以文本格式写入
+pub fn write_mapped_row(
+    sheet: &mut Worksheet,
+    layout: &SheetDescriptor,
+    columns: &[(String, u32)],
+    row_data: &ExcelRow,
+    target_row: u32,
+) -> Result<(), CommandError> {
+    for (source, col_idx) in columns {
+        if let Some(value) = row_data.get(source) {
+            if layout.is_text(source) {
+                if !value.is_empty() {
+                    write_text(sheet, target_row, *col_idx, &value.to_string())?;
+                }
+            } else {
+                write_cell_value(sheet, target_row, *col_idx, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 写入单元格值（数字写为数值单元格）
+pub fn write_cell_value(
+    sheet: &mut Worksheet,
+    row: u32,
+    col: u32,
+    value: &CellValue,
+) -> Result<(), CommandError> {
+    match value {
+        CellValue::Empty => Ok(()),
+        CellValue::String(s) => write_string(sheet, row, col, s),
+        CellValue::Number(n) => write_number(sheet, row, col, *n),
+        CellValue::Integer(i) => write_number(sheet, row, col, *i as f64),
+        CellValue::Boolean(b) => write_string(sheet, row, col, &b.to_string()),
+    }
+}
+
+/// 写入字符串（不按内容推断类型，"00123" 不会变成数字 123）
+pub fn write_string(sheet: &mut Worksheet, row: u32, col: u32, value: &str) -> Result<(), CommandError> {
+    sheet.get_cell_mut((col, row)).set_value_string(value);
+    Ok(())
+}
+
+/// 写入单号等文本列：字符串值，模板未设置格式时设为文本格式 (@)，避免长单号被显示为科学计数法
+pub fn write_text(sheet: &mut Worksheet, row: u32, col: u32, value: &str) -> Result<(), CommandError> {
+    let cell = sheet.get_cell_mut((col, row));
+    cell.set_value_string(value);
+    let general = cell
+        .get_style()
+        .get_number_format()
+        .is_none_or(|format| format.get_format_code().eq_ignore_ascii_case("General"));
+    if general {
+        cell.get_style_mut().get_number_format_mut().set_format_code("@");
+    }
+    Ok(())
+}
+
+/// 写入数值单元格，保留模板单元格原有的数字格式（只替换值，不改样式）
+pub fn write_number(sheet: &mut Worksheet, row: u32, col: u32, value: f64) -> Result<(), CommandError> {
+    sheet.get_cell_mut((col, row)).set_value_number(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{open_workbook_auto, Data, Reader};
+    use crate::commands::ups_dpd::descriptor;
+
+    #[test]
+    fn test_round_trip_cell_types() {
+        let descriptor = descriptor::parse(
+            r#"{"version":1,"template_type":"dpd","sheets":{"list":{
+                "names":["Sheet1"],
+                "columns":[
+                    {"source":"转单号","header":"Tracking No"},
+                    {"source":"件数","header":"PCS"},
+                    {"source":"收货实重","header":"GW (kg)"},
+                    {"source":"收件人邮编","header":"post Code"}
+                ],
+                "text_columns":["转单号"]}}}"#,
+        )
+        .unwrap();
+
+        // 模板：表头 + 重量列预设两位小数格式
+        let mut workbook = new_file();
+        let sheet = workbook.get_sheet_by_name_mut("Sheet1").unwrap();
+        for (col, header) in ["Tracking No", "PCS", "GW (kg)", "post Code"].iter().enumerate() {
+            sheet.get_cell_mut((col as u32 + 1, 1)).set_value_string(*header);
+        }
+        sheet.get_cell_mut((3, 2)).get_style_mut().get_number_format_mut().set_format_code("0.00");
+
+        let mut data = ExcelDataFrame::new(Vec::new());
+        let mut row = ExcelRow::new();
+        row.set("转单号".to_string(), CellValue::Number(1234567890123.0));
+        row.set("件数".to_string(), CellValue::Integer(3));
+        row.set("收货实重".to_string(), CellValue::Number(12.5));
+        row.set("收件人邮编".to_string(), CellValue::String("04347".to_string()));
+        data.add_row(row);
+
+        let mut filler = TemplateFiller::new(descriptor);
+        filler.fill_rows(&mut workbook, "list", &data, None).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.xlsx");
+        writer::xlsx::write(&workbook, &path).unwrap();
+
+        // calamine 读回：单号和邮编为文本，件数和重量为数值
+        let mut output = open_workbook_auto(&path).unwrap();
+        let range = output.worksheet_range("Sheet1").unwrap();
+        assert_eq!(range.get_value((1, 0)), Some(&Data::String("1234567890123".to_string())));
+        assert!(matches!(range.get_value((1, 1)), Some(Data::Float(n)) if *n == 3.0));
+        assert!(matches!(range.get_value((1, 2)), Some(Data::Float(n)) if *n == 12.5));
+        assert_eq!(range.get_value((1, 3)), Some(&Data::String("04347".to_string())));
+
+        // 模板单元格的数字格式保留
+        let reread = reader::xlsx::read(&path).unwrap();
+        let cell = reread.get_sheet_by_name("Sheet1").unwrap().get_cell((3, 2)).unwrap();
+        assert_eq!(cell.get_style().get_number_format().unwrap().get_format_code(), "0.00");
+    }
+}
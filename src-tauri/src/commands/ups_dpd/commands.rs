@@ -1,64 +1,41 @@
 use crate::commands::error::CommandError;
-use crate::commands::ups_dpd::{dpd_processor_v2, excel_utils, template_manager, ups_processor_v2};
-use crate::models::ups_dpd::{ProcessRequest, ProcessResponse, TemplateConfig, TemplateType};
-use chrono::Local;
-use std::path::PathBuf;
+use crate::commands::output;
+use crate::commands::ups_dpd::{carrier, descriptor, excel_utils, inspector, template_manager, validation};
+use crate::core::batch::{self, BatchSummary};
+use crate::models::output::OutputTool;
+use crate::models::ups_dpd::{
+    BatchProcessRequest, ExcelDataFrame, ProcessRequest, ProcessResponse, Severity, TemplateConfig, TemplateInspection,
+    TemplateType, ValidationReport,
+};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+/// 校验源数据，返回按行、列定位的问题报告（不生成文件）
+#[tauri::command]
+pub async fn validate_ups_dpd_file(
+    request: ProcessRequest,
+) -> Result<ValidationReport, CommandError> {
+    let (main_data, detail_data, _) = read_sources(&request)?;
+
+    Ok(validation::validate(&request.template_type, &main_data, detail_data.as_ref()))
+}
 
 /// 处理 UPS/DPD 文件
 #[tauri::command]
 pub async fn process_ups_dpd_file(
     request: ProcessRequest,
 ) -> Result<ProcessResponse, CommandError> {
-    // 生成输出文件路径（保存到桌面）
-    let desktop = dirs::desktop_dir()
-        .ok_or_else(|| CommandError::new("无法获取桌面路径", "ERROR"))?;
-
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-    let template_type_str = request.template_type.to_string().to_uppercase();
-    let output_filename = format!("{}总结单-{}.xlsx", template_type_str, timestamp);
-    let output_path = desktop.join(output_filename);
-
-    // 获取模板路径
-    let template_path = template_manager::get_template_path(&request.template_type)?;
-
-    // 读取主数据文件
-    let main_data = excel_utils::read_excel_file(&PathBuf::from(&request.main_file_path), 0)?;
-
-    // 读取明细表文件（如果有）
-    let detail_data = if let Some(detail_file_path) = &request.detail_file_path {
-        Some(excel_utils::read_excel_file(
-            &PathBuf::from(detail_file_path),
-            0,
-        )?)
-    } else {
-        None
-    };
-
-    // 根据模板类型选择处理器 (使用 V2 版本保留模板)
-    let logs: Vec<String>;
-
-    match request.template_type {
-        TemplateType::Ups => {
-            let mut processor = ups_processor_v2::UpsProcessorV2::new();
-            processor.process_ups_data(
-                &main_data,
-                detail_data.as_ref(),
-                &template_path,
-                &output_path,
-            )?;
-            logs = processor.get_logs();
-        }
-        TemplateType::Dpd => {
-            let mut processor = dpd_processor_v2::DpdProcessorV2::new();
-            processor.process_dpd_data(
-                &main_data,
-                detail_data.as_ref(),
-                &template_path,
-                &output_path,
-            )?;
-            logs = processor.get_logs();
-        }
-    }
+    // 多个主数据文件时按第一个文件命名
+    let main_file_path = request
+        .main_file_paths
+        .first()
+        .map(Path::new)
+        .ok_or_else(|| CommandError::new("请选择主数据文件", "ERROR"))?;
+    let template_type_str = request.template_type.label();
+    let output = output::claim_output(OutputTool::UpsDpd, main_file_path, template_type_str)?;
+
+    let logs = process_file(&request, output.path())?;
+    let output_path = output.keep();
 
     Ok(ProcessResponse {
         success: true,
@@ -68,6 +45,119 @@ pub async fn process_ups_dpd_file(
     })
 }
 
+/// 批量处理文件夹或 glob 匹配的主数据文件（不含明细表），每个文件完成后发送 ups-dpd-batch-progress 事件
+#[tauri::command]
+pub async fn process_ups_dpd_batch(
+    window: tauri::Window,
+    request: BatchProcessRequest,
+) -> Result<BatchSummary, CommandError> {
+    let inputs = batch::collect_inputs(&request.source)?;
+    let template_type_str = request.template_type.label();
+
+    let mut summary = batch::run_batch(
+        &inputs,
+        request.workers,
+        |path| {
+            let output = output::claim_output(OutputTool::UpsDpd, path, template_type_str)
+                .map_err(|e| e.message)?;
+            let file_request = ProcessRequest {
+                main_file_paths: vec![path.to_string_lossy().to_string()],
+                detail_file_paths: Vec::new(),
+                all_sheets: request.all_sheets,
+                template_type: request.template_type.clone(),
+                ignore_warnings: request.ignore_warnings,
+            };
+            process_file(&file_request, output.path()).map_err(|e| e.message)?;
+            Ok(output.keep())
+        },
+        |progress| {
+            let _ = window.emit("ups-dpd-batch-progress", progress);
+        },
+    );
+
+    if request.zip {
+        let rule = output::load_rule(OutputTool::UpsDpd)?;
+        let dir = output::output_dir(OutputTool::UpsDpd, &rule, &inputs[0])?;
+        batch::bundle_outputs(&mut summary, &dir, &format!("{}总结单", template_type_str))?;
+    }
+
+    Ok(summary)
+}
+
+/// 校验并读取数据后写入模板，返回处理日志
+///
+/// 源数据有错误时不生成；只有警告时需要 ignore_warnings 才继续，警告写入日志。
+fn process_file(request: &ProcessRequest, output_path: &Path) -> Result<Vec<String>, CommandError> {
+    let template_type = &request.template_type;
+
+    // 获取模板路径和模板描述
+    let template_path = template_manager::get_template_path(template_type)?;
+    let descriptor = template_manager::get_descriptor(template_type, &template_path)?;
+
+    let (main_data, detail_data, read_logs) = read_sources(request)?;
+
+    // 填充前校验源数据
+    let report = validation::validate(template_type, &main_data, detail_data.as_ref());
+    if report.error_count > 0 {
+        return Err(CommandError::new(
+            format!(
+                "源数据有 {} 个错误，已停止生成:\n{}",
+                report.error_count,
+                validation::summarize(&report, Severity::Error)
+            ),
+            "VALIDATION_ERROR",
+        ));
+    }
+    if report.warning_count > 0 && !request.ignore_warnings {
+        return Err(CommandError::new(
+            format!(
+                "源数据有 {} 个警告，确认后可忽略警告继续生成:\n{}",
+                report.warning_count,
+                validation::summarize(&report, Severity::Warning)
+            ),
+            "VALIDATION_WARNING",
+        ));
+    }
+
+    // 根据模板类型选择处理器，按模板描述填充（保留模板格式）
+    let mut logs = read_logs;
+    if let Some(warning) = template_manager::outdated_warning(&descriptor) {
+        logs.push(format!("警告: {}", warning));
+    }
+    logs.extend(report.issues.iter().map(|issue| format!("警告: {}", validation::describe(issue))));
+    logs.extend(carrier::process(
+        template_type,
+        descriptor,
+        &main_data,
+        detail_data.as_ref(),
+        &template_path,
+        output_path,
+    )?);
+
+    Ok(logs)
+}
+
+/// 读取并合并主数据文件和明细表文件（如果有），主数据按转单号、明细按子转单号去重
+fn read_sources(
+    request: &ProcessRequest,
+) -> Result<(ExcelDataFrame, Option<ExcelDataFrame>, Vec<String>), CommandError> {
+    if request.main_file_paths.is_empty() {
+        return Err(CommandError::new("请选择主数据文件", "ERROR"));
+    }
+    let (main_data, mut logs) = excel_utils::read_sources(&request.main_file_paths, request.all_sheets, "转单号")?;
+
+    let detail_data = if request.detail_file_paths.is_empty() {
+        None
+    } else {
+        let (detail, detail_logs) =
+            excel_utils::read_sources(&request.detail_file_paths, request.all_sheets, "子转单号")?;
+        logs.extend(detail_logs.into_iter().map(|log| format!("明细表 {}", log)));
+        Some(detail)
+    };
+
+    Ok((main_data, detail_data, logs))
+}
+
 /// 获取模板配置
 #[tauri::command]
 pub async fn get_template_config(
@@ -82,9 +172,34 @@ pub async fn get_template_config(
 /// 保存模板配置
 #[tauri::command]
 pub async fn save_template_config(config: TemplateConfig) -> Result<(), CommandError> {
+    // 保存前检查自定义模板和描述文件，避免处理时才发现错误
+    if !config.use_default {
+        inspector::ensure_usable(&config)?;
+    } else if let Some(path) = &config.descriptor_path {
+        descriptor::resolve(&config, Path::new(path))?;
+    }
     template_manager::save_config_for_type(&config)
 }
 
+/// 导出默认模板（及同名 .json 描述）供编辑，返回导出的模板路径
+#[tauri::command]
+pub async fn export_default_template(
+    template_type: String,
+    target_path: String,
+) -> Result<String, CommandError> {
+    let template_type = TemplateType::from_string(&template_type)
+        .ok_or_else(|| CommandError::new(format!("无效的模板类型: {}", template_type), "ERROR"))?;
+
+    let path = template_manager::export_default_template(&template_type, Path::new(&target_path))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 检查模板：工作表、表头行、已匹配和未匹配的字段、数据开始写入的行（不保存配置）
+#[tauri::command]
+pub async fn inspect_template(config: TemplateConfig) -> Result<TemplateInspection, CommandError> {
+    inspector::inspect(&config)
+}
+
 // 注意：文件选择功能已在前端使用 @tauri-apps/plugin-dialog 实现
 // 前端选择文件后直接调用 save_template_config 保存配置
 
@@ -107,6 +222,7 @@ pub async fn reset_to_default_template(
         template_type,
         template_path: None,
         use_default: true,
+        descriptor_path: None,
     };
 
     template_manager::save_config_for_type(&config)
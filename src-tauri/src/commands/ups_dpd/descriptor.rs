@@ -0,0 +1,285 @@
+// ============================================================================
+// 模板描述：工作表别名、表头映射、分桶单元格和强制文本列
+// ============================================================================
+//
+// 描述文件与模板放在一起（UPS_template.xlsx 对应 UPS_template.json），
+// 模板表头调整或新增模板版本时只需修改描述文件，无需重新编译。
+// 表头匹配忽略空白、大小写和全角/半角差异，模板中的换行不会导致漏填。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::commands::error::CommandError;
+use crate::commands::ups_dpd::buckets;
+use crate::models::ups_dpd::{ColumnMapping, SheetDescriptor, TemplateConfig, TemplateDescriptor, TemplateType};
+
+/// 当前支持的描述文件版本
+pub const DESCRIPTOR_VERSION: u32 = 1;
+
+const UPS_DESCRIPTOR: &str = include_str!("../../../resources/templates/UPS_template.json");
+const DPD_DESCRIPTOR: &str = include_str!("../../../resources/templates/DPD_template.json");
+const GLS_DESCRIPTOR: &str = include_str!("../../../resources/templates/GLS_template.json");
+const FEDEX_DESCRIPTOR: &str = include_str!("../../../resources/templates/FedEx_template.json");
+const ROYAL_MAIL_DESCRIPTOR: &str = include_str!("../../../resources/templates/RoyalMail_template.json");
+
+/// 内置描述（与默认模板对应）
+pub fn builtin(template_type: &TemplateType) -> Result<TemplateDescriptor, CommandError> {
+    parse(builtin_source(template_type))
+}
+
+/// 内置描述的原始 JSON（导出默认模板时写在模板旁边）
+pub fn builtin_source(template_type: &TemplateType) -> &'static str {
+    match template_type {
+        TemplateType::Ups => UPS_DESCRIPTOR,
+        TemplateType::Dpd => DPD_DESCRIPTOR,
+        TemplateType::Gls => GLS_DESCRIPTOR,
+        TemplateType::Fedex => FEDEX_DESCRIPTOR,
+        TemplateType::RoyalMail => ROYAL_MAIL_DESCRIPTOR,
+    }
+}
+
+/// 读取描述文件
+pub fn load(path: &Path) -> Result<TemplateDescriptor, CommandError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        CommandError::new(format!("读取模板描述 {} 失败: {}", path.display(), e), "CONFIG_ERROR")
+    })?;
+    parse(&content)
+}
+
+/// 解析描述并检查版本、工作表名称、分桶配置
+pub fn parse(content: &str) -> Result<TemplateDescriptor, CommandError> {
+    let descriptor: TemplateDescriptor = serde_json::from_str(content)
+        .map_err(|e| CommandError::new(format!("解析模板描述失败: {}", e), "CONFIG_ERROR"))?;
+
+    if descriptor.version > DESCRIPTOR_VERSION {
+        return Err(CommandError::new(
+            format!("模板描述版本 {} 高于当前支持的版本 {}，请升级软件", descriptor.version, DESCRIPTOR_VERSION),
+            "CONFIG_ERROR",
+        ));
+    }
+
+    for (key, sheet) in &descriptor.sheets {
+        if sheet.names.is_empty() {
+            return Err(invalid(key, "没有工作表名称"));
+        }
+        if sheet.header_row == 0 {
+            return Err(invalid(key, "表头行从 1 开始"));
+        }
+        if !sheet.lookup_columns.is_empty() && sheet.lookup_key.is_none() {
+            return Err(invalid(key, "lookup_columns 需要同时设置 lookup_key"));
+        }
+        if let Some(layout) = &sheet.buckets {
+            buckets::validate(layout).map_err(|reason| invalid(key, &reason))?;
+        }
+    }
+
+    Ok(descriptor)
+}
+
+fn invalid(sheet: &str, reason: &str) -> CommandError {
+    CommandError::new(format!("模板描述中工作表 {} {}", sheet, reason), "CONFIG_ERROR")
+}
+
+/// 模板对应的描述文件路径：配置指定的文件，其次是模板同目录同名的 .json
+pub fn descriptor_path(config: &TemplateConfig, template_path: &Path) -> Option<PathBuf> {
+    match &config.descriptor_path {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(template_path.with_extension("json")).filter(|path| path.is_file()),
+    }
+}
+
+/// 加载模板使用的描述，没有描述文件时使用内置描述
+pub fn resolve(config: &TemplateConfig, template_path: &Path) -> Result<TemplateDescriptor, CommandError> {
+    let descriptor = match descriptor_path(config, template_path) {
+        Some(path) => load(&path)?,
+        None => builtin(&config.template_type)?,
+    };
+
+    if descriptor.template_type != config.template_type {
+        return Err(CommandError::new(
+            format!(
+                "模板描述适用于 {}，与当前模板类型 {} 不一致",
+                descriptor.template_type.label(),
+                config.template_type.label()
+            ),
+            "CONFIG_ERROR",
+        ));
+    }
+
+    Ok(descriptor)
+}
+
+impl TemplateDescriptor {
+    /// 按用途获取工作表描述
+    pub fn sheet(&self, key: &str) -> Result<&SheetDescriptor, CommandError> {
+        self.sheets
+            .get(key)
+            .ok_or_else(|| CommandError::new(format!("模板描述缺少工作表 {}", key), "CONFIG_ERROR"))
+    }
+}
+
+impl SheetDescriptor {
+    /// 数据列是否按文本写入
+    pub fn is_text(&self, source: &str) -> bool {
+        self.text_columns.iter().any(|c| c == source)
+    }
+}
+
+/// 表头索引（规范化后的表头 -> 列号，从 1 开始）
+#[derive(Debug, Default)]
+pub struct HeaderIndex {
+    columns: HashMap<String, u32>,
+}
+
+impl HeaderIndex {
+    /// 由（列号, 表头）构建，重复表头取第一列
+    pub fn new(headers: impl IntoIterator<Item = (u32, String)>) -> Self {
+        let mut columns = HashMap::new();
+        for (col, header) in headers {
+            let key = normalize_header(&header);
+            if !key.is_empty() {
+                columns.entry(key).or_insert(col);
+            }
+        }
+        Self { columns }
+    }
+
+    /// 查找映射对应的列（依次尝试表头和别名）
+    pub fn find(&self, mapping: &ColumnMapping) -> Option<u32> {
+        std::iter::once(&mapping.header)
+            .chain(&mapping.aliases)
+            .find_map(|header| self.columns.get(&normalize_header(header)).copied())
+    }
+
+    /// 解析一组映射，返回找到的列和找不到的表头
+    pub fn resolve<'a>(&self, mappings: &'a [ColumnMapping]) -> (Vec<(&'a ColumnMapping, u32)>, Vec<&'a str>) {
+        let mut found = Vec::new();
+        let mut missing = Vec::new();
+        for mapping in mappings {
+            match self.find(mapping) {
+                Some(col) => found.push((mapping, col)),
+                None => missing.push(mapping.header.as_str()),
+            }
+        }
+        (found, missing)
+    }
+}
+
+/// 规范化表头：去掉空白（含换行），全角转半角，忽略大小写
+pub fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// 解析列字母（如 "AA"）为列号，从 1 开始
+pub fn parse_column(column: &str) -> Option<u32> {
+    let column = column.trim();
+    if column.is_empty() || column.len() > 3 || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some(
+        column
+            .chars()
+            .fold(0u32, |acc, c| acc * 26 + (c.to_ascii_uppercase() as u32 - 'A' as u32 + 1)),
+    )
+}
+
+/// 列号（从 1 开始）转为列字母，如 27 -> "AA"
+pub fn column_letter(col: u32) -> String {
+    let mut letters = String::new();
+    let mut n = col;
+    while n > 0 {
+        letters.insert(0, (b'A' + ((n - 1) % 26) as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_descriptors() {
+        let ups = builtin(&TemplateType::Ups).unwrap();
+        for key in ["summary", "waybill", "statistics", "german_zipcode", "sub_order"] {
+            assert!(ups.sheet(key).is_ok(), "UPS 缺少 {}", key);
+        }
+        assert!(ups.sheet("sub_order").unwrap().is_text("子转单号"));
+
+        let dpd = builtin(&TemplateType::Dpd).unwrap();
+        let layout = dpd.sheet("summary").unwrap().buckets.as_ref().unwrap();
+        assert_eq!(layout.buckets.len(), 20);
+        assert_eq!(buckets::target_cells(layout).last().unwrap().position, (27, 4));
+        assert_eq!(dpd.sheet("sub_order").unwrap().lookup_key.as_deref(), Some("客户单号"));
+
+        for template_type in TemplateType::ALL {
+            let descriptor = builtin(&template_type).unwrap();
+            assert_eq!(descriptor.template_type, template_type);
+            assert!(descriptor.template_version > 0, "{} 缺少 template_version", template_type.label());
+        }
+    }
+
+    #[test]
+    fn test_header_matching_ignores_layout() {
+        let index = HeaderIndex::new(vec![
+            (1, "参考号 (Reference NO)".to_string()),
+            (2, "UPS 子单号\n(Tracking Number)".to_string()),
+        ]);
+        let mapping = |header: &str| ColumnMapping {
+            source: String::new(),
+            header: header.to_string(),
+            aliases: vec!["Ref".to_string()],
+        };
+
+        assert_eq!(index.find(&mapping("参考号\n（Reference NO)")), Some(1));
+        assert_eq!(index.find(&mapping("UPS子单号(tracking number)")), Some(2));
+
+        let mappings = vec![mapping("参考号（Reference NO）"), mapping("提单号")];
+        let (found, missing) = index.resolve(&mappings);
+        assert_eq!(found.len(), 1);
+        assert_eq!(missing, vec!["提单号"]);
+    }
+
+    #[test]
+    fn test_parse_column() {
+        assert_eq!(parse_column("F"), Some(6));
+        assert_eq!(parse_column(" aa "), Some(27));
+        assert_eq!(parse_column("F4"), None);
+        assert_eq!(parse_column(""), None);
+        assert_eq!(column_letter(6), "F");
+        assert_eq!(column_letter(27), "AA");
+        assert_eq!(parse_column(&column_letter(703)), Some(703));
+    }
+
+    #[test]
+    fn test_resolve_prefers_sibling_descriptor() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("custom.xlsx");
+        let mut config = TemplateConfig::default_for_type(TemplateType::Dpd);
+
+        // 没有描述文件时使用内置描述
+        assert!(resolve(&config, &template).unwrap().sheets.contains_key("list"));
+
+        fs::write(
+            dir.path().join("custom.json"),
+            r#"{"version":1,"template_type":"dpd","sheets":{"list":{"names":["Sheet1"],"header_row":2}}}"#,
+        )
+        .unwrap();
+        let descriptor = resolve(&config, &template).unwrap();
+        assert_eq!(descriptor.sheet("list").unwrap().header_row, 2);
+        assert!(descriptor.sheet("summary").is_err());
+
+        // 类型不一致、版本过高时报错
+        config.template_type = TemplateType::Ups;
+        assert!(resolve(&config, &template).is_err());
+        assert!(parse(r#"{"version":99,"template_type":"ups","sheets":{}}"#).is_err());
+    }
+}
@@ -1,57 +1,45 @@
 use crate::commands::error::CommandError;
-use crate::models::ups_dpd::{CellValue, ExcelDataFrame, ExcelRow};
-use calamine::{open_workbook_auto, Reader};
-use rust_xlsxwriter::Worksheet;
-use std::collections::HashMap;
+use crate::commands::ups_dpd::descriptor::normalize_header;
+use crate::models::ups_dpd::{CellValue, ExcelDataFrame, ExcelRow, RowOrigin};
+use crate::core::tabular::{TabularCell, TabularSheet, TabularWorkbook};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
-/// 从 Excel 文件读取数据
+/// 从 Excel 文件读取数据（支持 xlsx / xls / ods / csv，自动检测表头行）
 pub fn read_excel_file(
     file_path: &Path,
     sheet_index: usize,
 ) -> Result<ExcelDataFrame, CommandError> {
-    let mut workbook = open_workbook_auto(file_path)
-        .map_err(|e| CommandError::new(format!("无法打开 Excel 文件: {}", e), "ERROR"))?;
-
-    let sheet_names = workbook.sheet_names().to_vec();
-
-    if sheet_index >= sheet_names.len() {
-        return Err(CommandError::new(
-            format!(
-                "工作表索引 {} 超出范围（共 {} 个工作表）",
-                sheet_index,
-                sheet_names.len()
-            ),
-            "ERROR"
-        ));
-    }
-
-    let sheet_name = &sheet_names[sheet_index];
-
-    let range = workbook
-        .worksheet_range(sheet_name)
-        .map_err(|e| CommandError::new(format!("无法读取工作表: {}", e), "ERROR"))?;
+    let mut workbook = TabularWorkbook::open(file_path)?;
+    let sheet = workbook.sheet_at(sheet_index)?;
 
-    // 获取表头（第一行）
-    let mut columns = Vec::new();
-    if let Some(first_row) = range.rows().next() {
-        for cell in first_row {
-            columns.push(datatype_to_string(cell));
-        }
-    } else {
+    if sheet.height() == 0 {
         return Err(CommandError::new("工作表为空", "ERROR"));
     }
 
+    Ok(sheet_to_dataframe(&sheet, &file_label(file_path)))
+}
+
+/// 工作表转为数据帧，每行记录来源（source）和源文件中的行号
+fn sheet_to_dataframe(sheet: &TabularSheet, source: &str) -> ExcelDataFrame {
+    // 表头上方可能有标题、说明等行
+    let header_row = sheet.detect_header_row();
+    let columns = sheet.headers(header_row);
+
     let mut dataframe = ExcelDataFrame::new(columns.clone());
 
     // 读取数据行（跳过表头）
-    for row_data in range.rows().skip(1) {
+    for (offset, row_data) in sheet.rows().skip(header_row + 1).enumerate() {
         let mut row = ExcelRow::new();
+        row.origin = Some(RowOrigin {
+            source: source.to_string(),
+            row: header_row + offset + 2,
+        });
 
         for (col_idx, cell) in row_data.iter().enumerate() {
             if col_idx < columns.len() {
                 let column_name = &columns[col_idx];
-                let value = datatype_to_cellvalue(cell);
+                let value = tabular_to_cellvalue(cell);
                 row.set(column_name.clone(), value);
             }
         }
@@ -59,198 +47,198 @@ pub fn read_excel_file(
         dataframe.add_row(row);
     }
 
-    Ok(dataframe)
-}
-
-/// 将 DataType 转换为字符串
-fn datatype_to_string(data: &calamine::Data) -> String {
-    match data {
-        calamine::Data::Empty => String::new(),
-        calamine::Data::String(s) => s.clone(),
-        calamine::Data::Float(f) => f.to_string(),
-        calamine::Data::Int(i) => i.to_string(),
-        calamine::Data::Bool(b) => b.to_string(),
-        calamine::Data::DateTime(dt) => dt.to_string(),
-        calamine::Data::Error(e) => format!("Error: {:?}", e),
-        calamine::Data::DateTimeIso(dt) => dt.to_string(),
-        calamine::Data::DurationIso(d) => d.to_string(),
-    }
-}
-
-/// 将 DataType 转换为 CellValue
-fn datatype_to_cellvalue(data: &calamine::Data) -> CellValue {
-    match data {
-        calamine::Data::Empty => CellValue::Empty,
-        calamine::Data::String(s) => CellValue::String(s.clone()),
-        calamine::Data::Float(f) => CellValue::Number(*f),
-        calamine::Data::Int(i) => CellValue::Integer(*i),
-        calamine::Data::Bool(b) => CellValue::Boolean(*b),
-        calamine::Data::DateTime(dt) => CellValue::String(dt.to_string()),
-        calamine::Data::Error(e) => CellValue::String(format!("Error: {:?}", e)),
-        calamine::Data::DateTimeIso(dt) => CellValue::String(dt.to_string()),
-        calamine::Data::DurationIso(d) => CellValue::String(d.to_string()),
-    }
+    dataframe
 }
 
-/// 获取工作簿的所有工作表名称
-pub fn get_sheet_names(file_path: &Path) -> Result<Vec<String>, CommandError> {
-    let workbook = open_workbook_auto(file_path)
-        .map_err(|e| CommandError::new(format!("无法打开 Excel 文件: {}", e), "ERROR"))?;
-
-    Ok(workbook.sheet_names().to_vec())
-}
-
-/// 查找工作表中的表头行并建立列映射
-pub fn get_header_column_mapping(
-    file_path: &Path,
-    sheet_name: &str,
-    header_row: usize,
-) -> Result<HashMap<String, usize>, CommandError> {
-    let mut workbook = open_workbook_auto(file_path)
-        .map_err(|e| CommandError::new(format!("无法打开 Excel 文件: {}", e), "ERROR"))?;
-
-    let range = workbook
-        .worksheet_range(sheet_name)
-        .map_err(|e| CommandError::new(format!("无法读取工作表: {}", e), "ERROR"))?;
-
-    let mut mapping = HashMap::new();
+/// 读取并合并多个源文件（WMS 按仓库分别导出时）
+///
+/// 每个文件读取第一个工作表，all_sheets 时读取所有工作表（跳过空表和没有 key_column 列的表）。
+/// 列按表头对齐（忽略空白、全角和大小写差异，沿用第一次出现的写法），
+/// key_column 与前面来源重复的行跳过；同一来源内的重复留给校验报告。
+/// 返回合并后的数据和读取日志。
+pub fn read_sources(
+    paths: &[String],
+    all_sheets: bool,
+    key_column: &str,
+) -> Result<(ExcelDataFrame, Vec<String>), CommandError> {
+    let mut sources: Vec<(String, ExcelDataFrame)> = Vec::new();
+    let mut logs = Vec::new();
+
+    for path in paths {
+        let path = Path::new(path);
+        if !all_sheets {
+            sources.push((file_label(path), read_excel_file(path, 0)?));
+            continue;
+        }
 
-    if let Some(row_data) = range.rows().nth(header_row) {
-        for (col_idx, cell) in row_data.iter().enumerate() {
-            let header = datatype_to_string(cell).trim().to_string();
-            if !header.is_empty() {
-                mapping.insert(header, col_idx);
+        let mut workbook = TabularWorkbook::open(path)?;
+        for name in workbook.sheet_names() {
+            let label = format!("{} / {}", file_label(path), name);
+            let sheet = workbook.sheet(&name)?;
+            if sheet.height() == 0 {
+                logs.push(format!("跳过 {}: 工作表为空", label));
+                continue;
+            }
+            let data = sheet_to_dataframe(&sheet, &label);
+            let key = normalize_header(key_column);
+            if !data.columns.iter().any(|c| normalize_header(c) == key) {
+                logs.push(format!("跳过 {}: 没有 {} 列", label, key_column));
+                continue;
             }
+            sources.push((label, data));
         }
     }
 
-    Ok(mapping)
-}
-
-/// 查找工作表中第一个完全空白的行
-pub fn find_first_empty_row(
-    file_path: &Path,
-    sheet_name: &str,
-) -> Result<usize, CommandError> {
-    let mut workbook = open_workbook_auto(file_path)
-        .map_err(|e| CommandError::new(format!("无法打开 Excel 文件: {}", e), "ERROR"))?;
-
-    let range = workbook
-        .worksheet_range(sheet_name)
-        .map_err(|e| CommandError::new(format!("无法读取工作表: {}", e), "ERROR"))?;
-
-    for (row_idx, row_data) in range.rows().enumerate() {
-        let is_empty = row_data.iter().all(|cell| matches!(cell, calamine::Data::Empty));
-        if is_empty {
-            return Ok(row_idx);
-        }
+    if sources.is_empty() {
+        return Err(CommandError::new("没有可读取的数据", "ERROR"));
     }
 
-    // 如果没有找到空行，返回最后一行的下一行
-    Ok(range.height())
-}
-
-/// 将 CellValue 写入工作表
-pub fn write_cell_value(
-    worksheet: &mut Worksheet,
-    row: u32,
-    col: u16,
-    value: &CellValue,
-) -> Result<(), CommandError> {
-    match value {
-        CellValue::Empty => Ok(()),
-        CellValue::String(s) => worksheet
-            .write_string(row, col, s)
-            .map_err(|e| CommandError::new(format!("写入字符串失败: {}", e), "ERROR"))
-            .map(|_| ()),
-        CellValue::Number(n) => worksheet
-            .write_number(row, col, *n)
-            .map_err(|e| CommandError::new(format!("写入数字失败: {}", e), "ERROR"))
-            .map(|_| ()),
-        CellValue::Integer(i) => worksheet
-            .write_number(row, col, *i as f64)
-            .map_err(|e| CommandError::new(format!("写入整数失败: {}", e), "ERROR"))
-            .map(|_| ()),
-        CellValue::Boolean(b) => worksheet
-            .write_boolean(row, col, *b)
-            .map_err(|e| CommandError::new(format!("写入布尔值失败: {}", e), "ERROR"))
-            .map(|_| ()),
-    }
+    let merged = merge_sources(sources, key_column, &mut logs);
+    Ok((merged, logs))
 }
 
-/// 按指定列分组统计
-pub fn group_by_column(
-    dataframe: &ExcelDataFrame,
-    group_column: &str,
-    sum_columns: &[&str],
-) -> Result<HashMap<String, HashMap<String, f64>>, CommandError> {
-    let mut groups: HashMap<String, HashMap<String, f64>> = HashMap::new();
-
-    for row in &dataframe.rows {
-        // 获取分组键
-        let group_key = row
-            .get(group_column)
-            .ok_or_else(|| {
-                CommandError::new(format!("找不到分组列: {}", group_column), "ERROR")
-            })?
-            .to_string()
-            .trim()
-            .to_uppercase();
-
-        if group_key.is_empty() || group_key == "NAN" || group_key == "NONE" {
-            continue;
-        }
-
-        // 获取或创建该分组的统计数据
-        let stats = groups.entry(group_key).or_insert_with(HashMap::new);
+/// 按表头对齐合并多个来源，跳过与前面来源重复的 key_column
+fn merge_sources(
+    sources: Vec<(String, ExcelDataFrame)>,
+    key_column: &str,
+    logs: &mut Vec<String>,
+) -> ExcelDataFrame {
+    let source_count = sources.len();
+    let mut merged = ExcelDataFrame::new(Vec::new());
+    // 规范化表头 -> 合并后的列名
+    let mut canonical: HashMap<String, String> = HashMap::new();
+    let mut seen_keys: HashSet<String> = HashSet::new();
+    let mut skipped = 0;
+
+    for (label, data) in sources {
+        let renames: HashMap<String, String> = data
+            .columns
+            .iter()
+            .filter(|c| !c.is_empty())
+            .map(|column| {
+                let name = canonical
+                    .entry(normalize_header(column))
+                    .or_insert_with(|| {
+                        merged.columns.push(column.clone());
+                        column.clone()
+                    })
+                    .clone();
+                (column.clone(), name)
+            })
+            .collect();
+
+        let mut source_keys = HashSet::new();
+        let mut duplicates = 0;
+        let total = data.len();
+        for row in data.rows {
+            let mut aligned = ExcelRow::new();
+            aligned.origin = row.origin;
+            for (column, value) in row.data {
+                if let Some(name) = renames.get(&column) {
+                    aligned.set(name.clone(), value);
+                }
+            }
 
-        // 累加各个列的值
-        for &col in sum_columns {
-            if let Some(cell_value) = row.get(col) {
-                if let Some(num) = cell_value.to_f64() {
-                    *stats.entry(col.to_string()).or_insert(0.0) += num;
+            let key = aligned.get(key_column).map(|v| v.to_string().trim().to_string()).unwrap_or_default();
+            if !key.is_empty() {
+                if seen_keys.contains(&key) {
+                    duplicates += 1;
+                    continue;
                 }
+                source_keys.insert(key);
             }
+            merged.add_row(aligned);
+        }
+        seen_keys.extend(source_keys);
+        skipped += duplicates;
+
+        if duplicates > 0 {
+            logs.push(format!("读取 {}: {} 行（跳过与前面文件重复的{} {} 行）", label, total, key_column, duplicates));
+        } else {
+            logs.push(format!("读取 {}: {} 行", label, total));
         }
     }
 
-    Ok(groups)
-}
+    if source_count > 1 {
+        logs.push(format!(
+            "合并 {} 个来源: 共 {} 行，{} 列，去重 {} 行",
+            source_count,
+            merged.len(),
+            merged.columns.len(),
+            skipped
+        ));
+    }
 
-/// 筛选数据帧
-pub fn filter_dataframe(
-    dataframe: &ExcelDataFrame,
-    filter_column: &str,
-    filter_value: &str,
-) -> Result<ExcelDataFrame, CommandError> {
-    let mut filtered = ExcelDataFrame::new(dataframe.columns.clone());
+    merged
+}
 
-    let filter_value_upper = filter_value.trim().to_uppercase();
+/// 日志和校验报告中显示的文件名
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
 
-    for row in &dataframe.rows {
-        if let Some(cell_value) = row.get(filter_column) {
-            let cell_str = cell_value.to_string().trim().to_uppercase();
-            if cell_str == filter_value_upper {
-                filtered.add_row(row.clone());
-            }
-        }
+/// 将表格单元格转换为 CellValue
+fn tabular_to_cellvalue(cell: &TabularCell) -> CellValue {
+    match cell {
+        TabularCell::Empty => CellValue::Empty,
+        TabularCell::Text(s) => CellValue::String(s.clone()),
+        TabularCell::Number(f) => CellValue::Number(*f),
+        TabularCell::Integer(i) => CellValue::Integer(*i),
+        TabularCell::Bool(b) => CellValue::Boolean(*b),
+        TabularCell::DateTime(dt) => CellValue::String(dt.clone()),
+        TabularCell::Error(e) => CellValue::String(format!("Error: {}", e)),
     }
-
-    Ok(filtered)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use calamine::Data;
 
     #[test]
     fn test_datatype_conversion() {
-        let dt = Data::String("test".to_string());
-        assert_eq!(datatype_to_string(&dt), "test");
+        let cell = TabularCell::Text("test".to_string());
+        assert_eq!(tabular_to_cellvalue(&cell).to_string(), "test");
 
-        let cv = datatype_to_cellvalue(&dt);
-        assert_eq!(cv.to_string(), "test");
+        let cell = TabularCell::Text("00123".to_string());
+        assert_eq!(tabular_to_cellvalue(&cell).to_string(), "00123");
+    }
+
+    #[test]
+    fn test_read_csv_skips_title_rows() {
+        let mut file = tempfile::Builder::new().suffix(".csv").tempfile().unwrap();
+        std::io::Write::write_all(&mut file, "出货明细\nCountry,Weight,Zip\nDE,1.5,01067\n".as_bytes()).unwrap();
+
+        let df = read_excel_file(file.path(), 0).unwrap();
+        assert_eq!(df.columns, vec!["Country", "Weight", "Zip"]);
+        assert_eq!(df.len(), 1);
+        assert_eq!(df.rows[0].get("Zip").unwrap().to_string(), "01067");
+        assert_eq!(df.rows[0].get("Weight").unwrap().to_f64(), Some(1.5));
+    }
+
+    #[test]
+    fn test_merge_sources_aligns_headers_and_dedupes() {
+        let write = |content: &str| {
+            let mut file = tempfile::Builder::new().suffix(".csv").tempfile().unwrap();
+            std::io::Write::write_all(&mut file, content.as_bytes()).unwrap();
+            file
+        };
+        let first = write("转单号,国家二字码,件数\n1Z001,DE,1\n1Z002,FR,2\n");
+        let second = write("件数, 转单号 ,收件人邮编\n3,1Z002,75001\n4,1Z003,10115\n");
+        let paths = [first.path(), second.path()].map(|p| p.to_string_lossy().to_string());
+
+        let (df, logs) = read_sources(&paths, false, "转单号").unwrap();
+        assert_eq!(df.columns, vec!["转单号", "国家二字码", "件数", "收件人邮编"]);
+        let tracking: Vec<String> = df.rows.iter().map(|r| r.get("转单号").unwrap().to_string()).collect();
+        assert_eq!(tracking, vec!["1Z001", "1Z002", "1Z003"]);
+        assert_eq!(df.rows[2].get("收件人邮编").unwrap().to_string(), "10115");
+
+        let origin = df.rows[2].origin.as_ref().unwrap();
+        assert_eq!(origin.source, file_label(second.path()));
+        assert_eq!(origin.row, 3);
+        assert_eq!(logs.len(), 3);
+        assert!(logs[1].contains("1 行"), "{}", logs[1]);
     }
 
     #[test]
@@ -0,0 +1,124 @@
+// ============================================================================
+// 黄金文件回归测试：用内置模板和固定源数据生成输出，逐个比较所有填充的单元格
+// ============================================================================
+//
+// 源数据在 tests/fixtures/ups_dpd/main.csv、detail.csv，期望结果在 <模板类型>.golden.tsv，
+// 每行一个与模板不同的单元格：「工作表!单元格 类型 值」。模板、模板描述或填充逻辑变化导致
+// 输出变化时测试失败；确认变化符合预期后用 UPDATE_GOLDEN=1 cargo test golden 重新生成。
+
+use super::descriptor::{self, column_letter};
+use super::{carrier, excel_utils};
+use crate::core::tabular::{TabularCell, TabularSheet, TabularWorkbook};
+use crate::models::ups_dpd::TemplateType;
+use std::path::{Path, PathBuf};
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture(name: &str) -> PathBuf {
+    manifest_dir().join("tests/fixtures/ups_dpd").join(name)
+}
+
+/// 填充内置模板，返回与模板不同的所有单元格
+fn render(template_type: &TemplateType) -> Vec<String> {
+    let template = manifest_dir().join("resources/templates").join(template_type.template_file_name());
+    let main = excel_utils::read_excel_file(&fixture("main.csv"), 0).unwrap();
+    let detail = excel_utils::read_excel_file(&fixture("detail.csv"), 0).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("output.xlsx");
+    let descriptor = descriptor::builtin(template_type).unwrap();
+    carrier::process(template_type, descriptor, &main, Some(&detail), &template, &output).unwrap();
+
+    let mut before = TabularWorkbook::open(&template).unwrap();
+    let mut after = TabularWorkbook::open(&output).unwrap();
+    let mut lines = Vec::new();
+    for name in after.sheet_names() {
+        let filled = after.sheet(&name).unwrap();
+        let original = before.sheet(&name).ok();
+        lines.extend(changed_cells(&filled, original.as_ref()));
+    }
+    lines
+}
+
+/// 与模板工作表（新建的工作表为 None）相比变化的单元格
+fn changed_cells(filled: &TabularSheet, original: Option<&TabularSheet>) -> Vec<String> {
+    let height = filled.height().max(original.map_or(0, |s| s.height()));
+    let width = filled.width().max(original.map_or(0, |s| s.width()));
+    let mut lines = Vec::new();
+
+    for row in 0..height {
+        for col in 0..width {
+            let cell = filled.cell(row, col);
+            let before = original.map_or(&TabularCell::Empty, |s| s.cell(row, col));
+            if describe(cell) != describe(before) {
+                lines.push(format!("{}!{}{}\t{}", filled.name, column_letter(col as u32 + 1), row + 1, describe(cell)));
+            }
+        }
+    }
+    lines
+}
+
+/// 单元格类型和值（数字保留 6 位小数，避免浮点累加误差）
+fn describe(cell: &TabularCell) -> String {
+    match cell {
+        TabularCell::Empty => "empty\t".to_string(),
+        TabularCell::Text(s) => format!("text\t{}", s.replace('\n', "\\n")),
+        TabularCell::Integer(_) | TabularCell::Number(_) => {
+            let n = cell.as_f64().unwrap_or_default();
+            format!("number\t{}", (n * 1e6).round() / 1e6)
+        }
+        other => format!("other\t{}", other.as_text()),
+    }
+}
+
+fn check_golden(template_type: TemplateType) {
+    let actual = render(&template_type);
+    let path = fixture(&format!("{}.golden.tsv", template_type.to_string()));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual.join("\n") + "\n").unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("读取 {:?} 失败: {}（UPDATE_GOLDEN=1 可生成）", path, e));
+    let expected: Vec<&str> = expected.lines().collect();
+
+    let missing: Vec<&&str> = expected.iter().filter(|line| !actual.iter().any(|a| a == *line)).collect();
+    let unexpected: Vec<&String> = actual.iter().filter(|line| !expected.contains(&line.as_str())).collect();
+    assert!(
+        missing.is_empty() && unexpected.is_empty(),
+        "{} 输出与 {:?} 不一致\n缺少:\n{:#?}\n多出:\n{:#?}",
+        template_type.label(),
+        path,
+        missing,
+        unexpected
+    );
+}
+
+#[test]
+fn test_ups_golden() {
+    check_golden(TemplateType::Ups);
+}
+
+#[test]
+fn test_dpd_golden() {
+    check_golden(TemplateType::Dpd);
+}
+
+#[test]
+fn test_gls_golden() {
+    check_golden(TemplateType::Gls);
+}
+
+#[test]
+fn test_fedex_golden() {
+    check_golden(TemplateType::Fedex);
+}
+
+#[test]
+fn test_royal_mail_golden() {
+    check_golden(TemplateType::RoyalMail);
+}
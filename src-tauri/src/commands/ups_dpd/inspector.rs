@@ -0,0 +1,175 @@
+// ============================================================================
+// 模板检查：用 umya 加载模板，按模板描述检查工作表、表头和分桶单元格
+// ============================================================================
+//
+// 与处理时的查找逻辑一致（carrier::find_sheet / header_index / find_first_empty_row），
+// 在配置自定义模板时就发现找不到工作表、表头行不对等问题，而不是等到处理时才出错。
+
+use umya_spreadsheet::*;
+use crate::commands::error::CommandError;
+use crate::commands::ups_dpd::carrier::{find_first_empty_row, header_index};
+use crate::commands::ups_dpd::descriptor::{self, column_letter};
+use crate::commands::ups_dpd::{buckets, template_manager};
+use crate::models::ups_dpd::{
+    MappedField, SheetDescriptor, SheetInspection, TemplateConfig, TemplateDescriptor, TemplateInspection,
+};
+
+/// 检查配置对应的模板和模板描述
+pub fn inspect(config: &TemplateConfig) -> Result<TemplateInspection, CommandError> {
+    let template_path = template_manager::template_path_for(config)?;
+    let descriptor = descriptor::resolve(config, &template_path)?;
+    let descriptor_label = descriptor::descriptor_path(config, &template_path)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "内置描述".to_string());
+
+    let workbook = reader::xlsx::read(&template_path)
+        .map_err(|e| CommandError::new(format!("无法加载模板: {}", e), "TEMPLATE_ERROR"))?;
+
+    let mut inspection = inspect_workbook(&descriptor, &workbook);
+    inspection.template_path = template_path.to_string_lossy().to_string();
+    inspection.descriptor = descriptor_label;
+    inspection.default_version = template_manager::default_template_version(&config.template_type)?;
+    inspection.warnings.extend(template_manager::outdated_warning(&descriptor));
+    Ok(inspection)
+}
+
+/// 按模板描述检查已加载的模板
+pub fn inspect_workbook(descriptor: &TemplateDescriptor, workbook: &Spreadsheet) -> TemplateInspection {
+    let mut inspection = TemplateInspection {
+        template_path: String::new(),
+        descriptor: String::new(),
+        template_version: descriptor.template_version,
+        default_version: descriptor.template_version,
+        sheet_names: workbook.get_sheet_collection().iter().map(|s| s.get_name().to_string()).collect(),
+        sheets: Vec::new(),
+        errors: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    for (key, layout) in &descriptor.sheets {
+        let sheet = layout.names.iter().find_map(|name| workbook.get_sheet_by_name(name));
+        let Some(sheet) = sheet else {
+            // 处理时会退回第一个工作表，数据会填到错误的位置
+            inspection.errors.push(format!("找不到工作表 {}（{}）", layout.names.join(" / "), key));
+            inspection.sheets.push(sheet_result(key, layout, None));
+            continue;
+        };
+
+        let result = inspect_sheet(key, layout, sheet);
+        let name = sheet.get_name();
+        if !result.missing_cells.is_empty() {
+            inspection.errors.push(format!("工作表 {} 中找不到分桶单元格 {}", name, result.missing_cells.join("、")));
+        }
+        if result.mapped.is_empty() && !result.unmapped.is_empty() {
+            inspection.errors.push(format!(
+                "工作表 {} 第 {} 行找不到任何表头，请检查表头行或模板描述",
+                name, layout.header_row
+            ));
+        } else if !result.unmapped.is_empty() {
+            inspection.warnings.push(format!(
+                "工作表 {} 第 {} 行找不到表头 {}，对应数据不会填入",
+                name,
+                layout.header_row,
+                result.unmapped.join("、")
+            ));
+        }
+
+        inspection.sheets.push(result);
+    }
+
+    inspection
+}
+
+fn sheet_result(key: &str, layout: &SheetDescriptor, found: Option<&str>) -> SheetInspection {
+    SheetInspection {
+        key: key.to_string(),
+        expected_names: layout.names.clone(),
+        found: found.map(str::to_string),
+        header_row: None,
+        mapped: Vec::new(),
+        unmapped: Vec::new(),
+        first_empty_row: None,
+        missing_cells: Vec::new(),
+    }
+}
+
+fn inspect_sheet(key: &str, layout: &SheetDescriptor, sheet: &Worksheet) -> SheetInspection {
+    let mut result = sheet_result(key, layout, Some(sheet.get_name()));
+
+    // 分桶工作表写入固定单元格，没有表头
+    if let Some(bucket_layout) = &layout.buckets {
+        result.missing_cells = buckets::target_cells(bucket_layout)
+            .into_iter()
+            .filter(|cell| sheet.get_cell(cell.position).is_none())
+            .map(|cell| format!("{}（{}）", cell.reference, cell.label))
+            .collect();
+        return result;
+    }
+
+    let headers = header_index(sheet, layout.header_row);
+    for mapping in layout.columns.iter().chain(&layout.lookup_columns) {
+        match headers.find(mapping) {
+            Some(col) => result.mapped.push(MappedField {
+                source: mapping.source.clone(),
+                header: mapping.header.clone(),
+                column: column_letter(col),
+            }),
+            None => result.unmapped.push(mapping.header.clone()),
+        }
+    }
+    result.header_row = Some(layout.header_row);
+    result.first_empty_row = Some(find_first_empty_row(sheet, layout.header_row));
+    result
+}
+
+/// 检查模板，有错误时返回错误（有错误的模板不能保存到配置）
+pub fn ensure_usable(config: &TemplateConfig) -> Result<TemplateInspection, CommandError> {
+    let inspection = inspect(config)?;
+    if !inspection.errors.is_empty() {
+        return Err(CommandError::new(
+            format!("模板检查未通过:\n{}", inspection.errors.join("\n")),
+            "TEMPLATE_ERROR",
+        ));
+    }
+    Ok(inspection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ups_dpd::TemplateType;
+
+    #[test]
+    fn test_inspect_reports_sheets_and_headers() {
+        let descriptor = descriptor::builtin(&TemplateType::Ups).unwrap();
+        let mut workbook = new_file();
+
+        // 总结单：表头在第 5 行，缺少 ZIP code 列，第 6 行已有数据
+        let summary = workbook.new_sheet("Summary").unwrap();
+        for (col, header) in ["Tracking Number", "Packages", "G.W", "V.G", "country"].iter().enumerate() {
+            summary.get_cell_mut((col as u32 + 1, 5)).set_value_string(*header);
+        }
+        summary.get_cell_mut((1, 6)).set_value_string("1Z001");
+        // 运单信息：表头行不对
+        workbook.new_sheet("运单信息").unwrap().get_cell_mut((1, 3)).set_value_string("参考号");
+
+        let inspection = inspect_workbook(&descriptor, &workbook);
+        assert_eq!(inspection.sheet_names, vec!["Sheet1", "Summary", "运单信息"]);
+
+        let summary = inspection.sheets.iter().find(|s| s.key == "summary").unwrap();
+        assert_eq!(summary.found.as_deref(), Some("Summary"));
+        assert_eq!(summary.header_row, Some(5));
+        assert_eq!(summary.mapped.len(), 5);
+        assert_eq!(summary.mapped[4].column, "E");
+        assert_eq!(summary.unmapped, vec!["ZIP code"]);
+        assert_eq!(summary.first_empty_row, Some(7));
+
+        let sub_order = inspection.sheets.iter().find(|s| s.key == "sub_order").unwrap();
+        assert_eq!(sub_order.found, None);
+
+        // 找不到的统计、德国邮编、子单号工作表，以及运单信息表头行不对
+        assert_eq!(inspection.errors.len(), 4, "{:?}", inspection.errors);
+        assert!(inspection.errors.iter().any(|e| e.contains("运单信息 第 1 行找不到任何表头")));
+        assert_eq!(inspection.warnings.len(), 1);
+    }
+}
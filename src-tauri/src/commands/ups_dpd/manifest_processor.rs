@@ -0,0 +1,50 @@
+use crate::commands::error::CommandError;
+use crate::commands::ups_dpd::carrier::{CarrierProcessor, TemplateFiller};
+use crate::commands::ups_dpd::weights;
+use crate::models::ups_dpd::{DataSource, ExcelDataFrame};
+use umya_spreadsheet::*;
+
+/// 完全由模板描述驱动的清单填充（GLS、FedEx、Royal Mail/Parcelforce）
+///
+/// 描述中的每个工作表按键名顺序处理（各工作表互不影响）：配置了分桶的按主数据统计件数；
+/// data_source 为 detail 的逐行填入明细数据（lookup_columns 从主数据匹配）；其余逐行填入主数据。
+/// 最后按分桶和国家汇总重量并对账。
+pub struct ManifestProcessor;
+
+impl CarrierProcessor for ManifestProcessor {
+    fn fill(
+        &self,
+        filler: &mut TemplateFiller,
+        workbook: &mut Spreadsheet,
+        main_data: &ExcelDataFrame,
+        detail_data: Option<&ExcelDataFrame>,
+    ) -> Result<(), CommandError> {
+        let sheets: Vec<_> = filler
+            .descriptor()
+            .sheets
+            .iter()
+            .map(|(key, sheet)| (key.clone(), sheet.buckets.is_some(), sheet.data_source))
+            .collect();
+
+        for (key, has_buckets, data_source) in sheets {
+            filler.log(format!("处理工作表 {}", key));
+
+            if has_buckets {
+                filler.fill_buckets(workbook, &key, main_data)?;
+                continue;
+            }
+
+            match (data_source, detail_data) {
+                (DataSource::Main, _) => filler.fill_rows(workbook, &key, main_data, None)?,
+                (DataSource::Detail, Some(detail)) => filler.fill_rows(workbook, &key, detail, Some(main_data))?,
+                (DataSource::Detail, None) => filler.log(format!("未提供明细数据，跳过工作表 {}", key)),
+            }
+        }
+
+        let layout = filler.descriptor().sheets.values().find_map(|sheet| sheet.buckets.clone());
+        let report = weights::bucket_report(layout.as_ref(), main_data);
+        filler.write_weight_report(workbook, &report)?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,15 @@
 pub mod commands;
 pub mod template_manager;
+pub mod descriptor;
+pub mod buckets;
+pub mod weights;
+pub mod carrier;
+pub mod manifest_processor;
 pub mod ups_processor;
-pub mod ups_processor_v2;
 pub mod dpd_processor;
-pub mod dpd_processor_v2;
 pub mod excel_utils;
+pub mod validation;
+pub mod inspector;
+
+#[cfg(test)]
+mod golden_tests;
@@ -1,11 +1,12 @@
 use crate::commands::error::CommandError;
-use crate::models::ups_dpd::{TemplateConfig, TemplateType};
+use crate::commands::ups_dpd::descriptor;
+use crate::models::ups_dpd::{TemplateConfig, TemplateDescriptor, TemplateType};
 use serde_json;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// 获取配置文件路径
-fn get_config_path() -> Result<PathBuf, CommandError> {
+/// 应用配置目录（不存在时创建）
+fn get_app_config_dir() -> Result<PathBuf, CommandError> {
     let config_dir = dirs::config_dir()
         .ok_or_else(|| CommandError::new("无法获取配置目录", "ERROR"))?;
 
@@ -17,47 +18,93 @@ fn get_config_path() -> Result<PathBuf, CommandError> {
             .map_err(|e| CommandError::new(format!("创建配置目录失败: {}", e), "ERROR"))?;
     }
 
-    Ok(app_config_dir.join("ups-dpd-config.json"))
+    Ok(app_config_dir)
+}
+
+/// 获取配置文件路径
+fn get_config_path() -> Result<PathBuf, CommandError> {
+    Ok(get_app_config_dir()?.join("ups-dpd-config.json"))
+}
+
+const UPS_TEMPLATE: &[u8] = include_bytes!("../../../resources/templates/UPS_template.xlsx");
+const DPD_TEMPLATE: &[u8] = include_bytes!("../../../resources/templates/DPD_template.xlsx");
+const GLS_TEMPLATE: &[u8] = include_bytes!("../../../resources/templates/GLS_template.xlsx");
+const FEDEX_TEMPLATE: &[u8] = include_bytes!("../../../resources/templates/FedEx_template.xlsx");
+const ROYAL_MAIL_TEMPLATE: &[u8] = include_bytes!("../../../resources/templates/RoyalMail_template.xlsx");
+
+/// 编译时嵌入的默认模板
+fn default_template_bytes(template_type: &TemplateType) -> &'static [u8] {
+    match template_type {
+        TemplateType::Ups => UPS_TEMPLATE,
+        TemplateType::Dpd => DPD_TEMPLATE,
+        TemplateType::Gls => GLS_TEMPLATE,
+        TemplateType::Fedex => FEDEX_TEMPLATE,
+        TemplateType::RoyalMail => ROYAL_MAIL_TEMPLATE,
+    }
+}
+
+/// 当前默认模板的版本（内置描述中的 template_version）
+pub fn default_template_version(template_type: &TemplateType) -> Result<u32, CommandError> {
+    Ok(descriptor::builtin(template_type)?.template_version)
 }
 
 /// 获取默认模板路径
+///
+/// 默认模板嵌入在程序中，使用时写到配置目录的 templates 下（内容不同时覆盖），
+/// 不依赖工作目录或打包后的资源位置。
 pub fn get_default_template_path(template_type: &TemplateType) -> Result<PathBuf, CommandError> {
-    // 在开发环境中，模板在 resources/templates/ 目录
-    // 在打包后，模板会被打包到应用的 resources 目录
-
-    let template_name = match template_type {
-        TemplateType::Ups => "UPS_template.xlsx",
-        TemplateType::Dpd => "DPD_template.xlsx",
-    };
-
-    // 尝试多个可能的位置
-    let possible_paths: Vec<Option<PathBuf>> = vec![
-        // 开发环境
-        Some(PathBuf::from("resources").join("templates").join(template_name)),
-        Some(PathBuf::from("src-tauri").join("resources").join("templates").join(template_name)),
-        // 打包后的环境（相对于可执行文件）
-        std::env::current_exe()
-            .ok()
-            .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
-            .map(|p| p.join("resources").join("templates").join(template_name)),
-    ];
-
-    for path_opt in possible_paths.iter().flatten() {
-        if path_opt.exists() {
-            return Ok(path_opt.clone());
-        }
+    let path = get_app_config_dir()?
+        .join("templates")
+        .join(template_type.template_file_name());
+    let bytes = default_template_bytes(template_type);
+
+    if fs::read(&path).ok().as_deref() != Some(bytes) {
+        write_atomic(&path, bytes)?;
     }
+    Ok(path)
+}
 
-    Err(CommandError::new(
+/// 先写临时文件再重命名，批量处理并发读取时不会读到写了一半的模板
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), CommandError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| CommandError::new(format!("创建模板目录失败: {}", e), "ERROR"))?;
+    }
+    let temp_path = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    fs::write(&temp_path, bytes)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            CommandError::new(format!("写入默认模板失败: {}", e), "ERROR")
+        })
+}
+
+/// 导出默认模板供编辑，旁边写入同名 .json 描述（记录模板版本，处理时自动使用）
+pub fn export_default_template(template_type: &TemplateType, target: &Path) -> Result<PathBuf, CommandError> {
+    let target = target.with_extension("xlsx");
+    fs::write(&target, default_template_bytes(template_type))
+        .map_err(|e| CommandError::new(format!("导出模板失败: {}", e), "ERROR"))?;
+    fs::write(target.with_extension("json"), descriptor::builtin_source(template_type))
+        .map_err(|e| CommandError::new(format!("导出模板描述失败: {}", e), "ERROR"))?;
+    Ok(target)
+}
+
+/// 自定义模板基于旧版默认模板时的提示（模板描述中的 template_version 低于当前默认模板）
+pub fn outdated_warning(descriptor: &TemplateDescriptor) -> Option<String> {
+    let current = default_template_version(&descriptor.template_type).ok()?;
+    version_warning(&descriptor.template_type, descriptor.template_version, current)
+}
+
+/// 版本未知（0）或不低于当前版本时不提示
+fn version_warning(template_type: &TemplateType, version: u32, current: u32) -> Option<String> {
+    (version > 0 && version < current).then(|| {
         format!(
-            "找不到默认模板文件: {}\n\n请执行以下操作之一：\n\
-            1. 将模板文件放置到: src-tauri/resources/templates/{}\n\
-            2. 在「模板设置」页面选择自定义模板文件\n\
-            3. 如需创建模板，参考: src-tauri/resources/templates/README.md",
-            template_name, template_name
-        ),
-        "TEMPLATE_NOT_FOUND"
-    ))
+            "自定义模板基于 {} 默认模板 v{}，当前默认模板为 v{}，建议导出新版默认模板对照更新",
+            template_type.label(),
+            version,
+            current
+        )
+    })
 }
 
 /// 加载配置
@@ -66,10 +113,7 @@ pub fn load_config() -> Result<Vec<TemplateConfig>, CommandError> {
 
     if !config_path.exists() {
         // 如果配置文件不存在，返回默认配置
-        return Ok(vec![
-            TemplateConfig::default_for_type(TemplateType::Ups),
-            TemplateConfig::default_for_type(TemplateType::Dpd),
-        ]);
+        return Ok(TemplateType::ALL.into_iter().map(TemplateConfig::default_for_type).collect());
     }
 
     let content = fs::read_to_string(&config_path)
@@ -94,19 +138,14 @@ pub fn save_config(configs: &[TemplateConfig]) -> Result<(), CommandError> {
     Ok(())
 }
 
-/// 获取指定类型的配置
+/// 获取指定类型的配置（旧配置文件中没有的承运商使用默认配置）
 pub fn get_config_for_type(template_type: &TemplateType) -> Result<TemplateConfig, CommandError> {
     let configs = load_config()?;
 
-    configs
+    Ok(configs
         .into_iter()
         .find(|c| &c.template_type == template_type)
-        .ok_or_else(|| {
-            CommandError::new(
-                format!("找不到 {} 模板的配置", template_type.to_string()),
-                "ERROR"
-            )
-        })
+        .unwrap_or_else(|| TemplateConfig::default_for_type(template_type.clone())))
 }
 
 /// 保存指定类型的配置
@@ -128,14 +167,17 @@ pub fn save_config_for_type(config: &TemplateConfig) -> Result<(), CommandError>
 
 /// 获取模板文件路径（根据配置）
 pub fn get_template_path(template_type: &TemplateType) -> Result<PathBuf, CommandError> {
-    let config = get_config_for_type(template_type)?;
+    template_path_for(&get_config_for_type(template_type)?)
+}
 
+/// 配置对应的模板文件路径（未保存的配置也可以使用，如保存前检查）
+pub fn template_path_for(config: &TemplateConfig) -> Result<PathBuf, CommandError> {
     if config.use_default {
         // 使用默认模板
-        get_default_template_path(template_type)
-    } else if let Some(custom_path) = config.template_path {
+        get_default_template_path(&config.template_type)
+    } else if let Some(custom_path) = &config.template_path {
         // 使用自定义模板
-        let path = PathBuf::from(&custom_path);
+        let path = PathBuf::from(custom_path);
         if path.exists() {
             Ok(path)
         } else {
@@ -146,10 +188,16 @@ pub fn get_template_path(template_type: &TemplateType) -> Result<PathBuf, Comman
         }
     } else {
         // 配置有误，使用默认模板
-        get_default_template_path(template_type)
+        get_default_template_path(&config.template_type)
     }
 }
 
+/// 获取模板使用的描述（配置指定的描述文件、模板同目录的 .json 或内置描述）
+pub fn get_descriptor(template_type: &TemplateType, template_path: &Path) -> Result<TemplateDescriptor, CommandError> {
+    let config = get_config_for_type(template_type)?;
+    descriptor::resolve(&config, template_path)
+}
+
 /// 验证模板文件是否有效
 pub fn validate_template_file(path: &Path) -> Result<bool, CommandError> {
     // 检查文件是否存在
@@ -185,13 +233,32 @@ mod tests {
 
     #[test]
     fn test_default_config() {
-        let configs = vec![
-            TemplateConfig::default_for_type(TemplateType::Ups),
-            TemplateConfig::default_for_type(TemplateType::Dpd),
-        ];
-
-        assert_eq!(configs.len(), 2);
-        assert!(configs[0].use_default);
-        assert!(configs[1].use_default);
+        let configs: Vec<TemplateConfig> = TemplateType::ALL.into_iter().map(TemplateConfig::default_for_type).collect();
+
+        assert_eq!(configs.len(), 5);
+        assert!(configs.iter().all(|c| c.use_default));
+        assert_eq!(configs[4].template_type.template_file_name(), "RoyalMail_template.xlsx");
+    }
+
+    #[test]
+    fn test_export_default_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export_default_template(&TemplateType::Dpd, &dir.path().join("我的DPD模板")).unwrap();
+
+        assert_eq!(path.extension().unwrap(), "xlsx");
+        assert_eq!(fs::read(&path).unwrap(), DPD_TEMPLATE);
+        let exported = descriptor::load(&path.with_extension("json")).unwrap();
+        assert_eq!(exported.template_version, default_template_version(&TemplateType::Dpd).unwrap());
+        assert_eq!(outdated_warning(&exported), None);
+    }
+
+    #[test]
+    fn test_outdated_warning() {
+        assert_eq!(outdated_warning(&descriptor::builtin(&TemplateType::Ups).unwrap()), None);
+
+        assert_eq!(version_warning(&TemplateType::Ups, 0, 2), None);
+        assert_eq!(version_warning(&TemplateType::Ups, 2, 2), None);
+        let warning = version_warning(&TemplateType::Ups, 1, 2).unwrap();
+        assert!(warning.contains("v1") && warning.contains("v2"), "{}", warning);
     }
 }
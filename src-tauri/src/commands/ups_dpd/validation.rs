@@ -0,0 +1,377 @@
+// ============================================================================
+// 源数据校验：填充模板前检查主数据和明细表
+// ============================================================================
+//
+// 错误（缺列、缺国家、件数/重量不是数字、单号重复）会导致模板数据错误，阻止生成；
+// 警告（未知国家代码、德国邮编格式、子单号匹配不到主单）可由用户确认后忽略。
+
+use std::collections::{HashMap, HashSet};
+use crate::models::ups_dpd::{
+    CellValue, DataSource, ExcelDataFrame, ExcelRow, Severity, TemplateType, ValidationIssue, ValidationReport,
+};
+
+/// ISO 3166-1 二字码
+const ISO_COUNTRY_CODES: &str = "\
+    AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH BI BJ BL BM BN BO BQ \
+    BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX CY CZ DE DJ DK DM \
+    DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL GM GN GP GQ GR GS \
+    GT GU GW GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR IS IT JE JM JO JP KE KG KH KI KM KN \
+    KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM MN MO MP MQ \
+    MR MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH PK PL PM \
+    PN PR PS PT PW PY QA RE RO RS RU RW SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS ST SV \
+    SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ UA UG UM US UY UZ VA VC VE VG VI \
+    VN VU WF WS YE YT ZA ZM ZW";
+
+/// 明细表必需的列
+const DETAIL_COLUMNS: [&str; 2] = ["客户单号", "子转单号"];
+
+/// 各承运商主数据必需的列
+fn required_columns(template_type: &TemplateType) -> &'static [&'static str] {
+    match template_type {
+        // 统计工作表需要材积重
+        TemplateType::Ups => &["转单号", "客户单号", "国家二字码", "收件人邮编", "件数", "收货实重", "收货材积重"],
+        TemplateType::Dpd | TemplateType::Gls | TemplateType::Fedex | TemplateType::RoyalMail => {
+            &["转单号", "客户单号", "国家二字码", "收件人邮编", "件数", "收货实重"]
+        }
+    }
+}
+
+/// 校验主数据和明细表
+pub fn validate(
+    template_type: &TemplateType,
+    main_data: &ExcelDataFrame,
+    detail_data: Option<&ExcelDataFrame>,
+) -> ValidationReport {
+    let mut report = ReportBuilder::default();
+
+    let required = required_columns(template_type);
+    report.check_columns(DataSource::Main, main_data, required);
+    check_main_rows(&mut report, main_data);
+
+    if let Some(detail) = detail_data {
+        report.check_columns(DataSource::Detail, detail, &DETAIL_COLUMNS);
+        check_detail_rows(&mut report, detail, main_data);
+    }
+
+    report.finish()
+}
+
+fn check_main_rows(report: &mut ReportBuilder, data: &ExcelDataFrame) {
+    let has = |column: &str| data.columns.iter().any(|c| c == column);
+    let mut tracking_numbers: HashMap<String, (Option<String>, usize)> = HashMap::new();
+
+    for (idx, row) in data.rows.iter().enumerate() {
+        let (file, row_no) = locate(row, idx);
+        if row.data.values().all(is_blank) {
+            continue;
+        }
+        let issue = |column: &str, severity: Severity, message: String| ValidationIssue {
+            source: DataSource::Main,
+            file: file.clone(),
+            row: Some(row_no),
+            column: column.to_string(),
+            severity,
+            message,
+        };
+
+        // 1. 转单号：必填且不能重复
+        if has("转单号") {
+            let tracking = text(row.get("转单号"));
+            if tracking.is_empty() {
+                report.push(issue("转单号", Severity::Error, "转单号为空".to_string()));
+            } else if let Some(first) = tracking_numbers.get(&tracking) {
+                let message = format!("转单号 {} 与{}重复", tracking, position(first, &file));
+                report.push(issue("转单号", Severity::Error, message));
+            } else {
+                tracking_numbers.insert(tracking, (file.clone(), row_no));
+            }
+        }
+
+        // 2. 国家二字码：必填，应为 ISO 3166-1 代码
+        let country = text(row.get("国家二字码")).to_uppercase();
+        if has("国家二字码") {
+            if country.is_empty() || country == "NAN" {
+                report.push(issue("国家二字码", Severity::Error, "国家二字码为空".to_string()));
+            } else if !is_iso_country(&country) {
+                report.push(issue("国家二字码", Severity::Warning, format!("未知的国家代码 {}，统计时计入 Other", country)));
+            }
+        }
+
+        // 3. 件数：正整数
+        if has("件数") {
+            match row.get("件数").filter(|v| !is_blank(v)) {
+                Some(value) if !is_positive_integer(value) => {
+                    report.push(issue("件数", Severity::Error, format!("件数 \"{}\" 不是正整数", value.to_string())));
+                }
+                Some(_) => {}
+                None => report.push(issue("件数", Severity::Error, "件数为空".to_string())),
+            }
+        }
+
+        // 4. 重量：数字且不为负，实重为空时提示
+        for column in ["收货实重", "收货材积重"] {
+            if !has(column) {
+                continue;
+            }
+            match row.get(column).filter(|v| !is_blank(v)) {
+                Some(value) if !value.to_f64().is_some_and(|w| w >= 0.0) => {
+                    report.push(issue(column, Severity::Error, format!("{} \"{}\" 不是有效的重量", column, value.to_string())));
+                }
+                None if column == "收货实重" => {
+                    report.push(issue(column, Severity::Warning, "收货实重为空，按 0 统计".to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        // 5. 德国邮编：5 位数字（Excel 读成数字时前导零会丢失）
+        if country == "DE" && has("收件人邮编") {
+            let postcode = row.get("收件人邮编");
+            if !postcode.is_some_and(is_german_postcode) {
+                let shown = postcode.map(|v| v.to_string()).unwrap_or_default();
+                let message = if shown.trim().is_empty() {
+                    "德国收件人邮编为空".to_string()
+                } else {
+                    format!("德国邮编 \"{}\" 不是 5 位数字", shown)
+                };
+                report.push(issue("收件人邮编", Severity::Warning, message));
+            }
+        }
+    }
+}
+
+fn check_detail_rows(report: &mut ReportBuilder, detail: &ExcelDataFrame, main_data: &ExcelDataFrame) {
+    let has = |column: &str| detail.columns.iter().any(|c| c == column);
+    let references: HashSet<String> = main_data
+        .rows
+        .iter()
+        .map(|row| text(row.get("客户单号")))
+        .filter(|r| !r.is_empty())
+        .collect();
+    let mut sub_numbers: HashMap<String, (Option<String>, usize)> = HashMap::new();
+
+    for (idx, row) in detail.rows.iter().enumerate() {
+        let (file, row_no) = locate(row, idx);
+        if row.data.values().all(is_blank) {
+            continue;
+        }
+        let issue = |column: &str, severity: Severity, message: String| ValidationIssue {
+            source: DataSource::Detail,
+            file: file.clone(),
+            row: Some(row_no),
+            column: column.to_string(),
+            severity,
+            message,
+        };
+
+        if has("客户单号") {
+            let reference = text(row.get("客户单号"));
+            if reference.is_empty() {
+                report.push(issue("客户单号", Severity::Error, "客户单号为空".to_string()));
+            } else if !references.contains(&reference) {
+                report.push(issue("客户单号", Severity::Warning, format!("客户单号 {} 在主数据中找不到，主单号等信息不会填入", reference)));
+            }
+        }
+
+        if has("子转单号") {
+            let sub_number = text(row.get("子转单号"));
+            if sub_number.is_empty() {
+                report.push(issue("子转单号", Severity::Error, "子转单号为空".to_string()));
+            } else if let Some(first) = sub_numbers.get(&sub_number) {
+                let message = format!("子转单号 {} 与{}重复", sub_number, position(first, &file));
+                report.push(issue("子转单号", Severity::Error, message));
+            } else {
+                sub_numbers.insert(sub_number, (file.clone(), row_no));
+            }
+        }
+    }
+}
+
+/// 数据行的来源文件和行号（没有来源信息时按表头在第 1 行推算）
+fn locate(row: &ExcelRow, idx: usize) -> (Option<String>, usize) {
+    match &row.origin {
+        Some(origin) => (Some(origin.source.clone()), origin.row),
+        None => (None, idx + 2),
+    }
+}
+
+/// 重复单号首次出现的位置，与当前行同一文件时省略文件名
+fn position(first: &(Option<String>, usize), current: &Option<String>) -> String {
+    match &first.0 {
+        Some(file) if Some(file) != current.as_ref() => format!(" {} 第 {} 行", file, first.1),
+        _ => format!("第 {} 行", first.1),
+    }
+}
+
+/// 报告中的错误摘要（最多 5 条），用于阻止生成时的提示
+pub fn summarize(report: &ValidationReport, severity: Severity) -> String {
+    let items: Vec<String> = report
+        .issues
+        .iter()
+        .filter(|issue| issue.severity == severity)
+        .take(5)
+        .map(describe)
+        .collect();
+    let total = match severity {
+        Severity::Error => report.error_count,
+        Severity::Warning => report.warning_count,
+    };
+    let more = if total > items.len() { format!("\n…… 共 {} 条", total) } else { String::new() };
+    format!("{}{}", items.join("\n"), more)
+}
+
+/// 单条问题的说明，如 "主数据(仓库A.xlsx)第 5 行 [件数]: 件数为空"
+pub fn describe(issue: &ValidationIssue) -> String {
+    let source = match issue.source {
+        DataSource::Main => "主数据",
+        DataSource::Detail => "明细表",
+    };
+    let source = match &issue.file {
+        Some(file) => format!("{}({})", source, file),
+        None => source.to_string(),
+    };
+    match issue.row {
+        Some(row) => format!("{}第 {} 行 [{}]: {}", source, row, issue.column, issue.message),
+        None => format!("{} [{}]: {}", source, issue.column, issue.message),
+    }
+}
+
+#[derive(Default)]
+struct ReportBuilder {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ReportBuilder {
+    fn push(&mut self, issue: ValidationIssue) {
+        self.issues.push(issue);
+    }
+
+    /// 缺少必需列时整列报错，不再逐行检查该列
+    fn check_columns(&mut self, source: DataSource, data: &ExcelDataFrame, required: &[&str]) {
+        for column in required {
+            if !data.columns.iter().any(|c| c == column) {
+                self.push(ValidationIssue {
+                    source,
+                    file: None,
+                    row: None,
+                    column: column.to_string(),
+                    severity: Severity::Error,
+                    message: format!("缺少必需的列 {}", column),
+                });
+            }
+        }
+    }
+
+    fn finish(self) -> ValidationReport {
+        let error_count = self.issues.iter().filter(|i| i.severity == Severity::Error).count();
+        ValidationReport {
+            warning_count: self.issues.len() - error_count,
+            error_count,
+            issues: self.issues,
+        }
+    }
+}
+
+fn text(value: Option<&CellValue>) -> String {
+    value.map(|v| v.to_string().trim().to_string()).unwrap_or_default()
+}
+
+fn is_blank(value: &CellValue) -> bool {
+    value.to_string().trim().is_empty()
+}
+
+fn is_iso_country(code: &str) -> bool {
+    code.len() == 2 && ISO_COUNTRY_CODES.split_whitespace().any(|c| c == code)
+}
+
+fn is_positive_integer(value: &CellValue) -> bool {
+    match value {
+        CellValue::Integer(i) => *i > 0,
+        CellValue::Number(n) => n.fract() == 0.0 && *n > 0.0,
+        CellValue::String(s) => s.trim().parse::<i64>().is_ok_and(|i| i > 0),
+        _ => false,
+    }
+}
+
+fn is_german_postcode(value: &CellValue) -> bool {
+    match value {
+        // 数字单元格会丢失前导零（如 04347 读成 4347）
+        CellValue::Integer(i) => (1000..=99999).contains(i),
+        CellValue::Number(n) => n.fract() == 0.0 && (1000.0..=99999.0).contains(n),
+        CellValue::String(s) => {
+            let s = s.trim();
+            s.len() == 5 && s.chars().all(|c| c.is_ascii_digit())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(columns: &[&str], rows: &[&[CellValue]]) -> ExcelDataFrame {
+        let mut data = ExcelDataFrame::new(columns.iter().map(|c| c.to_string()).collect());
+        for values in rows {
+            let mut row = ExcelRow::new();
+            for (column, value) in columns.iter().zip(values.iter()) {
+                row.set(column.to_string(), value.clone());
+            }
+            data.add_row(row);
+        }
+        data
+    }
+
+    fn s(value: &str) -> CellValue {
+        CellValue::String(value.to_string())
+    }
+
+    const MAIN: [&str; 6] = ["转单号", "客户单号", "国家二字码", "收件人邮编", "件数", "收货实重"];
+
+    #[test]
+    fn test_valid_data_has_no_issues() {
+        assert_eq!(ISO_COUNTRY_CODES.split_whitespace().count(), 249);
+
+        let main = frame(&MAIN, &[
+            &[s("1Z001"), s("REF1"), s("de"), CellValue::Integer(4347), CellValue::Integer(2), CellValue::Number(12.5)],
+            &[s("1Z002"), s("REF2"), s("FR"), s("75001"), s("3"), s("8")],
+            &[CellValue::Empty, s(" "), CellValue::Empty, CellValue::Empty, CellValue::Empty, CellValue::Empty],
+        ]);
+        let detail = frame(&DETAIL_COLUMNS, &[&[s("REF1"), s("SUB1")], &[s("REF1"), s("SUB2")]]);
+
+        let report = validate(&TemplateType::Dpd, &main, Some(&detail));
+        assert_eq!(report.issues.len(), 0, "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_reports_errors_and_warnings() {
+        let main = frame(&MAIN, &[
+            &[s("1Z001"), s("REF1"), s(""), s(""), s("abc"), s("1")],
+            &[s("1Z001"), s("REF2"), s("XX"), s(""), CellValue::Number(1.5), s("-1")],
+            &[s("1Z003"), s("REF3"), s("DE"), s("123"), CellValue::Integer(1), CellValue::Empty],
+        ]);
+        let detail = frame(&DETAIL_COLUMNS, &[&[s("REF9"), s("SUB1")], &[s("REF1"), s("SUB1")]]);
+
+        let report = validate(&TemplateType::Ups, &main, Some(&detail));
+        let find = |row: Option<usize>, column: &str| {
+            report.issues.iter().find(|i| i.row == row && i.column == column).map(|i| i.severity)
+        };
+
+        // UPS 需要材积重列
+        assert_eq!(find(None, "收货材积重"), Some(Severity::Error));
+        assert_eq!(find(Some(2), "国家二字码"), Some(Severity::Error));
+        assert_eq!(find(Some(2), "件数"), Some(Severity::Error));
+        assert_eq!(find(Some(3), "转单号"), Some(Severity::Error));
+        assert_eq!(find(Some(3), "国家二字码"), Some(Severity::Warning));
+        assert_eq!(find(Some(3), "件数"), Some(Severity::Error));
+        assert_eq!(find(Some(3), "收货实重"), Some(Severity::Error));
+        assert_eq!(find(Some(4), "收件人邮编"), Some(Severity::Warning));
+        assert_eq!(find(Some(4), "收货实重"), Some(Severity::Warning));
+        assert_eq!(
+            report.issues.iter().filter(|i| i.source == DataSource::Detail).map(|i| i.severity).collect::<Vec<_>>(),
+            vec![Severity::Warning, Severity::Error]
+        );
+        assert_eq!(report.error_count + report.warning_count, report.issues.len());
+        assert!(summarize(&report, Severity::Error).contains("主数据 [收货材积重]"));
+    }
+}
@@ -0,0 +1,288 @@
+// ============================================================================
+// 重量汇总与对账：按国家、邮编、分桶统计件数和重量，并与输入合计核对
+// ============================================================================
+//
+// 计费重按每行取实重和材积重中较大者后累加。对账把各分组合计与原始数据合计比较，
+// 国家为空、邮编为空等未归入任何分组的行会体现为差异，在输出的「重量对账」工作表和日志中标出。
+
+use std::collections::BTreeMap;
+use umya_spreadsheet::*;
+use crate::commands::error::CommandError;
+use crate::commands::ups_dpd::buckets;
+use crate::commands::ups_dpd::carrier::{write_number, write_string};
+use crate::models::ups_dpd::{ExcelDataFrame, ExcelRow, SummaryBuckets};
+
+/// 输出中的对账工作表名称
+pub const REPORT_SHEET: &str = "重量对账";
+
+/// 对账允许的误差（浮点累加）
+const TOLERANCE: f64 = 0.001;
+
+/// 件数与重量合计
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WeightTotals {
+    pub pieces: i64,
+    pub gross_weight: f64,
+    pub volume_weight: f64,
+    /// 每行 max(实重, 材积重) 之和
+    pub chargeable_weight: f64,
+}
+
+impl WeightTotals {
+    /// 单行数据的件数、实重、材积重和计费重
+    pub fn of_row(row: &ExcelRow) -> Self {
+        let gross_weight = row.get("收货实重").and_then(|v| v.to_f64()).unwrap_or(0.0);
+        let volume_weight = row.get("收货材积重").and_then(|v| v.to_f64()).unwrap_or(0.0);
+        WeightTotals {
+            pieces: row.get("件数").and_then(|v| v.to_i64()).unwrap_or(0),
+            gross_weight,
+            volume_weight,
+            chargeable_weight: gross_weight.max(volume_weight),
+        }
+    }
+
+    pub fn add(&mut self, other: &WeightTotals) {
+        self.pieces += other.pieces;
+        self.gross_weight += other.gross_weight;
+        self.volume_weight += other.volume_weight;
+        self.chargeable_weight += other.chargeable_weight;
+    }
+
+    /// 所有数据行的合计
+    pub fn of_data(data: &ExcelDataFrame) -> Self {
+        sum(data.rows.iter().map(Self::of_row))
+    }
+}
+
+fn sum(items: impl IntoIterator<Item = WeightTotals>) -> WeightTotals {
+    items.into_iter().fold(WeightTotals::default(), |mut acc, item| {
+        acc.add(&item);
+        acc
+    })
+}
+
+/// 汇总中的一行
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryLine {
+    pub group: String,
+    pub label: String,
+    pub totals: WeightTotals,
+}
+
+/// 一项对账：分组合计与应有合计的比较
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconcileCheck {
+    /// 对账范围，如 "国家"、"德国邮编"
+    pub scope: String,
+    /// 指标，如 "件数"、"实重"
+    pub metric: &'static str,
+    pub expected: f64,
+    pub summarized: f64,
+}
+
+impl ReconcileCheck {
+    pub fn difference(&self) -> f64 {
+        self.summarized - self.expected
+    }
+
+    pub fn matches(&self) -> bool {
+        self.difference().abs() < TOLERANCE
+    }
+}
+
+/// 重量汇总与对账结果
+#[derive(Debug, Default)]
+pub struct WeightReport {
+    pub lines: Vec<SummaryLine>,
+    pub checks: Vec<ReconcileCheck>,
+}
+
+impl WeightReport {
+    /// 添加一组汇总行，并将该组合计与 expected 对账
+    pub fn add_group(&mut self, group: &str, lines: Vec<(String, WeightTotals)>, expected: &WeightTotals) {
+        let summarized = sum(lines.iter().map(|(_, totals)| *totals));
+
+        let metrics: [(&'static str, f64, f64); 4] = [
+            ("件数", expected.pieces as f64, summarized.pieces as f64),
+            ("实重", expected.gross_weight, summarized.gross_weight),
+            ("材积重", expected.volume_weight, summarized.volume_weight),
+            ("计费重", expected.chargeable_weight, summarized.chargeable_weight),
+        ];
+        self.checks.extend(metrics.into_iter().map(|(metric, expected, summarized)| ReconcileCheck {
+            scope: group.to_string(),
+            metric,
+            expected,
+            summarized,
+        }));
+
+        self.lines.extend(lines.into_iter().map(|(label, totals)| SummaryLine {
+            group: group.to_string(),
+            label,
+            totals,
+        }));
+    }
+
+    pub fn mismatches(&self) -> impl Iterator<Item = &ReconcileCheck> {
+        self.checks.iter().filter(|check| !check.matches())
+    }
+}
+
+/// 按国家汇总（按国家代码排序），国家为空的行不计入
+pub fn by_country(data: &ExcelDataFrame) -> Vec<(String, WeightTotals)> {
+    group_by(data, |row| {
+        Some(text(row, "国家二字码").to_uppercase()).filter(|c| !c.is_empty() && c != "NAN")
+    })
+}
+
+/// 指定国家按邮编汇总（按邮编排序），邮编为空的行不计入
+pub fn by_postcode(data: &ExcelDataFrame, country: &str) -> Vec<(String, WeightTotals)> {
+    group_by(data, |row| {
+        if !text(row, "国家二字码").eq_ignore_ascii_case(country) {
+            return None;
+        }
+        Some(text(row, "收件人邮编")).filter(|p| !p.is_empty() && !p.eq_ignore_ascii_case("nan"))
+    })
+}
+
+/// 指定国家的合计
+pub fn country_totals(data: &ExcelDataFrame, country: &str) -> WeightTotals {
+    sum(data
+        .rows
+        .iter()
+        .filter(|row| text(row, "国家二字码").eq_ignore_ascii_case(country))
+        .map(WeightTotals::of_row))
+}
+
+/// 按总结单分桶汇总（各分桶及 Other，顺序与模板一致）
+pub fn by_bucket(layout: &SummaryBuckets, data: &ExcelDataFrame) -> Vec<(String, WeightTotals)> {
+    let counts = buckets::count(layout, data);
+    layout
+        .buckets
+        .iter()
+        .map(|bucket| bucket.display_name())
+        .zip(counts.buckets)
+        .chain([("Other".to_string(), counts.other)])
+        .collect()
+}
+
+/// 按总结单分桶（如有）和国家汇总，两组合计都与输入合计对账
+pub fn bucket_report(layout: Option<&SummaryBuckets>, data: &ExcelDataFrame) -> WeightReport {
+    let input = WeightTotals::of_data(data);
+    let mut report = WeightReport::default();
+    if let Some(layout) = layout {
+        report.add_group("分桶", by_bucket(layout, data), &input);
+    }
+    report.add_group("国家", by_country(data), &input);
+    report
+}
+
+fn group_by(data: &ExcelDataFrame, key: impl Fn(&ExcelRow) -> Option<String>) -> Vec<(String, WeightTotals)> {
+    let mut groups: BTreeMap<String, WeightTotals> = BTreeMap::new();
+    for row in &data.rows {
+        if let Some(key) = key(row) {
+            groups.entry(key).or_default().add(&WeightTotals::of_row(row));
+        }
+    }
+    groups.into_iter().collect()
+}
+
+fn text(row: &ExcelRow, column: &str) -> String {
+    row.get(column).map(|v| v.to_string().trim().to_string()).unwrap_or_default()
+}
+
+/// 写入「重量对账」工作表（模板中已有同名工作表时覆盖其内容）
+pub fn write_sheet(workbook: &mut Spreadsheet, report: &WeightReport) -> Result<(), CommandError> {
+    if workbook.get_sheet_by_name(REPORT_SHEET).is_none() {
+        workbook
+            .new_sheet(REPORT_SHEET)
+            .map_err(|e| CommandError::new(format!("创建工作表 {} 失败: {}", REPORT_SHEET, e), "ERROR"))?;
+    }
+    let sheet = workbook
+        .get_sheet_by_name_mut(REPORT_SHEET)
+        .ok_or_else(|| CommandError::new("工作表不存在", "ERROR"))?;
+
+    // 1. 重量汇总
+    write_string(sheet, 1, 1, "重量汇总")?;
+    for (col, header) in ["分组", "名称", "件数", "实重(kg)", "材积重(kg)", "计费重(kg)"].iter().enumerate() {
+        write_string(sheet, 2, col as u32 + 1, header)?;
+    }
+    let mut row = 3;
+    for line in &report.lines {
+        write_string(sheet, row, 1, &line.group)?;
+        write_string(sheet, row, 2, &line.label)?;
+        write_number(sheet, row, 3, line.totals.pieces as f64)?;
+        write_weight(sheet, row, 4, line.totals.gross_weight)?;
+        write_weight(sheet, row, 5, line.totals.volume_weight)?;
+        write_weight(sheet, row, 6, line.totals.chargeable_weight)?;
+        row += 1;
+    }
+
+    // 2. 对账
+    row += 1;
+    write_string(sheet, row, 1, "对账")?;
+    row += 1;
+    for (col, header) in ["范围", "指标", "输入合计", "汇总合计", "差异", "结果"].iter().enumerate() {
+        write_string(sheet, row, col as u32 + 1, header)?;
+    }
+    for check in &report.checks {
+        row += 1;
+        write_string(sheet, row, 1, &check.scope)?;
+        write_string(sheet, row, 2, check.metric)?;
+        write_weight(sheet, row, 3, check.expected)?;
+        write_weight(sheet, row, 4, check.summarized)?;
+        write_weight(sheet, row, 5, check.difference())?;
+        write_string(sheet, row, 6, if check.matches() { "一致" } else { "不一致" })?;
+    }
+
+    Ok(())
+}
+
+fn write_weight(sheet: &mut Worksheet, row: u32, col: u32, value: f64) -> Result<(), CommandError> {
+    write_number(sheet, row, col, value)?;
+    sheet.get_cell_mut((col, row)).get_style_mut().get_number_format_mut().set_format_code("0.00");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ups_dpd::CellValue;
+
+    fn row(country: &str, postcode: &str, pieces: i64, gross: f64, volume: f64) -> ExcelRow {
+        let mut row = ExcelRow::new();
+        row.set("国家二字码".to_string(), CellValue::String(country.to_string()));
+        row.set("收件人邮编".to_string(), CellValue::String(postcode.to_string()));
+        row.set("件数".to_string(), CellValue::Integer(pieces));
+        row.set("收货实重".to_string(), CellValue::Number(gross));
+        row.set("收货材积重".to_string(), CellValue::Number(volume));
+        row
+    }
+
+    #[test]
+    fn test_group_and_reconcile() {
+        let mut data = ExcelDataFrame::new(Vec::new());
+        data.add_row(row("DE", "10115", 2, 10.0, 12.0));
+        data.add_row(row("de", "10115", 1, 5.0, 3.0));
+        data.add_row(row("FR", "75001", 3, 7.5, 6.0));
+        data.add_row(row("", "", 4, 1.0, 1.0));
+
+        let countries = by_country(&data);
+        assert_eq!(countries.len(), 2);
+        assert_eq!(countries[0].0, "DE");
+        assert_eq!(
+            countries[0].1,
+            WeightTotals { pieces: 3, gross_weight: 15.0, volume_weight: 15.0, chargeable_weight: 17.0 }
+        );
+        assert_eq!(by_postcode(&data, "DE"), vec![("10115".to_string(), countries[0].1)]);
+
+        let mut report = WeightReport::default();
+        report.add_group("国家", countries, &WeightTotals::of_data(&data));
+        report.add_group("德国邮编", by_postcode(&data, "DE"), &country_totals(&data, "DE"));
+
+        // 国家为空的 4 件未归入任何国家
+        let mismatches: Vec<_> = report.mismatches().map(|c| (c.scope.as_str(), c.metric)).collect();
+        assert_eq!(mismatches, vec![("国家", "件数"), ("国家", "实重"), ("国家", "材积重"), ("国家", "计费重")]);
+        assert_eq!(report.checks[0].difference(), -4.0);
+        assert_eq!(report.lines.len(), 3);
+    }
+}
@@ -0,0 +1,275 @@
+// 通用批量处理：收集输入文件、有界线程池并行处理、汇总结果、打包输出
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use super::output::OutputRule;
+use super::tabular;
+
+/// 最大并行数（自动和手动指定时都不超过）
+const MAX_WORKERS: usize = 4;
+
+/// 单个文件的处理结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchFileResult {
+    pub input_path: String,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 批量处理汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchFileResult>,     // 与输入文件顺序一致
+    pub zip_path: Option<String>,          // 打包后的 zip 文件
+}
+
+/// 单个文件处理完成时发送的进度
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchProgress {
+    pub completed: usize,                  // 已完成的文件数
+    pub total: usize,
+    pub input_path: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// 收集输入文件
+///
+/// source 为文件夹时取其中所有支持的表格文件（不含子文件夹），否则按 glob 模式匹配（如 `D:\装箱单\*.xlsx`）。
+/// 跳过 Excel 打开文件时生成的 `~$` 临时文件，结果按路径排序。
+pub fn collect_inputs(source: &str) -> Result<Vec<PathBuf>, String> {
+    let source_path = Path::new(source);
+
+    let mut files: Vec<PathBuf> = if source_path.is_dir() {
+        std::fs::read_dir(source_path)
+            .map_err(|e| format!("读取文件夹失败: {}", e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && tabular::is_supported(path))
+            .collect()
+    } else {
+        glob::glob(source)
+            .map_err(|e| format!("无效的文件匹配模式 \"{}\": {}", source, e))?
+            .filter_map(|entry| entry.ok())
+            .filter(|path| path.is_file())
+            .collect()
+    };
+
+    files.retain(|path| {
+        !path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("~$"))
+    });
+    files.sort();
+
+    if files.is_empty() {
+        return Err(format!("没有找到要处理的文件: {}", source));
+    }
+    Ok(files)
+}
+
+/// 并行处理文件（workers 个线程，0 表示自动，都不超过 MAX_WORKERS），单个文件失败不影响其他文件
+///
+/// process 返回输出文件路径；每个文件处理完成后调用 on_progress。
+pub fn run_batch<F, P>(inputs: &[PathBuf], workers: usize, process: F, on_progress: P) -> BatchSummary
+where
+    F: Fn(&Path) -> Result<PathBuf, String> + Sync,
+    P: Fn(&BatchProgress) + Sync,
+{
+    let total = inputs.len();
+    let workers = match workers {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(MAX_WORKERS)
+    .clamp(1, total.max(1));
+
+    let next = AtomicUsize::new(0);
+    let completed = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<BatchFileResult>>> = Mutex::new(vec![None; total]);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(input) = inputs.get(index) else {
+                    break;
+                };
+
+                // 单个文件的 panic 也记为失败，不中断整个批次
+                let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| process(input)))
+                    .unwrap_or_else(|_| Err("处理时发生内部错误".to_string()));
+
+                let result = BatchFileResult {
+                    input_path: input.to_string_lossy().to_string(),
+                    output_path: outcome.as_ref().ok().map(|p| p.to_string_lossy().to_string()),
+                    error: outcome.err(),
+                };
+
+                on_progress(&BatchProgress {
+                    completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                    total,
+                    input_path: result.input_path.clone(),
+                    success: result.error.is_none(),
+                    error: result.error.clone(),
+                });
+
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    let results: Vec<BatchFileResult> = results.into_inner().unwrap().into_iter().flatten().collect();
+    let succeeded = results.iter().filter(|r| r.error.is_none()).count();
+
+    BatchSummary {
+        total,
+        succeeded,
+        failed: total - succeeded,
+        results,
+        zip_path: None,
+    }
+}
+
+/// 将所有成功的输出文件打包为 zip（重名文件自动加序号）
+pub fn zip_outputs(summary: &BatchSummary, zip_path: &Path) -> Result<(), String> {
+    use zip::write::FileOptions;
+
+    let file = File::create(zip_path).map_err(|e| format!("创建 zip 文件失败: {}", e))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut used_names = std::collections::HashSet::new();
+
+    for output in summary.results.iter().filter_map(|r| r.output_path.as_deref()) {
+        let path = Path::new(output);
+        let name = unique_entry_name(path, &mut used_names);
+        let data = std::fs::read(path).map_err(|e| format!("读取输出文件失败 {}: {}", output, e))?;
+
+        zip.start_file(name, options).map_err(|e| format!("写入 zip 失败: {}", e))?;
+        zip.write_all(&data).map_err(|e| format!("写入 zip 失败: {}", e))?;
+    }
+
+    zip.finish().map_err(|e| format!("写入 zip 失败: {}", e))?;
+    Ok(())
+}
+
+/// 把成功的输出文件打包到 dir 下的 `{prefix}_{时间}.zip`，并记录到汇总中（没有成功的文件时不打包）
+pub fn bundle_outputs(summary: &mut BatchSummary, dir: &Path, prefix: &str) -> Result<(), String> {
+    if summary.succeeded == 0 {
+        return Ok(());
+    }
+
+    // 通过输出服务选定文件名，已有同名 zip（同一秒完成的批次、重复运行）时加序号，不覆盖
+    let rule = OutputRule::new(&format!("{}_{{date}}_{{time}}", prefix));
+    let output = rule.claim(dir, "", "", "zip", None).map_err(|e| e.to_string())?;
+    zip_outputs(summary, output.path())?;
+    summary.zip_path = Some(output.keep().to_string_lossy().to_string());
+    Ok(())
+}
+
+fn unique_entry_name(path: &Path, used: &mut std::collections::HashSet<String>) -> String {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("output").to_string();
+    let stem = path.file_stem().and_then(|n| n.to_str()).unwrap_or("output");
+    let extension = path.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+
+    let mut name = file_name;
+    let mut seq = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{}({}){}", stem, seq, extension);
+        seq += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_continues_past_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.xlsx", "b.csv", "c.xlsx", "~$a.xlsx", "notes.txt"] {
+            std::fs::write(dir.path().join(name), name).unwrap();
+        }
+
+        let inputs = collect_inputs(dir.path().to_str().unwrap()).unwrap();
+        let names: Vec<_> = inputs.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, vec!["a.xlsx", "b.csv", "c.xlsx"]);
+
+        let progress = Mutex::new(Vec::new());
+        let summary = run_batch(
+            &inputs,
+            2,
+            |path| {
+                if path.extension().unwrap() == "csv" {
+                    return Err("格式错误".to_string());
+                }
+                let output = path.with_file_name(format!("{}_out.txt", path.file_stem().unwrap().to_str().unwrap()));
+                std::fs::write(&output, "ok").map_err(|e| e.to_string())?;
+                Ok(output)
+            },
+            |p| progress.lock().unwrap().push(p.completed),
+        );
+
+        assert_eq!((summary.total, summary.succeeded, summary.failed), (3, 2, 1));
+        assert_eq!(summary.results[1].error.as_deref(), Some("格式错误"));
+        let mut completed = progress.into_inner().unwrap();
+        completed.sort();
+        assert_eq!(completed, vec![1, 2, 3]);
+
+        let zip_path = dir.path().join("outputs.zip");
+        zip_outputs(&summary, &zip_path).unwrap();
+        let archive = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+    }
+
+    #[test]
+    fn test_bundle_does_not_overwrite_existing_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("a_out.txt");
+        std::fs::write(&output, "ok").unwrap();
+        let result = BatchFileResult {
+            input_path: "a.xlsx".to_string(),
+            output_path: Some(output.to_string_lossy().to_string()),
+            error: None,
+        };
+        let mut first = BatchSummary { total: 1, succeeded: 1, failed: 0, results: vec![result], zip_path: None };
+        let mut second = first.clone();
+
+        bundle_outputs(&mut first, dir.path(), "拆分表").unwrap();
+        bundle_outputs(&mut second, dir.path(), "拆分表").unwrap();
+        assert_ne!(first.zip_path, second.zip_path);
+        assert!(Path::new(first.zip_path.as_deref().unwrap()).exists());
+    }
+
+    #[test]
+    fn test_workers_are_capped() {
+        let inputs: Vec<PathBuf> = (0..MAX_WORKERS * 3).map(|i| PathBuf::from(format!("{}.xlsx", i))).collect();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let summary = run_batch(
+            &inputs,
+            inputs.len(),
+            |path| {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(path.to_path_buf())
+            },
+            |_| {},
+        );
+
+        assert_eq!(summary.succeeded, inputs.len());
+        assert!(peak.into_inner() <= MAX_WORKERS);
+    }
+}
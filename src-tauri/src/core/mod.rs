@@ -1,6 +1,9 @@
 // Core module for shared utilities and common functionality
 // This module contains generic, reusable code that is not specific to any tool
 
+pub mod batch;
 pub mod database;
 pub mod html;
 pub mod http;
+pub mod output;
+pub mod tabular;
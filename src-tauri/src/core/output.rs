@@ -0,0 +1,308 @@
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+// 输出路径服务
+//
+// 输出文件名由命名模板生成（不含扩展名），支持的占位符：
+//   {stem} 输入文件名（不含扩展名）    {type} 处理类型
+//   {date} 日期，如 20250101           {time} 时间，如 153000
+//   {seq}  序号，取第一个未被占用的值（从 1 开始）
+// 目标文件已存在时按冲突策略处理。选定的文件会立即创建占位，并行处理时不会选到同一个文件名。
+// 输入文件无论哪种策略都不会被选中（视为冲突），不会覆盖客户的源文件。
+
+/// 查找可用文件名时的最大尝试次数
+const MAX_ATTEMPTS: u32 = 10_000;
+
+/// 文件名中不允许出现的字符
+const INVALID_CHARS: [char; 9] = ['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
+
+/// 占位符，如 {stem}
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{(\w+)\}").unwrap());
+
+/// 输出路径错误
+#[derive(Debug, thiserror::Error)]
+pub enum OutputError {
+    #[error("命名模板无效: {0}")]
+    InvalidTemplate(String),
+    #[error("输出文件已存在: {0}")]
+    Exists(String),
+    #[error("无法确定输出目录")]
+    NoDirectory,
+    #[error("创建输出文件失败: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, OutputError>;
+
+/// 输出文件已存在时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CollisionPolicy {
+    Overwrite,                            // 覆盖已有文件
+    #[default]
+    Suffix,                               // 在文件名后加序号，如 "结果(2).xlsx"
+    Fail,                                 // 报错，不写入
+}
+
+/// 输出规则：命名模板、输出目录和冲突策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputRule {
+    pub template: String,
+    #[serde(default)]
+    pub output_dir: Option<String>,       // 为空时使用工具的默认目录
+    #[serde(default)]
+    pub collision: CollisionPolicy,
+}
+
+impl OutputRule {
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.to_string(),
+            output_dir: None,
+            collision: CollisionPolicy::default(),
+        }
+    }
+
+    /// 配置的输出目录（空白视为未配置）
+    pub fn configured_dir(&self) -> Option<PathBuf> {
+        self.output_dir
+            .as_deref()
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// 检查模板：只能包含已知占位符，生成的文件名不能为空或包含非法字符
+    pub fn validate(&self) -> Result<()> {
+        self.render("stem", "type", 1, &Local::now()).map(|_| ())
+    }
+
+    /// 按模板生成文件名（不含扩展名）
+    pub fn render(&self, stem: &str, type_name: &str, seq: u32, now: &DateTime<Local>) -> Result<String> {
+        let mut unknown = Vec::new();
+        let name = PLACEHOLDER.replace_all(&self.template, |caps: &regex::Captures| {
+            match &caps[1] {
+                "stem" => stem.to_string(),
+                "type" => type_name.to_string(),
+                "date" => now.format("%Y%m%d").to_string(),
+                "time" => now.format("%H%M%S").to_string(),
+                "seq" => seq.to_string(),
+                other => {
+                    unknown.push(format!("{{{}}}", other));
+                    String::new()
+                }
+            }
+        });
+
+        if !unknown.is_empty() {
+            return Err(OutputError::InvalidTemplate(format!("未知的占位符 {}", unknown.join(", "))));
+        }
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(OutputError::InvalidTemplate("生成的文件名为空".to_string()));
+        }
+        if let Some(c) = name.chars().find(|c| INVALID_CHARS.contains(c)) {
+            return Err(OutputError::InvalidTemplate(format!("文件名不能包含 \"{}\"", c)));
+        }
+        Ok(name.to_string())
+    }
+
+    /// 在 dir 中选定输出文件并创建占位（dir 不存在时自动创建）
+    ///
+    /// input 为输入文件，与其相同的路径按已占用处理，不会被覆盖。
+    pub fn claim(
+        &self,
+        dir: &Path,
+        stem: &str,
+        type_name: &str,
+        extension: &str,
+        input: Option<&Path>,
+    ) -> Result<OutputFile> {
+        std::fs::create_dir_all(dir)?;
+        let input = input.and_then(|path| path.canonicalize().ok());
+
+        let now = Local::now();
+        let uses_seq = self.template.contains("{seq}");
+        let base = self.render(stem, type_name, 1, &now)?;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let name = match (uses_seq, attempt) {
+                (true, _) => self.render(stem, type_name, attempt, &now)?,
+                (false, 1) => base.clone(),
+                (false, n) => format!("{}({})", base, n),
+            };
+            let path = dir.join(format!("{}.{}", name, extension));
+            if input.is_some() && path.canonicalize().ok() == input {
+                if self.collision == CollisionPolicy::Fail && !uses_seq {
+                    return Err(OutputError::Exists(path.to_string_lossy().to_string()));
+                }
+                continue;
+            }
+
+            // 带 {seq} 的模板总是取未占用的序号，不会覆盖
+            if self.collision == CollisionPolicy::Overwrite && !uses_seq {
+                let created = !path.exists();
+                return Ok(OutputFile { path, created, kept: false });
+            }
+
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(OutputFile { path, created: true, kept: false }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if self.collision == CollisionPolicy::Fail && !uses_seq {
+                        return Err(OutputError::Exists(path.to_string_lossy().to_string()));
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(OutputError::Exists(dir.join(format!("{}.{}", base, extension)).to_string_lossy().to_string()))
+    }
+
+    /// 文件名是否可能由该模板生成（用于批量处理时跳过之前的输出）
+    ///
+    /// 模板全部由占位符组成时无法区分，始终返回 false。
+    pub fn is_output_name(&self, file_name: &str) -> bool {
+        let mut pattern = String::from("^");
+        let mut has_literal = false;
+        let mut last = 0;
+
+        for caps in PLACEHOLDER.captures_iter(&self.template) {
+            let m = caps.get(0).unwrap();
+            let literal = &self.template[last..m.start()];
+            has_literal |= !literal.trim().is_empty();
+            pattern.push_str(&regex::escape(literal));
+            pattern.push_str(match &caps[1] {
+                "date" => r"\d{8}",
+                "time" => r"\d{6}",
+                "seq" => r"\d+",
+                _ => ".*",
+            });
+            last = m.end();
+        }
+        let literal = &self.template[last..];
+        has_literal |= !literal.trim().is_empty();
+        pattern.push_str(&regex::escape(literal));
+        pattern.push_str(r"(\(\d+\))?\.[^.]+$");
+
+        has_literal && Regex::new(&pattern).is_ok_and(|re| re.is_match(file_name))
+    }
+}
+
+/// 已选定的输出文件
+///
+/// 在调用 keep 之前被丢弃（处理失败）时，删除本次新建的文件，不留下空文件。
+#[derive(Debug)]
+pub struct OutputFile {
+    path: PathBuf,
+    created: bool,                        // 文件是否由本次创建（覆盖已有文件时为 false）
+    kept: bool,
+}
+
+impl OutputFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 写入成功，保留文件
+    pub fn keep(mut self) -> PathBuf {
+        self.kept = true;
+        std::mem::take(&mut self.path)
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        if self.created && !self.kept {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(template: &str, collision: CollisionPolicy) -> OutputRule {
+        OutputRule { collision, ..OutputRule::new(template) }
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let now = Local::now();
+        let name = rule("{type}-{stem}_{seq}", CollisionPolicy::Suffix).render("装箱单", "UPS", 3, &now).unwrap();
+        assert_eq!(name, "UPS-装箱单_3");
+
+        let name = rule("{stem}_{date}", CollisionPolicy::Suffix).render("a", "", 1, &now).unwrap();
+        assert_eq!(name, format!("a_{}", now.format("%Y%m%d")));
+
+        assert!(rule("{stem}_{unknown}", CollisionPolicy::Suffix).validate().is_err());
+        assert!(rule("{stem}/out", CollisionPolicy::Suffix).validate().is_err());
+    }
+
+    #[test]
+    fn test_collision_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a_拆分表.xlsx"), "old").unwrap();
+
+        let suffix = rule("{stem}_拆分表", CollisionPolicy::Suffix);
+        let first = suffix.claim(dir.path(), "a", "", "xlsx", None).unwrap();
+        let second = suffix.claim(dir.path(), "a", "", "xlsx", None).unwrap();
+        assert_eq!(first.path().file_name().unwrap(), "a_拆分表(2).xlsx");
+        assert_eq!(second.path().file_name().unwrap(), "a_拆分表(3).xlsx");
+        let kept = first.keep();
+        drop(second);
+        assert!(kept.exists());
+        assert!(!dir.path().join("a_拆分表(3).xlsx").exists());
+
+        let err = rule("{stem}_拆分表", CollisionPolicy::Fail).claim(dir.path(), "a", "", "xlsx", None).unwrap_err();
+        assert!(matches!(err, OutputError::Exists(_)));
+
+        // 覆盖时不删除原有文件
+        drop(rule("{stem}_拆分表", CollisionPolicy::Overwrite).claim(dir.path(), "a", "", "xlsx", None).unwrap());
+        assert_eq!(std::fs::read_to_string(dir.path().join("a_拆分表.xlsx")).unwrap(), "old");
+
+        let seq = rule("结果_{seq}", CollisionPolicy::Fail);
+        std::fs::write(dir.path().join("结果_1.xlsx"), "").unwrap();
+        assert_eq!(seq.claim(dir.path(), "a", "", "xlsx", None).unwrap().keep().file_name().unwrap(), "结果_2.xlsx");
+    }
+
+    #[test]
+    fn test_never_claims_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("装箱单.xlsx");
+        std::fs::write(&input, "source").unwrap();
+
+        // 覆盖策略下同名输出改为加序号，不覆盖输入文件
+        let output = rule("{stem}", CollisionPolicy::Overwrite)
+            .claim(dir.path(), "装箱单", "", "xlsx", Some(&input))
+            .unwrap();
+        assert_eq!(output.path().file_name().unwrap(), "装箱单(2).xlsx");
+        drop(output);
+        assert_eq!(std::fs::read_to_string(&input).unwrap(), "source");
+
+        let err = rule("{stem}", CollisionPolicy::Fail)
+            .claim(dir.path(), "装箱单", "", "xlsx", Some(&input))
+            .unwrap_err();
+        assert!(matches!(err, OutputError::Exists(_)));
+    }
+
+    #[test]
+    fn test_is_output_name() {
+        let split = rule("{stem}_拆分表", CollisionPolicy::Suffix);
+        assert!(split.is_output_name("装箱单_拆分表.xlsx"));
+        assert!(split.is_output_name("装箱单_拆分表(2).xlsx"));
+        assert!(!split.is_output_name("装箱单.xlsx"));
+
+        let dated = rule("{stem}_查询结果_{date}_{time}", CollisionPolicy::Suffix);
+        assert!(dated.is_output_name("a_查询结果_20250101_120000.xlsx"));
+        assert!(!dated.is_output_name("a_查询结果.xlsx"));
+
+        assert!(!rule("{stem}", CollisionPolicy::Suffix).is_output_name("a.xlsx"));
+    }
+}
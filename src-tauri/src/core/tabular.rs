@@ -0,0 +1,507 @@
+use calamine::{open_workbook_auto, Data, Range, Reader, Sheets};
+use encoding_rs::{Encoding, GBK, UTF_8};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+// 通用表格输入（xlsx / xlsm / xlsb / xls / ods / csv）
+//
+// 表格文件统一通过 calamine 的 `open_workbook_auto` 打开，CSV 单独解析（自动识别编码和分隔符）。
+// 所有坐标均为工作表绝对坐标（0-based，A1 = (0, 0)），与 xlsx XML 中的位置一致。
+
+/// 支持的文件扩展名
+pub const SUPPORTED_EXTENSIONS: [&str; 6] = ["xlsx", "xlsm", "xlsb", "xls", "ods", "csv"];
+
+/// 表头检测时最多扫描的行数
+const HEADER_SCAN_ROWS: usize = 20;
+
+/// 超过该位数的纯数字按文本处理（超出 f64 精确表示范围的长编号）
+const MAX_NUMERIC_DIGITS: usize = 15;
+
+/// 表格读取错误
+#[derive(Debug, thiserror::Error)]
+pub enum TabularError {
+    #[error("不支持的文件格式: {0}")]
+    Unsupported(String),
+    #[error("打开文件失败: {0}")]
+    Open(String),
+    #[error("找不到工作表: {0}")]
+    SheetNotFound(String),
+    #[error("读取工作表失败: {0}")]
+    Read(String),
+    #[error("读取文件失败: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, TabularError>;
+
+/// 单元格值
+///
+/// 文本单元格原样保留（"00123" 不会变成 123），CSV 中带前导零或超过 15 位的数字也按文本读取。
+#[derive(Debug, Clone, PartialEq)]
+pub enum TabularCell {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Number(f64),
+    Bool(bool),
+    DateTime(String),
+    Error(String),
+}
+
+impl TabularCell {
+    /// 是否为空（空白文本也视为空）
+    pub fn is_empty(&self) -> bool {
+        match self {
+            TabularCell::Empty => true,
+            TabularCell::Text(s) => s.trim().is_empty(),
+            _ => false,
+        }
+    }
+
+    /// 显示文本（整数值的浮点数不带小数部分）
+    pub fn as_text(&self) -> String {
+        match self {
+            TabularCell::Empty => String::new(),
+            TabularCell::Text(s) => s.clone(),
+            TabularCell::Integer(i) => i.to_string(),
+            TabularCell::Number(n) => n.to_string(),
+            TabularCell::Bool(b) => b.to_string(),
+            TabularCell::DateTime(s) => s.clone(),
+            TabularCell::Error(e) => e.clone(),
+        }
+    }
+
+    /// 数值（文本按数字解析）
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            TabularCell::Integer(i) => Some(*i as f64),
+            TabularCell::Number(n) => Some(*n),
+            TabularCell::Text(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// 是否为文本单元格
+    pub fn is_text(&self) -> bool {
+        matches!(self, TabularCell::Text(s) if !s.trim().is_empty())
+    }
+
+    fn from_data(data: &Data) -> Self {
+        match data {
+            Data::Empty => TabularCell::Empty,
+            Data::String(s) => TabularCell::Text(s.clone()),
+            Data::Int(i) => TabularCell::Integer(*i),
+            Data::Float(f) => TabularCell::Number(*f),
+            Data::Bool(b) => TabularCell::Bool(*b),
+            Data::DateTime(dt) => TabularCell::DateTime(dt.to_string()),
+            Data::DateTimeIso(dt) => TabularCell::DateTime(dt.clone()),
+            Data::DurationIso(d) => TabularCell::DateTime(d.clone()),
+            Data::Error(e) => TabularCell::Error(format!("{:?}", e)),
+        }
+    }
+
+    /// 根据 CSV 字段文本推断类型
+    fn from_csv_field(field: &str) -> Self {
+        let trimmed = field.trim();
+        if trimmed.is_empty() {
+            return TabularCell::Empty;
+        }
+
+        let digits = trimmed.strip_prefix('-').unwrap_or(trimmed);
+        let is_integer = digits.chars().all(|c| c.is_ascii_digit());
+
+        // 前导零（编码、邮编）和长数字（运单号、海关编码）保留为文本
+        if is_integer && ((digits.len() > 1 && digits.starts_with('0')) || digits.len() > MAX_NUMERIC_DIGITS) {
+            return TabularCell::Text(field.to_string());
+        }
+        if is_integer {
+            if let Ok(i) = trimmed.parse() {
+                return TabularCell::Integer(i);
+            }
+        }
+
+        let looks_numeric = trimmed
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+            && trimmed.chars().any(|c| c.is_ascii_digit());
+        if looks_numeric && !digits.starts_with("00") {
+            if let Ok(n) = trimmed.parse::<f64>() {
+                return TabularCell::Number(n);
+            }
+        }
+
+        TabularCell::Text(field.to_string())
+    }
+}
+
+/// 单元格区域（0-based，含首尾）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellRange {
+    pub start_row: u32,
+    pub start_col: u32,
+    pub end_row: u32,
+    pub end_col: u32,
+}
+
+/// 工作表数据
+#[derive(Debug, Clone)]
+pub struct TabularSheet {
+    pub name: String,
+    rows: Vec<Vec<TabularCell>>,
+    width: usize,
+    merged: Vec<CellRange>,
+}
+
+impl TabularSheet {
+    fn new(name: impl Into<String>, rows: Vec<Vec<TabularCell>>, merged: Vec<CellRange>) -> Self {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        TabularSheet {
+            name: name.into(),
+            rows,
+            width,
+            merged,
+        }
+    }
+
+    /// 将 calamine 的区域转换为绝对坐标的行列表
+    fn from_range(name: &str, range: &Range<Data>, merged: Vec<CellRange>) -> Self {
+        let (start_row, start_col) = range.start().unwrap_or((0, 0));
+        let mut rows = vec![Vec::new(); start_row as usize];
+
+        for source_row in range.rows() {
+            let mut row = vec![TabularCell::Empty; start_col as usize];
+            row.extend(source_row.iter().map(TabularCell::from_data));
+            rows.push(row);
+        }
+
+        Self::new(name, rows, merged)
+    }
+
+    /// 行数
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// 列数（最宽一行）
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 获取单元格（越界返回 Empty）
+    pub fn cell(&self, row: usize, col: usize) -> &TabularCell {
+        const EMPTY: &TabularCell = &TabularCell::Empty;
+        self.rows.get(row).and_then(|r| r.get(col)).unwrap_or(EMPTY)
+    }
+
+    /// 获取一行
+    pub fn row(&self, row: usize) -> &[TabularCell] {
+        self.rows.get(row).map(|r| r.as_slice()).unwrap_or(&[])
+    }
+
+    /// 遍历所有行
+    pub fn rows(&self) -> impl Iterator<Item = &[TabularCell]> {
+        self.rows.iter().map(|r| r.as_slice())
+    }
+
+    /// 合并单元格区域（xlsx / xls 支持，其他格式为空）
+    pub fn merged_ranges(&self) -> &[CellRange] {
+        &self.merged
+    }
+
+    /// 检测表头行
+    ///
+    /// 在前 20 行中选择非数字文本单元格最多的一行（并列时取靠前的行），
+    /// 用于跳过表头上方的标题、说明等行。文本形式的编号（如 "00123"）不计入。没有文本行时返回 0。
+    pub fn detect_header_row(&self) -> usize {
+        let mut best_row = 0;
+        let mut best_score = 0;
+
+        for (row_idx, row) in self.rows.iter().take(HEADER_SCAN_ROWS).enumerate() {
+            let score = row
+                .iter()
+                .filter(|cell| cell.is_text() && cell.as_f64().is_none())
+                .count();
+            if score > best_score {
+                best_score = score;
+                best_row = row_idx;
+            }
+        }
+
+        best_row
+    }
+
+    /// 获取指定行的表头文本（去除首尾空白）
+    pub fn headers(&self, row: usize) -> Vec<String> {
+        self.row(row).iter().map(|cell| cell.as_text().trim().to_string()).collect()
+    }
+}
+
+/// 表格文件
+pub struct TabularWorkbook {
+    source: Source,
+}
+
+enum Source {
+    Spreadsheet(Sheets<BufReader<File>>),
+    Csv(TabularSheet),
+}
+
+impl TabularWorkbook {
+    /// 打开表格文件（按扩展名识别格式）
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = file_extension(path);
+
+        let source = match extension.as_str() {
+            "csv" => {
+                let bytes = std::fs::read(path)?;
+                let name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("Sheet1")
+                    .to_string();
+                Source::Csv(TabularSheet::new(name, parse_csv(&decode_text(&bytes)), Vec::new()))
+            }
+            ext if SUPPORTED_EXTENSIONS.contains(&ext) => {
+                let workbook = open_workbook_auto(path).map_err(|e| TabularError::Open(e.to_string()))?;
+                Source::Spreadsheet(workbook)
+            }
+            _ => return Err(TabularError::Unsupported(extension)),
+        };
+
+        Ok(TabularWorkbook { source })
+    }
+
+    /// 所有工作表名称（CSV 只有一个以文件名命名的工作表）
+    pub fn sheet_names(&self) -> Vec<String> {
+        match &self.source {
+            Source::Spreadsheet(workbook) => workbook.sheet_names(),
+            Source::Csv(sheet) => vec![sheet.name.clone()],
+        }
+    }
+
+    /// 根据名称读取工作表
+    pub fn sheet(&mut self, name: &str) -> Result<TabularSheet> {
+        match &mut self.source {
+            Source::Spreadsheet(workbook) => {
+                if !workbook.sheet_names().iter().any(|n| n == name) {
+                    return Err(TabularError::SheetNotFound(name.to_string()));
+                }
+                let range = workbook
+                    .worksheet_range(name)
+                    .map_err(|e| TabularError::Read(e.to_string()))?;
+                let merged = merged_ranges(workbook, name);
+                Ok(TabularSheet::from_range(name, &range, merged))
+            }
+            Source::Csv(sheet) if sheet.name == name => Ok(sheet.clone()),
+            Source::Csv(_) => Err(TabularError::SheetNotFound(name.to_string())),
+        }
+    }
+
+    /// 按索引读取工作表
+    pub fn sheet_at(&mut self, index: usize) -> Result<TabularSheet> {
+        let names = self.sheet_names();
+        let name = names.get(index).ok_or_else(|| {
+            TabularError::SheetNotFound(format!("第 {} 个工作表（共 {} 个）", index + 1, names.len()))
+        })?;
+        self.sheet(name)
+    }
+
+    /// 读取第一个工作表
+    pub fn first_sheet(&mut self) -> Result<TabularSheet> {
+        self.sheet_at(0)
+    }
+}
+
+/// 文件扩展名是否受支持
+pub fn is_supported<P: AsRef<Path>>(path: P) -> bool {
+    SUPPORTED_EXTENSIONS.contains(&file_extension(path.as_ref()).as_str())
+}
+
+/// 是否为 OOXML 压缩包（xlsx / xlsm），可进一步解析样式、图片等 XML 信息
+pub fn is_xlsx_package<P: AsRef<Path>>(path: P) -> bool {
+    matches!(file_extension(path.as_ref()).as_str(), "xlsx" | "xlsm")
+}
+
+fn file_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// 读取合并单元格（只有 xlsx 和 xls 支持）
+fn merged_ranges(workbook: &mut Sheets<BufReader<File>>, name: &str) -> Vec<CellRange> {
+    let dimensions = match workbook {
+        Sheets::Xlsx(xlsx) => xlsx.worksheet_merge_cells(name).and_then(|r| r.ok()),
+        Sheets::Xls(xls) => xls.worksheet_merge_cells(name),
+        _ => None,
+    };
+
+    dimensions
+        .unwrap_or_default()
+        .into_iter()
+        .map(|d| CellRange {
+            start_row: d.start.0,
+            start_col: d.start.1,
+            end_row: d.end.0,
+            end_col: d.end.1,
+        })
+        .collect()
+}
+
+/// 识别文本编码并解码（UTF-8 / UTF-16 BOM、无 BOM 的 UTF-8，其余按 GBK）
+fn decode_text(bytes: &[u8]) -> String {
+    let encoding: &'static Encoding = match Encoding::for_bom(bytes) {
+        Some((encoding, _)) => encoding,
+        None if std::str::from_utf8(bytes).is_ok() => UTF_8,
+        None => GBK,
+    };
+
+    // decode 会自动去掉与编码匹配的 BOM
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+/// 解析 CSV（支持引号、转义引号和字段内换行，自动识别 , ; 和制表符分隔）
+fn parse_csv(text: &str) -> Vec<Vec<TabularCell>> {
+    let delimiter = detect_delimiter(text);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter => row.push(TabularCell::from_csv_field(&std::mem::take(&mut field))),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(TabularCell::from_csv_field(&std::mem::take(&mut field)));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(TabularCell::from_csv_field(&field));
+        rows.push(row);
+    }
+
+    rows
+}
+
+/// 根据第一行（引号外）出现次数最多的字符选择分隔符
+fn detect_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or("");
+    let mut in_quotes = false;
+    let mut counts = [(',', 0), (';', 0), ('\t', 0)];
+
+    for c in first_line.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes {
+            for (delimiter, count) in counts.iter_mut() {
+                if c == *delimiter {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    counts
+        .iter()
+        .max_by_key(|(_, count)| *count)
+        .filter(|(_, count)| *count > 0)
+        .map(|(delimiter, _)| *delimiter)
+        .unwrap_or(',')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_csv_field_types() {
+        assert_eq!(TabularCell::from_csv_field(""), TabularCell::Empty);
+        assert_eq!(TabularCell::from_csv_field("42"), TabularCell::Integer(42));
+        assert_eq!(TabularCell::from_csv_field("1.5"), TabularCell::Number(1.5));
+        assert_eq!(TabularCell::from_csv_field("0101"), TabularCell::Text("0101".to_string()));
+        assert_eq!(
+            TabularCell::from_csv_field("1Z9999999999999999"),
+            TabularCell::Text("1Z9999999999999999".to_string())
+        );
+        assert_eq!(
+            TabularCell::from_csv_field("12345678901234567890"),
+            TabularCell::Text("12345678901234567890".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_gbk_csv_with_semicolons() {
+        let (bytes, _, _) = GBK.encode("说明;;\n编码;名称;数量\n0101;\"马;驴\";3\n");
+        let mut file = tempfile::Builder::new().suffix(".csv").tempfile().unwrap();
+        file.write_all(&bytes).unwrap();
+
+        let mut workbook = TabularWorkbook::open(file.path()).unwrap();
+        let sheet = workbook.first_sheet().unwrap();
+
+        assert_eq!(sheet.height(), 3);
+        assert_eq!(sheet.detect_header_row(), 1);
+        assert_eq!(sheet.headers(1), vec!["编码", "名称", "数量"]);
+        assert_eq!(sheet.cell(2, 0), &TabularCell::Text("0101".to_string()));
+        assert_eq!(sheet.cell(2, 1).as_text(), "马;驴");
+        assert_eq!(sheet.cell(2, 2), &TabularCell::Integer(3));
+    }
+
+    #[test]
+    fn test_utf8_bom_is_stripped() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice("HS Code\r\n8471300000\r\n".as_bytes());
+        let text = decode_text(&bytes);
+        let rows = parse_csv(&text);
+
+        assert_eq!(rows[0][0], TabularCell::Text("HS Code".to_string()));
+        assert_eq!(rows[1][0], TabularCell::Integer(8471300000));
+    }
+
+    #[test]
+    fn test_xlsx_uses_absolute_coordinates() {
+        use rust_xlsxwriter::{Format, Workbook};
+
+        let file = tempfile::Builder::new().suffix(".xlsx").tempfile().unwrap();
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_string(2, 1, "编码").unwrap();
+        sheet.write_string(3, 1, "00123").unwrap();
+        sheet.merge_range(3, 2, 4, 2, "x", &Format::new()).unwrap();
+        workbook.save(file.path()).unwrap();
+
+        let mut workbook = TabularWorkbook::open(file.path()).unwrap();
+        let sheet = workbook.first_sheet().unwrap();
+
+        assert_eq!(sheet.cell(2, 1).as_text(), "编码");
+        assert_eq!(sheet.cell(3, 1).as_text(), "00123");
+        assert_eq!(sheet.detect_header_row(), 2);
+        assert_eq!(
+            sheet.merged_ranges(),
+            &[CellRange { start_row: 3, start_col: 2, end_row: 4, end_col: 2 }]
+        );
+    }
+}
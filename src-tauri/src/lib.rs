@@ -10,6 +10,7 @@ use commands::excel::*;
 use commands::file_utils::open_file_with_default_app;
 use commands::icon_extractor::extract_icon;
 use commands::installed_apps::get_installed_apps;
+use commands::output::{get_output_rules, save_output_rule};
 use commands::system_tools::*;
 use commands::tax::*;
 use commands::ups_dpd::commands::*;
@@ -101,13 +102,30 @@ pub fn run() {
             tax_update_single_row,
             // Excel commands
             process_excel_file,
+            process_excel_batch,
+            preview_excel_split,
             get_excel_config,
             save_excel_config,
+            list_excel_profiles,
+            create_excel_profile,
+            clone_excel_profile,
+            rename_excel_profile,
+            save_excel_profile,
+            delete_excel_profile,
+            export_excel_profiles,
+            import_excel_profiles,
+            // Output commands
+            get_output_rules,
+            save_output_rule,
             // UPS/DPD commands
+            validate_ups_dpd_file,
             process_ups_dpd_file,
+            process_ups_dpd_batch,
             get_template_config,
             save_template_config,
             validate_template_file,
+            inspect_template,
+            export_default_template,
             reset_to_default_template,
             // Updater commands
             check_for_updates,
@@ -19,6 +19,13 @@ pub struct ForbiddenItem {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub has_exceptions: Option<bool>,      // 是否包含例外
+
+    // === 新增字段 (v3) ===
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hs_code_high: Option<String>,      // 范围上界（hs_code 为下界）
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_partial: Option<bool>,          // "из" 标记：仅部分商品受限
 }
 
 /// HS 编码条目（用于解析结果）
@@ -28,6 +35,8 @@ pub struct HsCodeEntry {
     pub code_4: String,
     pub code_6: String,
     pub code_8: String,
+    pub code_high: Option<String>,    // 范围上界，单个编码时为 None
+    pub is_partial: bool,             // 是否带 "из" 标记
     pub is_exception: bool,
     pub parent_raw: String,
 }
@@ -122,6 +131,8 @@ impl ForbiddenItem {
             created_at: None,
             raw_text: None,
             has_exceptions: None,
+            hs_code_high: None,
+            is_partial: None,
         }
     }
 
@@ -134,11 +145,13 @@ impl ForbiddenItem {
         additional_info: String,
         source_url: String,
     ) -> Self {
-        let enhanced_description = if has_exceptions {
-            format!("{} [含例外]", description)
-        } else {
-            description
-        };
+        let mut enhanced_description = description;
+        if code.is_partial {
+            enhanced_description = format!("{} [部分商品]", enhanced_description);
+        }
+        if has_exceptions {
+            enhanced_description = format!("{} [含例外]", enhanced_description);
+        }
 
         Self {
             id: None,
@@ -152,6 +165,21 @@ impl ForbiddenItem {
             created_at: None,
             raw_text: Some(raw_text),
             has_exceptions: Some(has_exceptions),
+            hs_code_high: code.code_high,
+            is_partial: Some(code.is_partial),
+        }
+    }
+
+    /// 是否为编码范围
+    pub fn is_range(&self) -> bool {
+        self.hs_code_high.is_some()
+    }
+
+    /// 用于展示的编码（范围显示为 "下界–上界"）
+    pub fn display_code(&self) -> String {
+        match &self.hs_code_high {
+            Some(high) => format!("{}–{}", self.hs_code, high),
+            None => self.hs_code.clone(),
         }
     }
 
@@ -10,6 +10,21 @@ pub enum ProcessType {
 }
 
 impl ProcessType {
+    pub const ALL: [ProcessType; 3] = [
+        ProcessType::SeaRailWithImage,
+        ProcessType::SeaRailNoImage,
+        ProcessType::AirFreight,
+    ];
+
+    /// 显示名称（内置方案的名称）
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProcessType::SeaRailWithImage => "海铁数据（有图版）",
+            ProcessType::SeaRailNoImage => "海铁数据（无图版）",
+            ProcessType::AirFreight => "空运数据",
+        }
+    }
+
     pub fn from_string(s: &str) -> Option<Self> {
         match s {
             "sea-rail-with-image" => Some(ProcessType::SeaRailWithImage),
@@ -28,39 +43,292 @@ impl ProcessType {
     }
 }
 
+/// 合并单元格的拆分方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SplitMode {
+    /// 按指定列（如数量列，1-based）的比例分配；设置 by_header 时按表头查找数量列
+    Proportional {
+        by_column: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        by_header: Option<String>,
+    },
+    /// 平均分配到每一行
+    Even,
+    /// 第一行保留原值，其他行为 0
+    FirstRowOnly,
+    /// 每一行复制原值
+    Copy,
+    /// 第一行保留原值，其他行留空
+    Clear,
+    /// 保持合并单元格（不拆分，图片锚定到合并区域）
+    KeepMerged,
+}
+
+impl SplitMode {
+    /// 按指定列（1-based）的比例分配
+    pub fn proportional(by_column: usize) -> Self {
+        SplitMode::Proportional { by_column, by_header: None }
+    }
+
+    /// 是否为数值分配（需要舍入处理）
+    pub fn is_distribution(&self) -> bool {
+        matches!(self, SplitMode::Proportional { .. } | SplitMode::Even)
+    }
+}
+
+/// 数值分配后的舍入误差处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemainderMode {
+    /// 最大余数法：按小数部分从大到小补足差额，保证合计与原值一致
+    #[default]
+    LargestRemainder,
+    /// 误差归入最后一行，保证合计与原值一致
+    LastRow,
+    /// 每行独立四舍五入（合计可能与原值相差几个最小单位）
+    PerRow,
+}
+
+/// 公式处理方式（公式被复制到合并区域的其他行时）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FormulaMode {
+    /// 平移相对引用；无法安全移动的公式保留原样
+    #[default]
+    Rebase,
+    /// 平移相对引用；无法安全移动的公式写入原单元格的计算结果
+    RebaseOrValue,
+    /// 所有公式都写入计算结果
+    Value,
+}
+
+/// 单列拆分规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnRule {
+    pub column: usize,               // 列索引（1-based，如 13 = M列）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,      // 表头名称，设置时按表头（含同义词）查找列，找不到则报错
+    pub mode: SplitMode,
+    #[serde(default = "default_decimals")]
+    pub decimals: u32,               // 数值分配保留的小数位数
+    #[serde(default)]
+    pub remainder: RemainderMode,    // 舍入误差处理方式
+}
+
+fn default_decimals() -> u32 {
+    2
+}
+
+impl ColumnRule {
+    pub fn new(column: usize, mode: SplitMode) -> Self {
+        Self {
+            column,
+            header: None,
+            mode,
+            decimals: default_decimals(),
+            remainder: RemainderMode::default(),
+        }
+    }
+
+    /// 按表头查找列（column 仅在表头未配置时使用）
+    pub fn with_header(mut self, header: impl Into<String>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+}
+
+/// 图片处理选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageOptions {
+    pub max_width: Option<u32>,        // 最大宽度（像素），超出时按比例缩小
+    pub max_height: Option<u32>,       // 最大高度（像素）
+    pub jpeg_quality: Option<u8>,      // 设置时不透明图片重新压缩为 JPEG（1-100）
+    pub dedupe: bool,                  // 内容相同的图片只处理、嵌入一次（导出时每行都写文件）
+    pub export_to_folder: bool,        // 同时导出图片到输出文件旁的文件夹
+    pub embed: bool,                   // 是否嵌入到拆分表（仅导出时可关闭）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_column: Option<usize>,    // 导出文件按该列（如 SKU，1-based）命名，为空时按行号命名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_header: Option<String>,   // 按表头查找命名列
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            max_width: None,
+            max_height: None,
+            jpeg_quality: None,
+            dedupe: true,
+            export_to_folder: false,
+            embed: true,
+            name_column: None,
+            name_header: None,
+        }
+    }
+}
+
+impl ImageOptions {
+    /// 是否需要重新编码图片
+    pub fn transforms(&self) -> bool {
+        self.max_width.is_some() || self.max_height.is_some() || self.jpeg_quality.is_some()
+    }
+}
+
 /// 处理配置
+///
+/// 未配置规则的列按 `SplitMode::Copy` 处理。
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "RawProcessConfig")]
 pub struct ProcessConfig {
     pub process_type: ProcessType,
-    pub weight_column: usize,      // 重量列索引（海铁13，空运15）
-    pub box_column: usize,         // 箱子列索引（海铁11，空运13）
+    pub header_row: Option<u32>,   // 表头行（1-based），为空时自动检测
+    pub column_rules: Vec<ColumnRule>,
+    pub formula_mode: FormulaMode,
     pub copy_images: bool,         // 是否复制图片
+    pub images: ImageOptions,      // 图片缩放、压缩和导出
+}
+
+/// 配置文件的原始格式，兼容旧版的 weight_column / box_column 字段
+#[derive(Deserialize)]
+struct RawProcessConfig {
+    process_type: ProcessType,
+    #[serde(default)]
+    header_row: Option<u32>,
+    #[serde(default)]
+    column_rules: Option<Vec<ColumnRule>>,
+    #[serde(default)]
+    weight_column: Option<usize>,
+    #[serde(default)]
+    box_column: Option<usize>,
+    #[serde(default)]
+    formula_mode: FormulaMode,
+    copy_images: bool,
+    #[serde(default)]
+    images: ImageOptions,
+}
+
+impl From<RawProcessConfig> for ProcessConfig {
+    fn from(raw: RawProcessConfig) -> Self {
+        let column_rules = match (raw.column_rules, raw.weight_column, raw.box_column) {
+            (Some(rules), _, _) => rules,
+            (None, Some(weight_column), Some(box_column)) => {
+                ProcessConfig::legacy_rules(weight_column, box_column)
+            }
+            (None, _, _) => ProcessConfig::default_for_type(raw.process_type.clone()).column_rules,
+        };
+
+        ProcessConfig {
+            process_type: raw.process_type,
+            header_row: raw.header_row,
+            column_rules,
+            formula_mode: raw.formula_mode,
+            copy_images: raw.copy_images,
+            images: raw.images,
+        }
+    }
 }
 
 impl ProcessConfig {
-    /// 创建默认配置
+    /// 创建默认配置（三种处理类型即为预设）
     pub fn default_for_type(process_type: ProcessType) -> Self {
         match process_type {
             ProcessType::SeaRailWithImage => ProcessConfig {
                 process_type,
-                weight_column: 13,
-                box_column: 11,
+                header_row: None,
+                column_rules: Self::preset_rules(13, 11),
+                formula_mode: FormulaMode::default(),
                 copy_images: true,
+                images: ImageOptions::default(),
             },
             ProcessType::SeaRailNoImage => ProcessConfig {
                 process_type,
-                weight_column: 13,
-                box_column: 11,
+                header_row: None,
+                column_rules: Self::preset_rules(13, 11),
+                formula_mode: FormulaMode::default(),
                 copy_images: false,
+                images: ImageOptions::default(),
             },
             ProcessType::AirFreight => ProcessConfig {
                 process_type,
-                weight_column: 15,
-                box_column: 13,
+                header_row: None,
+                column_rules: Self::preset_rules(15, 13),
+                formula_mode: FormulaMode::default(),
                 copy_images: true,
+                images: ImageOptions::default(),
             },
         }
     }
+
+    /// 预设规则：按表头查找毛重、箱数和数量列，列号仅作为默认位置
+    fn preset_rules(weight_column: usize, box_column: usize) -> Vec<ColumnRule> {
+        let quantity_column = weight_column.saturating_sub(1);
+        vec![
+            ColumnRule::new(
+                weight_column,
+                SplitMode::Proportional {
+                    by_column: quantity_column,
+                    by_header: Some("数量".to_string()),
+                },
+            )
+            .with_header("毛重"),
+            ColumnRule::new(box_column, SplitMode::FirstRowOnly).with_header("箱数"),
+        ]
+    }
+
+    /// 旧版规则：重量列按左侧数量列比例分配，箱子列仅第一行保留（固定列号）
+    fn legacy_rules(weight_column: usize, box_column: usize) -> Vec<ColumnRule> {
+        vec![
+            ColumnRule::new(weight_column, SplitMode::proportional(weight_column.saturating_sub(1))),
+            ColumnRule::new(box_column, SplitMode::FirstRowOnly),
+        ]
+    }
+
+    /// 获取指定列（0-based）的规则
+    pub fn rule_for_column(&self, col_idx: u32) -> Option<&ColumnRule> {
+        self.column_rules
+            .iter()
+            .find(|rule| rule.column as u32 == col_idx + 1)
+    }
+
+    /// 合并区域是否保持合并（区域内每一列都配置为 `SplitMode::KeepMerged`）
+    pub fn keeps_merged(&self, range: &MergedRange) -> bool {
+        (range.start_col..=range.end_col).all(|col| {
+            self.rule_for_column(col)
+                .is_some_and(|rule| rule.mode == SplitMode::KeepMerged)
+        })
+    }
+}
+
+/// 处理方案
+///
+/// 内置方案对应三种处理类型（id 即处理类型，不能删除或重命名），自定义方案由用户创建，
+/// 各自保存列规则和图片处理方式；config.process_type 记录方案基于的处理类型。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub builtin: bool,
+    pub config: ProcessConfig,
+}
+
+/// 方案导出文件
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileExport {
+    pub version: u32,
+    pub profiles: Vec<ProcessProfile>,
+}
+
+/// 工作表选择
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "name", rename_all = "kebab-case")]
+pub enum SheetSelection {
+    #[default]
+    First,          // 第一个工作表（默认）
+    Named(String),  // 指定名称的工作表
+    All,            // 所有工作表，每个输入表输出一个拆分表
 }
 
 /// 处理请求
@@ -68,6 +336,21 @@ impl ProcessConfig {
 pub struct ProcessRequest {
     pub file_path: String,
     pub config: ProcessConfig,
+    #[serde(default)]
+    pub sheets: SheetSelection,
+}
+
+/// 批量处理请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchProcessRequest {
+    pub source: String,              // 文件夹或 glob 模式
+    pub config: ProcessConfig,
+    #[serde(default)]
+    pub sheets: SheetSelection,
+    #[serde(default)]
+    pub workers: usize,              // 并行数，0 表示自动
+    #[serde(default)]
+    pub zip: bool,                   // 是否将输出打包为 zip
 }
 
 /// 处理响应
@@ -79,6 +362,43 @@ pub struct ProcessResponse {
     pub logs: Vec<String>,
 }
 
+/// 拆分预览（不写入文件）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitPreview {
+    pub sheets: Vec<SheetPreview>,
+}
+
+/// 单个工作表的拆分预览（行列号均为 1-based）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SheetPreview {
+    pub sheet_name: String,
+    pub header_row: u32,                         // 检测到的表头行
+    pub data_rows: u32,                          // 将被处理的行数（到第一列为空为止）
+    pub merged_blocks: Vec<MergedBlockPreview>,
+    pub image_count: usize,
+    pub warnings: Vec<String>,
+}
+
+/// 一个合并区域的拆分预览
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergedBlockPreview {
+    pub start_row: u32,
+    pub end_row: u32,
+    pub start_col: u32,
+    pub end_col: u32,
+    pub keep_merged: bool,                       // 输出时保持合并
+    pub distributions: Vec<ColumnDistribution>,  // 区域内各规则列（数值分配、首行保留、复制、清空）的输出结果
+}
+
+/// 一个配置了拆分规则的列在合并区域内的输出结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnDistribution {
+    pub column: usize,
+    pub total: Option<f64>,                      // 合并单元格原值，非数字时为空
+    pub weights: Vec<f64>,                       // 每一行的权重（按比例分配时为数量，非数值分配列为空）
+    pub values: Vec<Option<f64>>,                // 每一行的输出值，留空或非数字时为空
+}
+
 /// 合并单元格范围
 #[derive(Debug, Clone)]
 pub struct MergedRange {
@@ -1,5 +1,6 @@
 pub mod alta;
 pub mod excel;
+pub mod output;
 pub mod tax;
 pub mod ups_dpd;
 pub mod system_tools;
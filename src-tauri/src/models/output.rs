@@ -0,0 +1,44 @@
+use crate::core::output::OutputRule;
+use serde::{Deserialize, Serialize};
+
+/// 生成输出文件的工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputTool {
+    ExcelSplit,                           // Excel 数据拆分
+    Alta,                                 // 禁运标记
+    Tax,                                  // 税率批量查询
+    UpsDpd,                               // UPS/DPD 总结单
+}
+
+impl OutputTool {
+    pub const ALL: [OutputTool; 4] = [
+        OutputTool::ExcelSplit,
+        OutputTool::Alta,
+        OutputTool::Tax,
+        OutputTool::UpsDpd,
+    ];
+
+    /// 配置文件中的键
+    pub fn key(&self) -> &'static str {
+        match self {
+            OutputTool::ExcelSplit => "excel-split",
+            OutputTool::Alta => "alta",
+            OutputTool::Tax => "tax",
+            OutputTool::UpsDpd => "ups-dpd",
+        }
+    }
+
+    /// 默认输出规则
+    ///
+    /// 拆分表、禁运标记与之前固定的文件名一致；税率查询的时间精确到秒（之前为分钟），
+    /// UPS/DPD 总结单增加了输入文件名，批量处理时可以对应到各自的输入文件。
+    pub fn default_rule(&self) -> OutputRule {
+        match self {
+            OutputTool::ExcelSplit => OutputRule::new("{stem}_拆分表"),
+            OutputTool::Alta => OutputRule::new("{stem}_禁运标记"),
+            OutputTool::Tax => OutputRule::new("{stem}_查询结果_{date}_{time}"),
+            OutputTool::UpsDpd => OutputRule::new("{type}总结单-{stem}-{date}_{time}"),
+        }
+    }
+}
@@ -1,19 +1,33 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-/// 模板类型枚举
+/// 模板类型枚举（承运商）
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "kebab-case")]
 pub enum TemplateType {
     Ups,
     Dpd,
+    Gls,
+    Fedex,
+    RoyalMail,
 }
 
 impl TemplateType {
+    pub const ALL: [TemplateType; 5] = [
+        TemplateType::Ups,
+        TemplateType::Dpd,
+        TemplateType::Gls,
+        TemplateType::Fedex,
+        TemplateType::RoyalMail,
+    ];
+
     pub fn from_string(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "ups" => Some(TemplateType::Ups),
             "dpd" => Some(TemplateType::Dpd),
+            "gls" => Some(TemplateType::Gls),
+            "fedex" => Some(TemplateType::Fedex),
+            "royal-mail" => Some(TemplateType::RoyalMail),
             _ => None,
         }
     }
@@ -22,6 +36,31 @@ impl TemplateType {
         match self {
             TemplateType::Ups => "ups".to_string(),
             TemplateType::Dpd => "dpd".to_string(),
+            TemplateType::Gls => "gls".to_string(),
+            TemplateType::Fedex => "fedex".to_string(),
+            TemplateType::RoyalMail => "royal-mail".to_string(),
+        }
+    }
+
+    /// 显示名称，也用于输出文件名
+    pub fn label(&self) -> &'static str {
+        match self {
+            TemplateType::Ups => "UPS",
+            TemplateType::Dpd => "DPD",
+            TemplateType::Gls => "GLS",
+            TemplateType::Fedex => "FedEx",
+            TemplateType::RoyalMail => "RoyalMail",
+        }
+    }
+
+    /// 默认模板文件名（模板描述为同名 .json）
+    pub fn template_file_name(&self) -> &'static str {
+        match self {
+            TemplateType::Ups => "UPS_template.xlsx",
+            TemplateType::Dpd => "DPD_template.xlsx",
+            TemplateType::Gls => "GLS_template.xlsx",
+            TemplateType::Fedex => "FedEx_template.xlsx",
+            TemplateType::RoyalMail => "RoyalMail_template.xlsx",
         }
     }
 }
@@ -29,9 +68,32 @@ impl TemplateType {
 /// 处理请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessRequest {
-    pub main_file_path: String,
-    pub detail_file_path: Option<String>,
+    /// 主数据文件，多个文件按表头合并（输出按第一个文件命名）
+    pub main_file_paths: Vec<String>,
+    #[serde(default)]
+    pub detail_file_paths: Vec<String>,
+    /// 读取每个文件的所有工作表（默认只读第一个）
+    #[serde(default)]
+    pub all_sheets: bool,
+    pub template_type: TemplateType,
+    /// 源数据只有警告时仍然生成
+    #[serde(default)]
+    pub ignore_warnings: bool,
+}
+
+/// 批量处理请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProcessRequest {
+    pub source: String,              // 文件夹或 glob 模式
     pub template_type: TemplateType,
+    #[serde(default)]
+    pub workers: usize,              // 并行数，0 表示自动
+    #[serde(default)]
+    pub zip: bool,                   // 是否将输出打包为 zip
+    #[serde(default)]
+    pub ignore_warnings: bool,       // 源数据只有警告时仍然生成
+    #[serde(default)]
+    pub all_sheets: bool,            // 合并每个文件的所有工作表
 }
 
 /// 处理响应
@@ -43,12 +105,44 @@ pub struct ProcessResponse {
     pub logs: Vec<String>,
 }
 
+/// 校验问题级别：错误阻止生成，警告可忽略后继续
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 源数据校验问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub source: DataSource,
+    /// 来源文件（多个文件合并时用于区分）
+    pub file: Option<String>,
+    /// 源文件中的行号（从 1 开始），整列问题为空
+    pub row: Option<usize>,
+    pub column: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// 源数据校验报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
 /// 模板配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateConfig {
     pub template_type: TemplateType,
     pub template_path: Option<String>,
     pub use_default: bool,
+    /// 模板描述文件，未设置时使用模板同目录同名的 .json，再没有则使用内置描述
+    #[serde(default)]
+    pub descriptor_path: Option<String>,
 }
 
 impl TemplateConfig {
@@ -57,52 +151,185 @@ impl TemplateConfig {
             template_type,
             template_path: None,
             use_default: true,
+            descriptor_path: None,
         }
     }
 }
 
-/// 字段映射配置
-#[derive(Debug, Clone)]
-pub struct FieldMapping {
-    pub data_field: String,
-    pub template_field: String,
+/// 模板检查结果（配置自定义模板时检查工作表、表头和分桶单元格）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateInspection {
+    pub template_path: String,
+    /// 使用的模板描述：描述文件路径或 "内置描述"
+    pub descriptor: String,
+    /// 模板对应的默认模板版本（0 表示未知）
+    pub template_version: u32,
+    /// 当前内置默认模板的版本
+    pub default_version: u32,
+    /// 模板中实际存在的工作表
+    pub sheet_names: Vec<String>,
+    /// 模板描述中每个工作表的检查结果
+    pub sheets: Vec<SheetInspection>,
+    /// 会导致处理出错或填错位置的问题，有错误时不保存配置
+    pub errors: Vec<String>,
+    /// 部分数据不会填入的问题
+    pub warnings: Vec<String>,
 }
 
-impl FieldMapping {
-    pub fn new(data_field: &str, template_field: &str) -> Self {
-        FieldMapping {
-            data_field: data_field.to_string(),
-            template_field: template_field.to_string(),
-        }
-    }
+/// 单个工作表的检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetInspection {
+    /// 模板描述中的用途（summary、sub_order 等）
+    pub key: String,
+    pub expected_names: Vec<String>,
+    /// 找到的工作表，没找到时为空
+    pub found: Option<String>,
+    /// 表头行（分桶工作表为空）
+    pub header_row: Option<u32>,
+    pub mapped: Vec<MappedField>,
+    /// 找不到的表头
+    pub unmapped: Vec<String>,
+    /// 数据开始写入的行
+    pub first_empty_row: Option<u32>,
+    /// 分桶工作表中缺少的目标单元格
+    pub missing_cells: Vec<String>,
 }
 
-/// 工作表配置
-#[derive(Debug, Clone)]
-pub struct SheetConfig {
-    pub sheet_name: String,
-    pub mappings: Vec<FieldMapping>,
+/// 已匹配到列的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MappedField {
+    pub source: String,
+    pub header: String,
+    /// 列字母，如 "C"
+    pub column: String,
+}
+
+/// 模板描述：说明数据如何填入模板，随模板文件一起修改，无需重新编译
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateDescriptor {
+    pub version: u32,
+    /// 对应的默认模板版本（自定义模板为导出时的默认模板版本，0 表示未知）
+    #[serde(default)]
+    pub template_version: u32,
+    pub template_type: TemplateType,
+    /// 按用途索引的工作表（summary、waybill、sub_order 等）
+    pub sheets: BTreeMap<String, SheetDescriptor>,
 }
 
-impl SheetConfig {
-    pub fn new(sheet_name: &str, mappings: Vec<FieldMapping>) -> Self {
-        SheetConfig {
-            sheet_name: sheet_name.to_string(),
-            mappings,
+/// 工作表描述
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SheetDescriptor {
+    /// 工作表名称及别名，按顺序查找
+    pub names: Vec<String>,
+    /// 表头所在行（从 1 开始）
+    pub header_row: u32,
+    /// 数据列 -> 模板表头
+    pub columns: Vec<ColumnMapping>,
+    /// 按 lookup_key 从主数据匹配的列（如 DPD 子单号从运单清单取主单号）
+    pub lookup_columns: Vec<ColumnMapping>,
+    pub lookup_key: Option<String>,
+    /// 填入主数据还是明细表（子单号）
+    pub data_source: DataSource,
+    /// 按国家、邮编分桶统计的固定单元格（DPD 总结单）
+    pub buckets: Option<SummaryBuckets>,
+    /// 按文本写入的数据列（单号等）
+    pub text_columns: Vec<String>,
+}
+
+impl Default for SheetDescriptor {
+    fn default() -> Self {
+        SheetDescriptor {
+            names: Vec::new(),
+            header_row: 1,
+            columns: Vec::new(),
+            lookup_columns: Vec::new(),
+            lookup_key: None,
+            data_source: DataSource::Main,
+            buckets: None,
+            text_columns: Vec::new(),
         }
     }
 }
 
+/// 工作表的数据来源
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DataSource {
+    #[default]
+    Main,
+    Detail,
+}
+
+/// 数据列到模板表头的映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub source: String,
+    pub header: String,
+    /// 表头的其他写法
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// 总结单分桶：每个分桶统计一列，写在同一数据行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryBuckets {
+    /// 数据行（从 1 开始）
+    pub data_row: u32,
+    /// 按顺序匹配，每行数据归入第一个匹配的分桶
+    pub buckets: Vec<Bucket>,
+    /// 未归入任何分桶的件数
+    pub other_column: String,
+    /// 所有件数合计
+    pub total_column: String,
+}
+
+/// 分桶：国家，加上可选的邮编条件（精确值、前缀或数字区间，满足任一即可）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bucket {
+    /// 列字母，如 "F"
+    pub column: String,
+    pub country: String,
+    /// 日志中显示的名称，默认为国家或邮编
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub postcodes: Vec<String>,
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+    #[serde(default)]
+    pub ranges: Vec<PostcodeRange>,
+}
+
+/// 邮编数字区间（含两端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostcodeRange {
+    pub from: u32,
+    pub to: u32,
+}
+
 /// Excel 数据行（类似 pandas DataFrame 的一行）
 #[derive(Debug, Clone)]
 pub struct ExcelRow {
     pub data: HashMap<String, CellValue>,
+    /// 来源文件中的位置，用于校验报告定位
+    pub origin: Option<RowOrigin>,
+}
+
+/// 数据行的来源
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowOrigin {
+    /// 文件名（多工作表时为 "文件名 / 工作表"）
+    pub source: String,
+    /// 行号（从 1 开始）
+    pub row: usize,
 }
 
 impl ExcelRow {
     pub fn new() -> Self {
         ExcelRow {
             data: HashMap::new(),
+            origin: None,
         }
     }
 
@@ -193,55 +420,3 @@ impl ExcelDataFrame {
             .collect()
     }
 }
-
-/// 国家统计数据
-#[derive(Debug, Clone)]
-pub struct CountryStats {
-    pub country_code: String,
-    pub package_count: i64,
-    pub gross_weight: f64,
-    pub volume_weight: f64,
-}
-
-impl CountryStats {
-    pub fn new(country_code: String) -> Self {
-        CountryStats {
-            country_code,
-            package_count: 0,
-            gross_weight: 0.0,
-            volume_weight: 0.0,
-        }
-    }
-
-    pub fn add(&mut self, packages: i64, gross: f64, volume: f64) {
-        self.package_count += packages;
-        self.gross_weight += gross;
-        self.volume_weight += volume;
-    }
-}
-
-/// 邮编统计数据
-#[derive(Debug, Clone)]
-pub struct ZipcodeStats {
-    pub zipcode: String,
-    pub package_count: i64,
-    pub gross_weight: f64,
-    pub volume_weight: f64,
-}
-
-impl ZipcodeStats {
-    pub fn new(zipcode: String) -> Self {
-        ZipcodeStats {
-            zipcode,
-            package_count: 0,
-            gross_weight: 0.0,
-            volume_weight: 0.0,
-        }
-    }
-
-    pub fn add(&mut self, packages: i64, gross: f64, volume: f64) {
-        self.package_count += packages;
-        self.gross_weight += gross;
-        self.volume_weight += volume;
-    }
-}
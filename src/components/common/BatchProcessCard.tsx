@@ -0,0 +1,134 @@
+import { useState } from 'react';
+import { open } from '@tauri-apps/plugin-dialog';
+import { Button } from '@/components/ui/button';
+import { Card } from '@/components/ui/card';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Progress } from '@/components/ui/progress';
+import { Switch } from '@/components/ui/switch';
+import { useToast } from '@/hooks/use-toast';
+import { getFileName } from '@/lib/file-opener';
+import { listenToBatchProgress, type BatchProgress, type BatchSummary } from '@/lib/api/batch';
+import { FolderOpen, Layers } from 'lucide-react';
+
+interface BatchProcessCardProps {
+  eventName: 'excel-batch-progress' | 'ups-dpd-batch-progress';
+  description?: string;
+  onRun: (source: string, zip: boolean) => Promise<BatchSummary>;
+}
+
+/**
+ * 批量处理：选择文件夹或填写 glob 模式，显示进度和每个文件的结果
+ */
+export function BatchProcessCard({ eventName, description, onRun }: BatchProcessCardProps) {
+  const [source, setSource] = useState('');
+  const [zip, setZip] = useState(false);
+  const [running, setRunning] = useState(false);
+  const [progress, setProgress] = useState<BatchProgress | null>(null);
+  const [summary, setSummary] = useState<BatchSummary | null>(null);
+  const { toast } = useToast();
+
+  const handleSelectFolder = async () => {
+    const selected = await open({ directory: true });
+    if (selected && typeof selected === 'string') {
+      setSource(selected);
+      setSummary(null);
+    }
+  };
+
+  const handleRun = async () => {
+    if (!source.trim()) return;
+
+    setRunning(true);
+    setProgress(null);
+    setSummary(null);
+    const unlisten = await listenToBatchProgress(eventName, setProgress);
+
+    try {
+      const result = await onRun(source.trim(), zip);
+      setSummary(result);
+      toast({
+        title: '批量处理完成',
+        description: `成功 ${result.succeeded} 个，失败 ${result.failed} 个`,
+        variant: result.failed > 0 ? 'destructive' : undefined,
+      });
+    } catch (error: any) {
+      console.error('批量处理失败:', error);
+      toast({
+        title: '批量处理失败',
+        description: error?.message || '未知错误',
+        variant: 'destructive',
+      });
+    } finally {
+      unlisten();
+      setRunning(false);
+    }
+  };
+
+  return (
+    <Card className="p-6">
+      <div className="space-y-4">
+        <div className="flex items-center gap-2">
+          <Layers className="w-5 h-5" />
+          <h3 className="text-lg font-semibold">批量处理</h3>
+        </div>
+        {description && <p className="text-sm text-muted-foreground">{description}</p>}
+
+        <div className="flex gap-2">
+          <Input
+            value={source}
+            onChange={(e) => setSource(e.target.value)}
+            placeholder="文件夹路径或匹配模式，如 D:\装箱单\*.xlsx"
+            className="flex-1"
+          />
+          <Button variant="outline" onClick={handleSelectFolder} disabled={running}>
+            <FolderOpen className="w-4 h-4 mr-2" />
+            选择文件夹
+          </Button>
+        </div>
+
+        <div className="flex items-center justify-between">
+          <div className="flex items-center gap-2">
+            <Switch id={`${eventName}-zip`} checked={zip} onCheckedChange={setZip} disabled={running} />
+            <Label htmlFor={`${eventName}-zip`} className="text-sm font-normal">
+              输出打包为 zip
+            </Label>
+          </div>
+          <Button onClick={handleRun} disabled={!source.trim() || running}>
+            {running ? '处理中...' : '开始批量处理'}
+          </Button>
+        </div>
+
+        {running && progress && (
+          <div className="space-y-1">
+            <Progress value={(progress.completed / progress.total) * 100} />
+            <p className="text-xs text-muted-foreground truncate">
+              {progress.completed}/{progress.total} · {getFileName(progress.input_path)}
+            </p>
+          </div>
+        )}
+
+        {summary && (
+          <div className="space-y-2 text-sm">
+            <p>
+              共 {summary.total} 个文件，成功 {summary.succeeded} 个，失败 {summary.failed} 个
+            </p>
+            {summary.zip_path && (
+              <p className="text-muted-foreground truncate" title={summary.zip_path}>
+                打包文件: {summary.zip_path}
+              </p>
+            )}
+            <div className="max-h-48 overflow-auto rounded-md border p-2 font-mono text-xs space-y-1">
+              {summary.results.map((result) => (
+                <div key={result.input_path} className={result.error ? 'text-destructive' : undefined}>
+                  {result.error ? '✗' : '✓'} {getFileName(result.input_path)}
+                  {result.error && ` — ${result.error}`}
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
+      </div>
+    </Card>
+  );
+}
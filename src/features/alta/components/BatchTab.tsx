@@ -6,6 +6,7 @@ import { Label } from '@/components/ui/label';
 import { FileOpenDialog } from '@/components/common/FileOpenDialog';
 import { altaApi } from '@/lib/api/alta';
 import { getFileName } from '@/lib/file-opener';
+import { SPREADSHEET_EXTENSIONS } from '@/lib/utils';
 import type { AltaBatchResult } from '@/types';
 import { useToast } from '@/hooks/use-toast';
 import { open, save } from '@tauri-apps/plugin-dialog';
@@ -37,7 +38,7 @@ export function BatchTab({ onSwitchToManage }: BatchTabProps) {
         multiple: false,
         filters: [{
           name: 'Excel Files',
-          extensions: ['xlsx', 'xls']
+          extensions: SPREADSHEET_EXTENSIONS
         }]
       });
 
@@ -156,7 +157,7 @@ export function BatchTab({ onSwitchToManage }: BatchTabProps) {
       <Card>
         <CardHeader>
           <CardTitle>第二步：选择填写好的Excel文件</CardTitle>
-          <CardDescription>支持.xlsx和.xls格式</CardDescription>
+          <CardDescription>支持.xlsx、.xls、.ods和.csv格式</CardDescription>
         </CardHeader>
         <CardContent className="space-y-4">
           <div>
@@ -1,4 +1,4 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import { Button } from '@/components/ui/button';
@@ -6,30 +6,65 @@ import { Card } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
 import { ProcessConfigPanel } from './components/ProcessConfigPanel';
 import { ProcessLogPanel } from './components/ProcessLogPanel';
+import { SplitPreviewPanel } from './components/SplitPreviewPanel';
 import { FileOpenDialog } from '@/components/common/FileOpenDialog';
+import { BatchProcessCard } from '@/components/common/BatchProcessCard';
 import { useToast } from '@/hooks/use-toast';
 import { getFileName } from '@/lib/file-opener';
-import type { ProcessType, ProcessRequest, ProcessResponse } from '@/lib/api/excel';
-import { ProcessTypeLabels } from '@/lib/api/excel';
-import { FileText, Play, Settings } from 'lucide-react';
+import { SPREADSHEET_EXTENSIONS } from '@/lib/utils';
+import type { BatchSummary } from '@/lib/api/batch';
+import type { BatchProcessRequest, ProcessProfile, ProcessRequest, ProcessResponse, SplitPreview } from '@/lib/api/excel';
+import { Eye, FileText, Play, Settings } from 'lucide-react';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 
 export function ExcelPage() {
-  const [processType, setProcessType] = useState<ProcessType>('sea-rail-with-image');
+  const [profiles, setProfiles] = useState<ProcessProfile[]>([]);
+  const [profileId, setProfileId] = useState<string>('sea-rail-with-image');
   const [selectedFile, setSelectedFile] = useState<string | null>(null);
   const [processing, setProcessing] = useState(false);
   const [logs, setLogs] = useState<string[]>([]);
+  const [preview, setPreview] = useState<SplitPreview | null>(null);
+  const [previewing, setPreviewing] = useState(false);
   const [showFileDialog, setShowFileDialog] = useState(false);
   const [outputFilePath, setOutputFilePath] = useState<string>('');
   const { toast } = useToast();
 
+  const loadProfiles = async () => {
+    try {
+      const list = await invoke<ProcessProfile[]>('list_excel_profiles');
+      setProfiles(list);
+      // 当前方案被删除时回到第一个方案
+      if (!list.some((p) => p.id === profileId) && list.length > 0) {
+        setProfileId(list[0].id);
+      }
+    } catch (error) {
+      console.error('加载处理方案失败:', error);
+    }
+  };
+
+  useEffect(() => {
+    loadProfiles();
+  }, []);
+
+  const currentProfile = profiles.find((p) => p.id === profileId);
+
+  // 每次处理前读取最新保存的方案配置
+  const loadCurrentConfig = async () => {
+    const list = await invoke<ProcessProfile[]>('list_excel_profiles');
+    const profile = list.find((p) => p.id === profileId);
+    if (!profile) {
+      throw new Error('找不到当前处理方案');
+    }
+    return profile.config;
+  };
+
   const handleSelectFile = async () => {
     try {
       const selected = await open({
         filters: [
           {
             name: 'Excel 文件',
-            extensions: ['xlsx'],
+            extensions: SPREADSHEET_EXTENSIONS,
           },
         ],
       });
@@ -37,6 +72,7 @@ export function ExcelPage() {
       if (selected && typeof selected === 'string') {
         setSelectedFile(selected);
         setLogs([]);
+        setPreview(null);
       }
     } catch (error) {
       console.error('选择文件失败:', error);
@@ -48,6 +84,31 @@ export function ExcelPage() {
     }
   };
 
+  const handlePreview = async () => {
+    if (!selectedFile) {
+      return;
+    }
+
+    setPreviewing(true);
+    try {
+      const config = await loadCurrentConfig();
+      const request: ProcessRequest = {
+        file_path: selectedFile,
+        config,
+      };
+      setPreview(await invoke<SplitPreview>('preview_excel_split', { request }));
+    } catch (error: any) {
+      console.error('预览失败:', error);
+      toast({
+        title: '预览失败',
+        description: error?.message || '未知错误',
+        variant: 'destructive',
+      });
+    } finally {
+      setPreviewing(false);
+    }
+  };
+
   const handleProcess = async () => {
     if (!selectedFile) {
       toast({
@@ -62,7 +123,7 @@ export function ExcelPage() {
 
     try {
       // 获取配置
-      const config = await invoke<any>('get_excel_config', { processType });
+      const config = await loadCurrentConfig();
 
       // 处理文件
       const request: ProcessRequest = {
@@ -101,6 +162,12 @@ export function ExcelPage() {
     }
   };
 
+  const handleBatch = async (source: string, zip: boolean) => {
+    const config = await loadCurrentConfig();
+    const request: BatchProcessRequest = { source, config, workers: 0, zip };
+    return invoke<BatchSummary>('process_excel_batch', { request });
+  };
+
   return (
     <div className="space-y-6">
       <div>
@@ -110,7 +177,11 @@ export function ExcelPage() {
         </p>
       </div>
 
-      <Tabs defaultValue="process" className="w-full">
+      <Tabs
+        defaultValue="process"
+        className="w-full"
+        onValueChange={(value) => value === 'process' && loadProfiles()}
+      >
         <TabsList>
           <TabsTrigger value="process">
             <Play className="w-4 h-4 mr-2" />
@@ -126,28 +197,29 @@ export function ExcelPage() {
           <Card className="p-6">
             <div className="space-y-6">
               <div className="space-y-3">
-                <Label className="text-sm font-medium">处理类型</Label>
+                <Label className="text-sm font-medium">处理方案</Label>
                 <div className="flex flex-wrap gap-4">
-                  {(Object.entries(ProcessTypeLabels) as [ProcessType, string][]).map(([value, label]) => (
-                    <div key={value} className="flex items-center space-x-2">
+                  {profiles.map((profile) => (
+                    <div key={profile.id} className="flex items-center space-x-2">
                       <input
                         type="radio"
-                        id={value}
-                        name="processType"
-                        value={value}
-                        checked={processType === value}
+                        id={`profile-${profile.id}`}
+                        name="processProfile"
+                        value={profile.id}
+                        checked={profileId === profile.id}
                         onChange={(e) => {
-                          setProcessType(e.target.value as ProcessType);
+                          setProfileId(e.target.value);
                           setSelectedFile(null);
                           setLogs([]);
+                          setPreview(null);
                         }}
                         className="w-4 h-4 text-primary border-gray-300 focus:ring-2 focus:ring-primary cursor-pointer"
                       />
                       <Label
-                        htmlFor={value}
+                        htmlFor={`profile-${profile.id}`}
                         className="text-sm font-normal cursor-pointer"
                       >
-                        {label}
+                        {profile.name}
                       </Label>
                     </div>
                   ))}
@@ -169,27 +241,46 @@ export function ExcelPage() {
                 )}
               </div>
 
-              <Button
-                onClick={handleProcess}
-                disabled={!selectedFile || processing}
-                className="w-full"
-              >
-                {processing ? (
-                  <>
-                    <span className="animate-spin mr-2">⏳</span>
-                    处理中...
-                  </>
-                ) : (
-                  <>
-                    <Play className="w-4 h-4 mr-2" />
-                    开始处理
-                  </>
-                )}
-              </Button>
+              <div className="flex gap-2">
+                <Button
+                  onClick={handlePreview}
+                  disabled={!selectedFile || processing || previewing}
+                  variant="outline"
+                  className="flex-1"
+                >
+                  <Eye className="w-4 h-4 mr-2" />
+                  {previewing ? '预览中...' : '预览拆分'}
+                </Button>
+                <Button
+                  onClick={handleProcess}
+                  disabled={!selectedFile || processing}
+                  className="flex-1"
+                >
+                  {processing ? (
+                    <>
+                      <span className="animate-spin mr-2">⏳</span>
+                      处理中...
+                    </>
+                  ) : (
+                    <>
+                      <Play className="w-4 h-4 mr-2" />
+                      开始处理
+                    </>
+                  )}
+                </Button>
+              </div>
             </div>
           </Card>
 
+          {preview && <SplitPreviewPanel preview={preview} />}
+
           {logs.length > 0 && <ProcessLogPanel logs={logs} />}
+
+          <BatchProcessCard
+            eventName="excel-batch-progress"
+            description={`按当前选择的「${currentProfile?.name ?? ''}」方案处理文件夹中的所有表格，跳过已生成的拆分表`}
+            onRun={handleBatch}
+          />
         </TabsContent>
 
         <TabsContent value="config">
@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
@@ -12,32 +13,29 @@ import {
   AccordionTrigger,
 } from '@/components/ui/accordion';
 import { useToast } from '@/hooks/use-toast';
-import type { ProcessConfig, ProcessType } from '@/lib/api/excel';
-import { ProcessTypeLabels } from '@/lib/api/excel';
-import { Save, RotateCcw, Ship, Plane, Image, Hash, Package } from 'lucide-react';
+import type { ColumnRule, FormulaMode, ImageOptions, ProcessConfig, ProcessProfile, ProcessType, SplitMode } from '@/lib/api/excel';
+import { FormulaModeLabels, ProcessTypeLabels, SplitModeLabels } from '@/lib/api/excel';
+import { Save, RotateCcw, Ship, Plane, Image, Hash, Plus, Trash2, Copy, Upload, Download } from 'lucide-react';
 
-type ConfigsMap = Record<ProcessType, ProcessConfig>;
+const buildSplitMode = (type: SplitMode['type'], column: number): SplitMode =>
+  type === 'proportional' ? { type, by_column: Math.max(column - 1, 1) } : { type };
+
+const errorMessage = (error: any) => error?.message || '未知错误';
 
 export function ProcessConfigPanel() {
-  const [configs, setConfigs] = useState<ConfigsMap | null>(null);
+  const [profiles, setProfiles] = useState<ProcessProfile[] | null>(null);
   const [loading, setLoading] = useState(false);
+  const [newName, setNewName] = useState('');
+  const [newType, setNewType] = useState<ProcessType>('sea-rail-with-image');
   const { toast } = useToast();
 
   useEffect(() => {
-    loadAllConfigs();
+    loadProfiles();
   }, []);
 
-  const loadAllConfigs = async () => {
+  const loadProfiles = async () => {
     try {
-      const types: ProcessType[] = ['sea-rail-with-image', 'sea-rail-no-image', 'air-freight'];
-      const configsData: ConfigsMap = {} as ConfigsMap;
-
-      for (const type of types) {
-        const config = await invoke<ProcessConfig>('get_excel_config', { processType: type });
-        configsData[type] = config;
-      }
-
-      setConfigs(configsData);
+      setProfiles(await invoke<ProcessProfile[]>('list_excel_profiles'));
     } catch (error) {
       console.error('加载配置失败:', error);
       toast({
@@ -48,13 +46,24 @@ export function ProcessConfigPanel() {
     }
   };
 
+  // 执行方案操作，成功后重新加载列表
+  const runAction = async (action: () => Promise<unknown>, success: string) => {
+    try {
+      await action();
+      await loadProfiles();
+      toast({ title: '成功', description: success });
+    } catch (error: any) {
+      toast({ title: '操作失败', description: errorMessage(error), variant: 'destructive' });
+    }
+  };
+
   const handleSaveAll = async () => {
-    if (!configs) return;
+    if (!profiles) return;
 
     setLoading(true);
     try {
-      for (const config of Object.values(configs)) {
-        await invoke('save_excel_config', { config });
+      for (const profile of profiles) {
+        await invoke('save_excel_profile', { id: profile.id, config: profile.config });
       }
       toast({
         title: '成功',
@@ -64,7 +73,7 @@ export function ProcessConfigPanel() {
       console.error('保存配置失败:', error);
       toast({
         title: '保存失败',
-        description: error?.message || '未知错误',
+        description: errorMessage(error),
         variant: 'destructive',
       });
     } finally {
@@ -73,22 +82,87 @@ export function ProcessConfigPanel() {
   };
 
   const handleReset = async () => {
-    await loadAllConfigs();
+    await loadProfiles();
     toast({
       title: '已重置',
       description: '所有配置已恢复为保存的值',
     });
   };
 
-  const updateConfig = (type: ProcessType, updates: Partial<ProcessConfig>) => {
-    if (!configs) return;
-    setConfigs({
-      ...configs,
-      [type]: { ...configs[type], ...updates },
+  const handleCreate = () =>
+    runAction(async () => {
+      await invoke('create_excel_profile', { name: newName, processType: newType });
+      setNewName('');
+    }, `已新建方案「${newName.trim()}」`);
+
+  const handleClone = (profile: ProcessProfile) =>
+    runAction(
+      () => invoke('clone_excel_profile', { id: profile.id, name: `${profile.name} 副本` }),
+      `已复制方案「${profile.name}」`
+    );
+
+  const handleRename = (profile: ProcessProfile, name: string) => {
+    if (name.trim() === profile.name) return;
+    runAction(() => invoke('rename_excel_profile', { id: profile.id, name }), '方案已重命名');
+  };
+
+  const handleDelete = (profile: ProcessProfile) =>
+    runAction(() => invoke('delete_excel_profile', { id: profile.id }), `已删除方案「${profile.name}」`);
+
+  const handleExport = async () => {
+    const path = await save({
+      defaultPath: '处理方案.json',
+      filters: [{ name: 'JSON', extensions: ['json'] }],
     });
+    if (!path) return;
+    try {
+      const count = await invoke<number>('export_excel_profiles', { ids: [], path });
+      toast({ title: '导出完成', description: `已导出 ${count} 个自定义方案` });
+    } catch (error: any) {
+      toast({ title: '导出失败', description: errorMessage(error), variant: 'destructive' });
+    }
   };
 
-  if (!configs) {
+  const handleImport = async () => {
+    const path = await open({ filters: [{ name: 'JSON', extensions: ['json'] }] });
+    if (!path || typeof path !== 'string') return;
+    runAction(() => invoke('import_excel_profiles', { path }), '方案已导入');
+  };
+
+  const updateConfig = (id: string, updates: Partial<ProcessConfig>) => {
+    if (!profiles) return;
+    setProfiles(
+      profiles.map((profile) =>
+        profile.id === id ? { ...profile, config: { ...profile.config, ...updates } } : profile
+      )
+    );
+  };
+
+  const updateImages = (id: string, updates: Partial<ImageOptions>) => {
+    updateConfig(id, { images: { ...configOf(id).images, ...updates } });
+  };
+
+  const configOf = (id: string) => profiles!.find((profile) => profile.id === id)!.config;
+
+  const updateRule = (id: string, index: number, updates: Partial<ColumnRule>) => {
+    const rules = configOf(id).column_rules.map((rule, i) =>
+      i === index ? { ...rule, ...updates } : rule
+    );
+    updateConfig(id, { column_rules: rules });
+  };
+
+  const addRule = (id: string) => {
+    const rule: ColumnRule = { column: 1, mode: { type: 'copy' }, decimals: 2, remainder: 'largest-remainder' };
+    updateConfig(id, { column_rules: [...configOf(id).column_rules, rule] });
+  };
+
+  const removeRule = (id: string, index: number) => {
+    updateConfig(id, {
+      column_rules: configOf(id).column_rules.filter((_, i) => i !== index),
+    });
+  };
+
+  if (!profiles) {
     return (
       <div className="flex items-center justify-center min-h-[400px]">
         <div className="text-center space-y-2">
@@ -110,94 +184,274 @@ export function ProcessConfigPanel() {
     return <Badge className="ml-2">空运</Badge>;
   };
 
-  const renderConfigSection = (type: ProcessType) => {
-    const config = configs[type];
-    
+  const renderConfigSection = (profile: ProcessProfile) => {
+    const { id, config } = profile;
+
     return (
-      <AccordionItem key={type} value={type}>
+      <AccordionItem key={id} value={id}>
         <AccordionTrigger className="hover:no-underline">
           <div className="flex items-center gap-3">
             <div className="p-2 rounded-lg bg-primary/10 text-primary">
-              {getTypeIcon(type)}
+              {getTypeIcon(config.process_type)}
             </div>
             <div className="flex items-center gap-2">
-              <span className="text-base font-medium">{ProcessTypeLabels[type]}</span>
-              {getTypeBadge(type)}
+              <span className="text-base font-medium">{profile.name}</span>
+              {profile.builtin ? <Badge variant="outline" className="ml-2">内置</Badge> : getTypeBadge(config.process_type)}
             </div>
           </div>
         </AccordionTrigger>
         <AccordionContent>
           <div className="pt-4 space-y-4">
-            <div className="grid gap-6 sm:grid-cols-2">
-              {/* 重量列 */}
-              <div className="space-y-2">
-                <Label htmlFor={`${type}-weight`} className="flex items-center gap-2 text-sm font-medium">
-                  <Hash className="w-4 h-4 text-muted-foreground/40" />
-                  重量列索引
-                </Label>
-                <Input
-                  id={`${type}-weight`}
-                  type="number"
-                  min="1"
-                  max="100"
-                  value={config.weight_column}
-                  onChange={(e) =>
-                    updateConfig(type, { weight_column: parseInt(e.target.value) || 1 })
-                  }
-                  className="font-mono"
-                />
-                <p className="text-xs text-muted-foreground/40 pl-6">
-                  Excel 列号 · 如 13 = M列
-                </p>
-              </div>
+            {/* 列拆分规则 */}
+            <div className="space-y-3">
+              <Label className="flex items-center gap-2 text-sm font-medium">
+                <Hash className="w-4 h-4 text-muted-foreground/40" />
+                列拆分规则
+              </Label>
+              {config.column_rules.map((rule, index) => (
+                <div key={index} className="flex items-center gap-3">
+                  <Input
+                    type="number"
+                    min="1"
+                    max="100"
+                    value={rule.column}
+                    onChange={(e) =>
+                      updateRule(id, index, { column: parseInt(e.target.value) || 1 })
+                    }
+                    className="font-mono w-24"
+                  />
+                  <Input
+                    value={rule.header ?? ''}
+                    onChange={(e) => updateRule(id, index, { header: e.target.value || null })}
+                    placeholder="表头（可选）"
+                    className="w-32"
+                    title="按表头查找列，如 毛重 / G.W. / Gross Weight"
+                  />
+                  <select
+                    value={rule.mode.type}
+                    onChange={(e) =>
+                      updateRule(id, index, {
+                        mode: buildSplitMode(e.target.value as SplitMode['type'], rule.column),
+                      })
+                    }
+                    className="h-9 rounded-md border bg-background px-3 text-sm"
+                  >
+                    {Object.entries(SplitModeLabels).map(([value, label]) => (
+                      <option key={value} value={value}>
+                        {label}
+                      </option>
+                    ))}
+                  </select>
+                  {rule.mode.type === 'proportional' && (
+                    <>
+                      <Input
+                        type="number"
+                        min="1"
+                        max="100"
+                        value={rule.mode.by_column}
+                        onChange={(e) =>
+                          updateRule(id, index, {
+                            mode: { ...rule.mode, type: 'proportional', by_column: parseInt(e.target.value) || 1 },
+                          })
+                        }
+                        className="font-mono w-24"
+                        title="比例依据列（数量列）"
+                      />
+                      <Input
+                        value={rule.mode.by_header ?? ''}
+                        onChange={(e) =>
+                          updateRule(id, index, {
+                            mode: { ...rule.mode, type: 'proportional', by_header: e.target.value || null },
+                          })
+                        }
+                        placeholder="数量列表头"
+                        className="w-28"
+                        title="按表头查找数量列，如 数量 / QTY / PCS"
+                      />
+                    </>
+                  )}
+                  <Button variant="ghost" size="icon" onClick={() => removeRule(id, index)}>
+                    <Trash2 className="w-4 h-4" />
+                  </Button>
+                </div>
+              ))}
+              <Button variant="outline" size="sm" onClick={() => addRule(id)}>
+                <Plus className="w-4 h-4 mr-1" />
+                添加规则
+              </Button>
+              <p className="text-xs text-muted-foreground/40 pl-6">
+                Excel 列号 · 如 13 = M列 · 填写表头时按表头查找列（找不到则报错）· 未配置的列复制原值
+              </p>
+            </div>
 
-              {/* 箱子列 */}
-              <div className="space-y-2">
-                <Label htmlFor={`${type}-box`} className="flex items-center gap-2 text-sm font-medium">
-                  <Package className="w-4 h-4 text-muted-foreground/40" />
-                  箱子列索引
-                </Label>
-                <Input
-                  id={`${type}-box`}
-                  type="number"
-                  min="1"
-                  max="100"
-                  value={config.box_column}
-                  onChange={(e) =>
-                    updateConfig(type, { box_column: parseInt(e.target.value) || 1 })
-                  }
-                  className="font-mono"
-                />
-                <p className="text-xs text-muted-foreground/40 pl-6">
-                  Excel 列号 · 如 11 = K列
-                </p>
-              </div>
+            {/* 表头行 */}
+            <div className="space-y-2">
+              <Label htmlFor={`${id}-header-row`} className="text-sm font-medium">
+                表头行
+              </Label>
+              <Input
+                id={`${id}-header-row`}
+                type="number"
+                min="1"
+                value={config.header_row ?? ''}
+                onChange={(e) =>
+                  updateConfig(id, { header_row: parseInt(e.target.value) || null })
+                }
+                placeholder="自动检测"
+                className="font-mono w-32"
+              />
+            </div>
+
+            {/* 公式处理方式 */}
+            <div className="space-y-2">
+              <Label htmlFor={`${id}-formula`} className="text-sm font-medium">
+                公式处理
+              </Label>
+              <select
+                id={`${id}-formula`}
+                value={config.formula_mode}
+                onChange={(e) => updateConfig(id, { formula_mode: e.target.value as FormulaMode })}
+                className="h-9 rounded-md border bg-background px-3 text-sm"
+              >
+                {Object.entries(FormulaModeLabels).map(([value, label]) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
             </div>
 
-            {/* 图片复制选项 - 仅显示有图版和空运 */}
-            {type !== 'sea-rail-no-image' && (
+            {/* 图片复制选项 - 内置方案由处理类型决定，自定义方案可单独设置 */}
+            {id !== 'sea-rail-no-image' && (
               <div className="flex items-center justify-between p-4 rounded-lg border bg-muted/20">
                 <div className="flex items-center gap-3">
                   <Image className="w-5 h-5 text-muted-foreground/40" />
                   <div>
-                    <Label htmlFor={`${type}-images`} className="text-sm font-medium cursor-not-allowed text-muted-foreground/60">
+                    <Label htmlFor={`${id}-images`} className="text-sm font-medium">
                       复制图片
                     </Label>
                     <p className="text-xs text-muted-foreground/40 mt-0.5">
-                      是否复制 Excel 中的图片 · 即将支持
+                      是否复制 Excel 中的图片{profile.builtin && ' · 内置方案不可修改，可复制后调整'}
                     </p>
                   </div>
                 </div>
                 <Switch
-                  id={`${type}-images`}
+                  id={`${id}-images`}
                   checked={config.copy_images}
-                  disabled
+                  disabled={profile.builtin}
                   onCheckedChange={(checked) =>
-                    updateConfig(type, { copy_images: checked })
+                    updateConfig(id, { copy_images: checked })
                   }
                 />
               </div>
             )}
+
+            {/* 图片缩放、压缩和导出 */}
+            {config.copy_images && (
+              <div className="space-y-3">
+                <Label className="text-sm font-medium">图片处理</Label>
+                <div className="flex flex-wrap items-center gap-3">
+                  <Input
+                    type="number"
+                    min="1"
+                    value={config.images.max_width ?? ''}
+                    onChange={(e) => updateImages(id, { max_width: parseInt(e.target.value) || null })}
+                    placeholder="最大宽度"
+                    className="font-mono w-28"
+                    title="最大宽度（像素），超出时按比例缩小"
+                  />
+                  <Input
+                    type="number"
+                    min="1"
+                    value={config.images.max_height ?? ''}
+                    onChange={(e) => updateImages(id, { max_height: parseInt(e.target.value) || null })}
+                    placeholder="最大高度"
+                    className="font-mono w-28"
+                    title="最大高度（像素），超出时按比例缩小"
+                  />
+                  <Input
+                    type="number"
+                    min="1"
+                    max="100"
+                    value={config.images.jpeg_quality ?? ''}
+                    onChange={(e) => updateImages(id, { jpeg_quality: parseInt(e.target.value) || null })}
+                    placeholder="JPEG 质量"
+                    className="font-mono w-28"
+                    title="1-100，设置后不透明图片重新压缩为 JPEG"
+                  />
+                  <div className="flex items-center gap-2">
+                    <Switch
+                      id={`${id}-dedupe`}
+                      checked={config.images.dedupe}
+                      onCheckedChange={(checked) => updateImages(id, { dedupe: checked })}
+                    />
+                    <Label htmlFor={`${id}-dedupe`} className="text-sm font-normal">
+                      相同图片去重
+                    </Label>
+                  </div>
+                </div>
+                <div className="flex flex-wrap items-center gap-3">
+                  <div className="flex items-center gap-2">
+                    <Switch
+                      id={`${id}-export`}
+                      checked={config.images.export_to_folder}
+                      onCheckedChange={(checked) =>
+                        updateImages(id, { export_to_folder: checked, embed: checked ? config.images.embed : true })
+                      }
+                    />
+                    <Label htmlFor={`${id}-export`} className="text-sm font-normal">
+                      导出到文件夹
+                    </Label>
+                  </div>
+                  {config.images.export_to_folder && (
+                    <>
+                      <Input
+                        value={config.images.name_header ?? ''}
+                        onChange={(e) => updateImages(id, { name_header: e.target.value || null })}
+                        placeholder="命名列表头，如 SKU"
+                        className="w-40"
+                        title="按该列的值命名图片文件，为空时按行号命名"
+                      />
+                      <div className="flex items-center gap-2">
+                        <Switch
+                          id={`${id}-embed`}
+                          checked={config.images.embed}
+                          onCheckedChange={(checked) => updateImages(id, { embed: checked })}
+                        />
+                        <Label htmlFor={`${id}-embed`} className="text-sm font-normal">
+                          同时嵌入拆分表
+                        </Label>
+                      </div>
+                    </>
+                  )}
+                </div>
+                <p className="text-xs text-muted-foreground/40">
+                  留空表示不缩放、不重新压缩 · 导出的图片保存在输出文件旁的「输出文件名_图片」文件夹
+                </p>
+              </div>
+            )}
+
+            {/* 方案操作 */}
+            <div className="flex items-center gap-2 pt-2 border-t">
+              {!profile.builtin && (
+                <Input
+                  defaultValue={profile.name}
+                  onBlur={(e) => handleRename(profile, e.target.value)}
+                  className="w-48"
+                  title="方案名称（失去焦点时保存）"
+                />
+              )}
+              <Button variant="outline" size="sm" onClick={() => handleClone(profile)}>
+                <Copy className="w-4 h-4 mr-1" />
+                复制方案
+              </Button>
+              {!profile.builtin && (
+                <Button variant="outline" size="sm" onClick={() => handleDelete(profile)}>
+                  <Trash2 className="w-4 h-4 mr-1" />
+                  删除方案
+                </Button>
+              )}
+            </div>
           </div>
         </AccordionContent>
       </AccordionItem>
@@ -210,15 +464,48 @@ export function ProcessConfigPanel() {
       <div className="space-y-1">
         <h2 className="text-2xl font-bold tracking-tight">处理配置</h2>
         <p className="text-sm text-muted-foreground/50">
-          为所有处理类型配置列索引和参数，配置会自动保存到本地
+          内置方案对应三种处理类型，可新建或复制出自定义方案以适配不同客户的表格格式
         </p>
       </div>
 
+      {/* 新建、导入、导出 */}
+      <div className="flex flex-wrap items-center gap-2">
+        <Input
+          value={newName}
+          onChange={(e) => setNewName(e.target.value)}
+          placeholder="新方案名称"
+          className="w-48"
+        />
+        <select
+          value={newType}
+          onChange={(e) => setNewType(e.target.value as ProcessType)}
+          className="h-9 rounded-md border bg-background px-3 text-sm"
+          title="基于的处理类型"
+        >
+          {Object.entries(ProcessTypeLabels).map(([value, label]) => (
+            <option key={value} value={value}>
+              {label}
+            </option>
+          ))}
+        </select>
+        <Button variant="outline" size="sm" onClick={handleCreate} disabled={!newName.trim()}>
+          <Plus className="w-4 h-4 mr-1" />
+          新建方案
+        </Button>
+        <div className="flex-1" />
+        <Button variant="outline" size="sm" onClick={handleImport}>
+          <Upload className="w-4 h-4 mr-1" />
+          导入
+        </Button>
+        <Button variant="outline" size="sm" onClick={handleExport}>
+          <Download className="w-4 h-4 mr-1" />
+          导出自定义方案
+        </Button>
+      </div>
+
       {/* 手风琴配置列表 */}
       <Accordion type="multiple" defaultValue={['sea-rail-with-image']} className="space-y-2">
-        {renderConfigSection('sea-rail-with-image')}
-        {renderConfigSection('sea-rail-no-image')}
-        {renderConfigSection('air-freight')}
+        {profiles.map(renderConfigSection)}
       </Accordion>
 
       {/* 操作按钮 */}
@@ -0,0 +1,93 @@
+import { Card } from '@/components/ui/card';
+import { Badge } from '@/components/ui/badge';
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from '@/components/ui/table';
+import { AlertTriangle, Eye } from 'lucide-react';
+import type { SplitPreview } from '@/lib/api/excel';
+
+interface Props {
+  preview: SplitPreview;
+}
+
+const formatNumber = (value: number | null) => (value === null ? '非数字' : String(value));
+
+export function SplitPreviewPanel({ preview }: Props) {
+  return (
+    <Card className="p-6">
+      <div className="space-y-6">
+        <div className="flex items-center gap-2">
+          <Eye className="w-5 h-5" />
+          <h3 className="text-lg font-semibold">拆分预览</h3>
+        </div>
+
+        {preview.sheets.map((sheet) => (
+          <div key={sheet.sheet_name} className="space-y-3">
+            <div className="flex flex-wrap items-center gap-2 text-sm">
+              <span className="font-medium">{sheet.sheet_name}</span>
+              <Badge variant="secondary">表头第 {sheet.header_row} 行</Badge>
+              <Badge variant="secondary">{sheet.data_rows} 行</Badge>
+              <Badge variant="secondary">{sheet.merged_blocks.length} 个合并区域</Badge>
+              <Badge variant="secondary">{sheet.image_count} 张图片</Badge>
+            </div>
+
+            {sheet.warnings.length > 0 && (
+              <div className="rounded-md border border-destructive/50 p-3 space-y-1">
+                {sheet.warnings.map((warning, index) => (
+                  <div key={index} className="flex items-start gap-2 text-sm text-destructive">
+                    <AlertTriangle className="w-4 h-4 mt-0.5 shrink-0" />
+                    {warning}
+                  </div>
+                ))}
+              </div>
+            )}
+
+            <div className="max-h-[300px] overflow-y-auto rounded-md border">
+              <Table>
+                <TableHeader>
+                  <TableRow>
+                    <TableHead>行</TableHead>
+                    <TableHead>列</TableHead>
+                    <TableHead>原值</TableHead>
+                    <TableHead>权重</TableHead>
+                    <TableHead>分配结果</TableHead>
+                  </TableRow>
+                </TableHeader>
+                <TableBody>
+                  {sheet.merged_blocks.flatMap((block) => {
+                    const rows = `${block.start_row}-${block.end_row}`;
+                    if (block.distributions.length === 0) {
+                      return [
+                        <TableRow key={`${rows}-${block.start_col}`}>
+                          <TableCell>{rows}</TableCell>
+                          <TableCell>{block.start_col}-{block.end_col}</TableCell>
+                          <TableCell colSpan={3} className="text-muted-foreground">
+                            {block.keep_merged ? '保持合并' : '按列规则拆分'}
+                          </TableCell>
+                        </TableRow>,
+                      ];
+                    }
+                    return block.distributions.map((d) => (
+                      <TableRow key={`${rows}-${d.column}`}>
+                        <TableCell>{rows}</TableCell>
+                        <TableCell>{d.column}</TableCell>
+                        <TableCell>{formatNumber(d.total)}</TableCell>
+                        <TableCell>{d.weights.length > 0 ? d.weights.join(' / ') : '-'}</TableCell>
+                        <TableCell>{d.values.map((v) => (v === null ? '空' : v)).join(' / ')}</TableCell>
+                      </TableRow>
+                    ));
+                  })}
+                </TableBody>
+              </Table>
+            </div>
+          </div>
+        ))}
+      </div>
+    </Card>
+  );
+}
@@ -11,6 +11,7 @@ import { useDarkMode, type DarkModeType } from '@/hooks/use-dark-mode';
 import { cn } from '@/lib/utils';
 import { UpdateDialog } from '@/components/UpdateDialog';
 import { loadSettings, saveSettings, type AppSettings } from '@/lib/settings';
+import { OutputSettingsCard } from './components/OutputSettingsCard';
 import logo from '@/assets/logo-64.png';
 
 export function SettingsPage() {
@@ -174,6 +175,9 @@ export function SettingsPage() {
         </CardContent>
       </Card>
 
+      {/* 输出文件设置 */}
+      <OutputSettingsCard />
+
       {/* 关于 */}
       <Card>
         <CardHeader>
@@ -0,0 +1,110 @@
+import { useEffect, useState } from 'react';
+import { open } from '@tauri-apps/plugin-dialog';
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { useToast } from '@/hooks/use-toast';
+import {
+  type CollisionPolicy,
+  type OutputRule,
+  type OutputTool,
+  CollisionPolicyLabels,
+  OutputToolDefaultDirs,
+  OutputToolLabels,
+  getOutputRules,
+  saveOutputRule,
+} from '@/lib/api/output';
+import { FolderOpen, Save } from 'lucide-react';
+
+export function OutputSettingsCard() {
+  const [rules, setRules] = useState<Record<OutputTool, OutputRule> | null>(null);
+  const { toast } = useToast();
+
+  useEffect(() => {
+    getOutputRules()
+      .then(setRules)
+      .catch((error) => console.error('加载输出配置失败:', error));
+  }, []);
+
+  const updateRule = (tool: OutputTool, updates: Partial<OutputRule>) => {
+    if (!rules) return;
+    setRules({ ...rules, [tool]: { ...rules[tool], ...updates } });
+  };
+
+  const handleSelectDir = async (tool: OutputTool) => {
+    const selected = await open({ directory: true });
+    if (selected && typeof selected === 'string') {
+      updateRule(tool, { output_dir: selected });
+    }
+  };
+
+  const handleSave = async (tool: OutputTool) => {
+    if (!rules) return;
+    try {
+      await saveOutputRule(tool, rules[tool]);
+      toast({ title: '已保存', description: `${OutputToolLabels[tool]}的输出设置已保存` });
+    } catch (error: any) {
+      toast({
+        title: '保存失败',
+        description: error?.message || '未知错误',
+        variant: 'destructive',
+      });
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>输出文件</CardTitle>
+        <CardDescription>
+          生成文件的保存位置和命名模板 · 可用占位符 {'{stem}'} 原文件名、{'{type}'} 类型、{'{date}'} 日期、
+          {'{time}'} 时间、{'{seq}'} 序号
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-6">
+        {rules &&
+          (Object.keys(OutputToolLabels) as OutputTool[]).map((tool) => (
+            <div key={tool} className="space-y-2 pt-4 first:pt-0 border-t first:border-t-0">
+              <Label className="text-base font-semibold">{OutputToolLabels[tool]}</Label>
+              <div className="flex gap-2">
+                <Input
+                  value={rules[tool].template}
+                  onChange={(e) => updateRule(tool, { template: e.target.value })}
+                  className="font-mono flex-1"
+                  title="命名模板（不含扩展名）"
+                />
+                <select
+                  value={rules[tool].collision}
+                  onChange={(e) => updateRule(tool, { collision: e.target.value as CollisionPolicy })}
+                  className="h-9 rounded-md border bg-background px-3 text-sm"
+                  title="文件已存在时"
+                >
+                  {Object.entries(CollisionPolicyLabels).map(([value, label]) => (
+                    <option key={value} value={value}>
+                      {label}
+                    </option>
+                  ))}
+                </select>
+              </div>
+              <div className="flex gap-2">
+                <Input
+                  value={rules[tool].output_dir ?? ''}
+                  onChange={(e) => updateRule(tool, { output_dir: e.target.value || null })}
+                  placeholder={OutputToolDefaultDirs[tool]}
+                  className="flex-1"
+                />
+                <Button variant="outline" size="icon" onClick={() => handleSelectDir(tool)} title="选择文件夹">
+                  <FolderOpen className="w-4 h-4" />
+                </Button>
+                <Button variant="outline" onClick={() => handleSave(tool)}>
+                  <Save className="w-4 h-4 mr-2" />
+                  保存
+                </Button>
+              </div>
+            </div>
+          ))}
+      </CardContent>
+    </Card>
+  );
+}
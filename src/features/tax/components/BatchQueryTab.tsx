@@ -7,6 +7,7 @@ import { FileOpenDialog } from '@/components/common/FileOpenDialog';
 import { useToast } from '@/hooks/use-toast';
 import { taxApi } from '@/lib/api/tax';
 import { getFileName } from '@/lib/file-opener';
+import { SPREADSHEET_EXTENSIONS } from '@/lib/utils';
 import { open, save } from '@tauri-apps/plugin-dialog';
 
 export function BatchQueryTab() {
@@ -42,7 +43,7 @@ export function BatchQueryTab() {
         filters: [
           {
             name: 'Excel',
-            extensions: ['xlsx', 'xls'],
+            extensions: SPREADSHEET_EXTENSIONS,
           },
         ],
       });
@@ -169,7 +170,7 @@ export function BatchQueryTab() {
       <Card>
         <CardHeader>
           <CardTitle>第二步：上传并处理</CardTitle>
-          <CardDescription>支持.xlsx、.xls和.csv格式</CardDescription>
+          <CardDescription>支持.xlsx、.xls、.ods和.csv格式</CardDescription>
         </CardHeader>
         <CardContent className="space-y-4">
           <div>
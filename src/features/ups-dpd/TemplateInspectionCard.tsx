@@ -0,0 +1,103 @@
+import { Card } from '@/components/ui/card';
+import { Badge } from '@/components/ui/badge';
+import { Button } from '@/components/ui/button';
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from '@/components/ui/table';
+import { AlertTriangle, CheckCircle2, X } from 'lucide-react';
+import type { TemplateInspection } from '@/lib/api/ups-dpd';
+
+interface Props {
+  title: string;
+  inspection: TemplateInspection;
+  onClose?: () => void;
+}
+
+export function TemplateInspectionCard({ title, inspection, onClose }: Props) {
+  const ok = inspection.errors.length === 0;
+
+  return (
+    <Card className="p-6">
+      <div className="space-y-4">
+        <div className="flex flex-wrap items-center gap-2">
+          {ok ? (
+            <CheckCircle2 className="w-5 h-5 text-green-600" />
+          ) : (
+            <AlertTriangle className="w-5 h-5 text-destructive" />
+          )}
+          <h3 className="text-lg font-semibold">{title} 模板检查</h3>
+          <Badge variant={ok ? 'secondary' : 'destructive'}>{inspection.errors.length} 个错误</Badge>
+          <Badge variant="secondary">{inspection.warnings.length} 个警告</Badge>
+          {onClose && (
+            <Button onClick={onClose} variant="ghost" size="icon" className="ml-auto h-6 w-6">
+              <X className="w-4 h-4" />
+            </Button>
+          )}
+        </div>
+
+        <div className="text-xs text-muted-foreground space-y-1">
+          <p className="break-all">模板: {inspection.template_path}</p>
+          <p className="break-all">描述: {inspection.descriptor}</p>
+          <p>
+            模板版本: {inspection.template_version > 0 ? `v${inspection.template_version}` : '未记录'}
+            （当前默认 v{inspection.default_version}）
+          </p>
+          <p>模板中的工作表: {inspection.sheet_names.join('、')}</p>
+        </div>
+
+        {inspection.errors.map((error, index) => (
+          <p key={`e${index}`} className="text-sm text-destructive">
+            {error}
+          </p>
+        ))}
+        {inspection.warnings.map((warning, index) => (
+          <p key={`w${index}`} className="text-sm text-muted-foreground">
+            {warning}
+          </p>
+        ))}
+
+        <div className="rounded-md border">
+          <Table>
+            <TableHeader>
+              <TableRow>
+                <TableHead>用途</TableHead>
+                <TableHead>工作表</TableHead>
+                <TableHead>表头行</TableHead>
+                <TableHead>已匹配</TableHead>
+                <TableHead>未匹配</TableHead>
+                <TableHead>写入起始行</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {inspection.sheets.map((sheet) => (
+                <TableRow key={sheet.key}>
+                  <TableCell>{sheet.key}</TableCell>
+                  <TableCell>
+                    {sheet.found ?? (
+                      <span className="text-destructive">
+                        缺少（{sheet.expected_names.join(' / ')}）
+                      </span>
+                    )}
+                  </TableCell>
+                  <TableCell>{sheet.header_row ?? '-'}</TableCell>
+                  <TableCell className="text-xs">
+                    {sheet.mapped.map((field) => `${field.source} → ${field.column}`).join('、') || '-'}
+                  </TableCell>
+                  <TableCell className="text-xs text-destructive">
+                    {[...sheet.unmapped, ...sheet.missing_cells].join('、') || '-'}
+                  </TableCell>
+                  <TableCell>{sheet.first_empty_row ?? '-'}</TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </div>
+      </div>
+    </Card>
+  );
+}
@@ -1,65 +1,80 @@
 import { useState } from 'react';
-import { open } from '@tauri-apps/plugin-dialog';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { Button } from '@/components/ui/button';
 import { Card } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { FileOpenDialog } from '@/components/common/FileOpenDialog';
+import { BatchProcessCard } from '@/components/common/BatchProcessCard';
+import { Switch } from '@/components/ui/switch';
+import { ValidationReportCard } from './ValidationReportCard';
+import { TemplateInspectionCard } from './TemplateInspectionCard';
 import { useToast } from '@/hooks/use-toast';
 import { getFileName } from '@/lib/file-opener';
-import { FileText, Play, Settings, X, Package } from 'lucide-react';
+import { SPREADSHEET_EXTENSIONS } from '@/lib/utils';
+import { Download, FileText, Play, Settings, X, Package } from 'lucide-react';
 import {
   type TemplateType,
   type ProcessRequest,
+  type ValidationReport,
+  type TemplateInspection,
   TemplateTypeLabels,
+  TemplateTypes,
   processUpsDpdFile,
+  validateUpsDpdFile,
+  processUpsDpdBatch,
   getTemplateConfig,
   saveTemplateConfig,
   resetToDefaultTemplate,
+  inspectTemplate,
+  exportDefaultTemplate,
   type TemplateConfig,
 } from '@/lib/api/ups-dpd';
 
 export function UpsUpdPage() {
   const [templateType, setTemplateType] = useState<TemplateType>('ups');
-  const [mainFile, setMainFile] = useState<string | null>(null);
-  const [detailFile, setDetailFile] = useState<string | null>(null);
+  const [mainFiles, setMainFiles] = useState<string[]>([]);
+  const [detailFiles, setDetailFiles] = useState<string[]>([]);
+  const [allSheets, setAllSheets] = useState(false);
   const [processing, setProcessing] = useState(false);
   const [logs, setLogs] = useState<string[]>([]);
+  const [report, setReport] = useState<ValidationReport | null>(null);
+  const [batchIgnoreWarnings, setBatchIgnoreWarnings] = useState(false);
   const [showFileDialog, setShowFileDialog] = useState(false);
   const [outputFilePath, setOutputFilePath] = useState<string>('');
 
   // 模板配置状态
-  const [upsConfig, setUpsConfig] = useState<TemplateConfig | null>(null);
-  const [dpdConfig, setDpdConfig] = useState<TemplateConfig | null>(null);
+  const [configs, setConfigs] = useState<Partial<Record<TemplateType, TemplateConfig>>>({});
+  const [inspection, setInspection] = useState<{ type: TemplateType; result: TemplateInspection } | null>(null);
 
   const { toast } = useToast();
 
   // 加载模板配置
   const loadConfigs = async () => {
     try {
-      const ups = await getTemplateConfig('ups');
-      const dpd = await getTemplateConfig('dpd');
-      setUpsConfig(ups);
-      setDpdConfig(dpd);
+      const loaded = await Promise.all(TemplateTypes.map((type) => getTemplateConfig(type)));
+      setConfigs(Object.fromEntries(TemplateTypes.map((type, i) => [type, loaded[i]])));
     } catch (error) {
       console.error('加载配置失败:', error);
     }
   };
 
-  // 选择主数据文件
-  const handleSelectMainFile = async () => {
+  // 选择主数据文件（可多选，追加到已选列表）
+  const handleSelectMainFiles = async () => {
     try {
       const selected = await open({
+        multiple: true,
         filters: [
           {
             name: 'Excel 文件',
-            extensions: ['xlsx', 'xls'],
+            extensions: SPREADSHEET_EXTENSIONS,
           },
         ],
       });
 
-      if (selected && typeof selected === 'string') {
-        setMainFile(selected);
+      if (selected && selected.length > 0) {
+        setMainFiles((current) => appendFiles(current, selected));
+        setReport(null);
         setLogs([]);
       }
     } catch (error) {
@@ -72,20 +87,22 @@ export function UpsUpdPage() {
     }
   };
 
-  // 选择明细表文件
-  const handleSelectDetailFile = async () => {
+  // 选择明细表文件（可多选，追加到已选列表）
+  const handleSelectDetailFiles = async () => {
     try {
       const selected = await open({
+        multiple: true,
         filters: [
           {
             name: 'Excel 文件',
-            extensions: ['xlsx', 'xls'],
+            extensions: SPREADSHEET_EXTENSIONS,
           },
         ],
       });
 
-      if (selected && typeof selected === 'string') {
-        setDetailFile(selected);
+      if (selected && selected.length > 0) {
+        setDetailFiles((current) => appendFiles(current, selected));
+        setReport(null);
       }
     } catch (error) {
       console.error('选择文件失败:', error);
@@ -97,9 +114,9 @@ export function UpsUpdPage() {
     }
   };
 
-  // 处理文件
-  const handleProcess = async () => {
-    if (!mainFile) {
+  // 处理文件：先校验源数据，有错误时不生成，只有警告时需确认忽略
+  const handleProcess = async (ignoreWarnings = false) => {
+    if (mainFiles.length === 0) {
       toast({
         title: '提示',
         description: '请先选择主数据文件',
@@ -112,11 +129,29 @@ export function UpsUpdPage() {
 
     try {
       const request: ProcessRequest = {
-        main_file_path: mainFile,
-        detail_file_path: detailFile || undefined,
+        main_file_paths: mainFiles,
+        detail_file_paths: detailFiles,
+        all_sheets: allSheets,
         template_type: templateType,
+        ignore_warnings: ignoreWarnings,
       };
 
+      if (!ignoreWarnings) {
+        const validation = await validateUpsDpdFile(request);
+        setReport(validation);
+        if (validation.error_count > 0 || validation.warning_count > 0) {
+          toast({
+            title: validation.error_count > 0 ? '源数据有错误' : '源数据有警告',
+            description:
+              validation.error_count > 0
+                ? `发现 ${validation.error_count} 个错误，请修正后重新处理`
+                : `发现 ${validation.warning_count} 个警告，确认后可忽略警告继续生成`,
+            variant: validation.error_count > 0 ? 'destructive' : 'default',
+          });
+          return;
+        }
+      }
+
       const result = await processUpsDpdFile(request);
 
       setLogs(result.logs);
@@ -148,6 +183,23 @@ export function UpsUpdPage() {
     }
   };
 
+  // 检查模板（默认使用当前配置），返回检查结果
+  const handleInspect = async (type: TemplateType, config = configs[type]) => {
+    if (!config) return null;
+    try {
+      const result = await inspectTemplate(config);
+      setInspection({ type, result });
+      return result;
+    } catch (error: any) {
+      toast({
+        title: '模板检查失败',
+        description: error?.message || '无法加载模板',
+        variant: 'destructive',
+      });
+      return null;
+    }
+  };
+
   // 选择自定义模板
   const handleSelectCustomTemplate = async (type: TemplateType) => {
     try {
@@ -163,13 +215,26 @@ export function UpsUpdPage() {
       });
 
       if (selected && typeof selected === 'string') {
-        // 保存自定义模板配置
+        // 保存自定义模板配置（描述文件改为自动查找模板同目录的 .json）
         const config: TemplateConfig = {
           template_type: type,
           template_path: selected,
           use_default: false,
+          descriptor_path: null,
         };
 
+        // 先检查模板，有错误时不保存
+        const result = await handleInspect(type, config);
+        if (!result) return;
+        if (result.errors.length > 0) {
+          toast({
+            title: '模板检查未通过',
+            description: `发现 ${result.errors.length} 个错误，请根据检查结果修改模板`,
+            variant: 'destructive',
+          });
+          return;
+        }
+
         await saveTemplateConfig(config);
         await loadConfigs();
 
@@ -187,6 +252,62 @@ export function UpsUpdPage() {
     }
   };
 
+  // 导出默认模板（及同名 .json 描述），在其基础上制作自定义模板
+  const handleExportDefault = async (type: TemplateType) => {
+    try {
+      const target = await save({
+        title: '导出默认模板',
+        defaultPath: `${TemplateTypeLabels[type]}模板.xlsx`,
+        filters: [{ name: 'Excel 文件', extensions: ['xlsx'] }],
+      });
+      if (!target) return;
+
+      const path = await exportDefaultTemplate(type, target);
+      toast({
+        title: '成功',
+        description: `已导出默认模板及模板描述: ${getFileName(path)}`,
+      });
+    } catch (error: any) {
+      toast({
+        title: '错误',
+        description: error?.message || '导出失败',
+        variant: 'destructive',
+      });
+    }
+  };
+
+  // 选择模板描述文件（clear 为 true 时恢复自动查找）
+  const handleSelectDescriptor = async (type: TemplateType, clear = false) => {
+    const current = configs[type];
+    if (!current) return;
+
+    try {
+      let descriptorPath: string | null = null;
+      if (!clear) {
+        const selected = await open({
+          title: '选择模板描述文件',
+          filters: [{ name: '模板描述', extensions: ['json'] }],
+          multiple: false,
+        });
+        if (!selected || typeof selected !== 'string') return;
+        descriptorPath = selected;
+      }
+
+      await saveTemplateConfig({ ...current, descriptor_path: descriptorPath });
+      await loadConfigs();
+      toast({
+        title: '成功',
+        description: descriptorPath ? '已设置模板描述文件' : '已恢复自动查找模板描述',
+      });
+    } catch (error: any) {
+      toast({
+        title: '错误',
+        description: error?.message || '设置失败',
+        variant: 'destructive',
+      });
+    }
+  };
+
   // 重置为默认模板
   const handleResetTemplate = async (type: TemplateType) => {
     try {
@@ -210,7 +331,7 @@ export function UpsUpdPage() {
       <div>
         <h2 className="text-3xl font-bold tracking-tight">UPS/DPD 数据处理</h2>
         <p className="text-muted-foreground">
-          支持 UPS 总结单和 DPD 数据预报模板填充
+          支持 UPS 总结单、DPD 数据预报及 GLS、FedEx、Royal Mail/Parcelforce 清单模板填充
         </p>
       </div>
 
@@ -232,7 +353,7 @@ export function UpsUpdPage() {
               {/* 模板类型选择 */}
               <div className="space-y-3">
                 <Label className="text-sm font-medium">模板类型</Label>
-                <div className="flex gap-4">
+                <div className="flex flex-wrap gap-4">
                   {(Object.entries(TemplateTypeLabels) as [TemplateType, string][]).map(
                     ([value, label]) => (
                       <div key={value} className="flex items-center space-x-2">
@@ -244,9 +365,10 @@ export function UpsUpdPage() {
                           checked={templateType === value}
                           onChange={(e) => {
                             setTemplateType(e.target.value as TemplateType);
-                            setMainFile(null);
-                            setDetailFile(null);
+                            setMainFiles([]);
+                            setDetailFiles([]);
                             setLogs([]);
+                            setReport(null);
                           }}
                           className="w-4 h-4 text-primary border-gray-300 focus:ring-2 focus:ring-primary cursor-pointer"
                         />
@@ -265,33 +387,24 @@ export function UpsUpdPage() {
               {/* 主数据文件选择 */}
               <div className="space-y-2">
                 <Label className="text-sm font-medium">主数据文件 *</Label>
-                <div className="flex gap-2">
-                  <Button
-                    onClick={handleSelectMainFile}
-                    variant="outline"
-                    className="flex-1"
-                  >
-                    <FileText className="w-4 h-4 mr-2" />
-                    {mainFile ? '更换文件' : '选择 Excel 文件'}
-                  </Button>
-                  {mainFile && (
-                    <Button
-                      onClick={() => setMainFile(null)}
-                      variant="ghost"
-                      size="icon"
-                    >
-                      <X className="w-4 h-4" />
-                    </Button>
-                  )}
-                </div>
-                {mainFile && (
-                  <p
-                    className="text-sm text-muted-foreground truncate"
-                    title={mainFile}
-                  >
-                    {mainFile}
-                  </p>
-                )}
+                <Button
+                  onClick={handleSelectMainFiles}
+                  variant="outline"
+                  className="w-full"
+                >
+                  <FileText className="w-4 h-4 mr-2" />
+                  {mainFiles.length > 0 ? '添加文件' : '选择 Excel 文件'}
+                </Button>
+                <SelectedFiles
+                  files={mainFiles}
+                  onRemove={(file) => {
+                    setMainFiles((current) => current.filter((f) => f !== file));
+                    setReport(null);
+                  }}
+                />
+                <p className="text-xs text-muted-foreground">
+                  可选择多个文件（如每个仓库一个导出文件），按表头合并，转单号重复的行只保留第一次出现的
+                </p>
               </div>
 
               {/* 明细表文件选择（可选） */}
@@ -299,42 +412,44 @@ export function UpsUpdPage() {
                 <Label className="text-sm font-medium">
                   单件明细表文件（可选）
                 </Label>
-                <div className="flex gap-2">
-                  <Button
-                    onClick={handleSelectDetailFile}
-                    variant="outline"
-                    className="flex-1"
-                  >
-                    <Package className="w-4 h-4 mr-2" />
-                    {detailFile ? '更换明细表' : '选择明细表文件'}
-                  </Button>
-                  {detailFile && (
-                    <Button
-                      onClick={() => setDetailFile(null)}
-                      variant="ghost"
-                      size="icon"
-                    >
-                      <X className="w-4 h-4" />
-                    </Button>
-                  )}
-                </div>
-                {detailFile && (
-                  <p
-                    className="text-sm text-muted-foreground truncate"
-                    title={detailFile}
-                  >
-                    {detailFile}
-                  </p>
-                )}
+                <Button
+                  onClick={handleSelectDetailFiles}
+                  variant="outline"
+                  className="w-full"
+                >
+                  <Package className="w-4 h-4 mr-2" />
+                  {detailFiles.length > 0 ? '添加明细表' : '选择明细表文件'}
+                </Button>
+                <SelectedFiles
+                  files={detailFiles}
+                  onRemove={(file) => {
+                    setDetailFiles((current) => current.filter((f) => f !== file));
+                    setReport(null);
+                  }}
+                />
                 <p className="text-xs text-muted-foreground">
-                  用于补充子单号等详细信息
+                  用于补充子单号等详细信息，多个文件按子转单号去重
                 </p>
               </div>
 
+              <div className="flex items-center gap-2">
+                <Switch
+                  id="all-sheets"
+                  checked={allSheets}
+                  onCheckedChange={(checked) => {
+                    setAllSheets(checked);
+                    setReport(null);
+                  }}
+                />
+                <Label htmlFor="all-sheets" className="text-sm font-normal">
+                  读取每个文件的所有工作表（默认只读第一个）
+                </Label>
+              </div>
+
               {/* 处理按钮 */}
               <Button
-                onClick={handleProcess}
-                disabled={!mainFile || processing}
+                onClick={() => handleProcess()}
+                disabled={mainFiles.length === 0 || processing}
                 className="w-full"
               >
                 {processing ? (
@@ -352,6 +467,15 @@ export function UpsUpdPage() {
             </div>
           </Card>
 
+          {/* 源数据校验报告 */}
+          {report && (
+            <ValidationReportCard
+              report={report}
+              processing={processing}
+              onIgnoreWarnings={() => handleProcess(true)}
+            />
+          )}
+
           {/* 处理日志 */}
           {logs.length > 0 && (
             <Card className="p-6">
@@ -368,98 +492,108 @@ export function UpsUpdPage() {
               </div>
             </Card>
           )}
+
+          <div className="flex items-center gap-2">
+            <Switch
+              id="batch-ignore-warnings"
+              checked={batchIgnoreWarnings}
+              onCheckedChange={setBatchIgnoreWarnings}
+            />
+            <Label htmlFor="batch-ignore-warnings" className="text-sm font-normal">
+              批量处理时忽略源数据警告（有错误的文件仍会跳过）
+            </Label>
+          </div>
+
+          <BatchProcessCard
+            eventName="ups-dpd-batch-progress"
+            description={`按当前选择的${TemplateTypeLabels[templateType]}模板逐个处理主数据文件（不含明细表），输出位置和文件名可在设置中配置`}
+            onRun={(source, zip) =>
+              processUpsDpdBatch({
+                source,
+                template_type: templateType,
+                workers: 0,
+                zip,
+                ignore_warnings: batchIgnoreWarnings,
+                all_sheets: allSheets,
+              })
+            }
+          />
         </TabsContent>
 
         <TabsContent value="config" className="space-y-4">
           <Card className="p-6">
             <div className="space-y-6">
-              <div>
-                <h3 className="text-lg font-semibold mb-4">UPS 模板配置</h3>
-                <div className="space-y-4">
-                  <div className="flex items-center justify-between">
-                    <div>
-                      <p className="text-sm font-medium">模板状态</p>
-                      <p className="text-xs text-muted-foreground">
-                        {upsConfig?.use_default
-                          ? '当前使用内置默认模板'
-                          : '当前使用自定义模板'}
-                      </p>
-                    </div>
-                    <div className="flex gap-2">
-                      <Button
-                        onClick={() => handleSelectCustomTemplate('ups')}
-                        variant="default"
-                        size="sm"
-                      >
-                        <FileText className="w-4 h-4 mr-1" />
-                        选择自定义模板
-                      </Button>
-                      {!upsConfig?.use_default && (
-                        <Button
-                          onClick={() => handleResetTemplate('ups')}
-                          variant="outline"
-                          size="sm"
-                        >
-                          重置为默认
+              {TemplateTypes.map((type) => {
+                const config = configs[type];
+                const label = type.toUpperCase();
+                return (
+                  <div key={type} className="space-y-4 border-t pt-6 first:border-t-0 first:pt-0">
+                    <h3 className="text-lg font-semibold">{label} 模板配置</h3>
+                    <div className="flex items-center justify-between">
+                      <div>
+                        <p className="text-sm font-medium">模板状态</p>
+                        <p className="text-xs text-muted-foreground">
+                          {config?.use_default ? '当前使用内置默认模板' : '当前使用自定义模板'}
+                        </p>
+                      </div>
+                      <div className="flex gap-2">
+                        <Button onClick={() => handleSelectCustomTemplate(type)} variant="default" size="sm">
+                          <FileText className="w-4 h-4 mr-1" />
+                          选择自定义模板
                         </Button>
-                      )}
-                    </div>
-                  </div>
-                  {upsConfig?.template_path && !upsConfig?.use_default && (
-                    <div className="p-3 bg-muted rounded-md">
-                      <p className="text-sm font-medium mb-1">自定义模板路径</p>
-                      <p className="text-xs text-muted-foreground break-all">
-                        {upsConfig.template_path}
-                      </p>
-                    </div>
-                  )}
-                </div>
-              </div>
-
-              <div className="border-t pt-6">
-                <h3 className="text-lg font-semibold mb-4">DPD 模板配置</h3>
-                <div className="space-y-4">
-                  <div className="flex items-center justify-between">
-                    <div>
-                      <p className="text-sm font-medium">模板状态</p>
-                      <p className="text-xs text-muted-foreground">
-                        {dpdConfig?.use_default
-                          ? '当前使用内置默认模板'
-                          : '当前使用自定义模板'}
-                      </p>
+                        <Button onClick={() => handleInspect(type)} variant="outline" size="sm">
+                          检查模板
+                        </Button>
+                        <Button onClick={() => handleExportDefault(type)} variant="outline" size="sm">
+                          <Download className="w-4 h-4 mr-1" />
+                          导出默认模板
+                        </Button>
+                        {!config?.use_default && (
+                          <Button onClick={() => handleResetTemplate(type)} variant="outline" size="sm">
+                            重置为默认
+                          </Button>
+                        )}
+                      </div>
                     </div>
-                    <div className="flex gap-2">
-                      <Button
-                        onClick={() => handleSelectCustomTemplate('dpd')}
-                        variant="default"
-                        size="sm"
-                      >
-                        <FileText className="w-4 h-4 mr-1" />
-                        选择自定义模板
-                      </Button>
-                      {!dpdConfig?.use_default && (
-                        <Button
-                          onClick={() => handleResetTemplate('dpd')}
-                          variant="outline"
-                          size="sm"
-                        >
-                          重置为默认
+                    {config?.template_path && !config?.use_default && (
+                      <div className="p-3 bg-muted rounded-md">
+                        <p className="text-sm font-medium mb-1">自定义模板路径</p>
+                        <p className="text-xs text-muted-foreground break-all">{config.template_path}</p>
+                      </div>
+                    )}
+                    <div className="flex items-center justify-between">
+                      <div>
+                        <p className="text-sm font-medium">模板描述</p>
+                        <p className="text-xs text-muted-foreground break-all">
+                          {config?.descriptor_path
+                            ? config.descriptor_path
+                            : '自动：模板同目录同名的 .json 文件，没有时使用内置描述'}
+                        </p>
+                      </div>
+                      <div className="flex gap-2 shrink-0">
+                        <Button onClick={() => handleSelectDescriptor(type)} variant="outline" size="sm">
+                          选择描述文件
                         </Button>
-                      )}
+                        {config?.descriptor_path && (
+                          <Button onClick={() => handleSelectDescriptor(type, true)} variant="outline" size="sm">
+                            自动查找
+                          </Button>
+                        )}
+                      </div>
                     </div>
                   </div>
-                  {dpdConfig?.template_path && !dpdConfig?.use_default && (
-                    <div className="p-3 bg-muted rounded-md">
-                      <p className="text-sm font-medium mb-1">自定义模板路径</p>
-                      <p className="text-xs text-muted-foreground break-all">
-                        {dpdConfig.template_path}
-                      </p>
-                    </div>
-                  )}
-                </div>
-              </div>
+                );
+              })}
             </div>
           </Card>
+
+          {inspection && (
+            <TemplateInspectionCard
+              title={TemplateTypeLabels[inspection.type]}
+              inspection={inspection.result}
+              onClose={() => setInspection(null)}
+            />
+          )}
         </TabsContent>
       </Tabs>
 
@@ -475,3 +609,34 @@ export function UpsUpdPage() {
     </div>
   );
 }
+
+// 追加文件，忽略已选择的
+function appendFiles(current: string[], selected: string[]): string[] {
+  return [...current, ...selected.filter((file) => !current.includes(file))];
+}
+
+// 已选择的文件列表，每个文件可单独移除
+function SelectedFiles({
+  files,
+  onRemove,
+}: {
+  files: string[];
+  onRemove: (file: string) => void;
+}) {
+  if (files.length === 0) return null;
+
+  return (
+    <div className="space-y-1">
+      {files.map((file) => (
+        <div key={file} className="flex items-center gap-2">
+          <p className="flex-1 text-sm text-muted-foreground truncate" title={file}>
+            {getFileName(file)}
+          </p>
+          <Button onClick={() => onRemove(file)} variant="ghost" size="icon" className="h-6 w-6">
+            <X className="w-3 h-3" />
+          </Button>
+        </div>
+      ))}
+    </div>
+  );
+}
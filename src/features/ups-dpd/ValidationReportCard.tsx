@@ -0,0 +1,88 @@
+import { Card } from '@/components/ui/card';
+import { Badge } from '@/components/ui/badge';
+import { Button } from '@/components/ui/button';
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from '@/components/ui/table';
+import { AlertTriangle, CheckCircle2 } from 'lucide-react';
+import type { ValidationReport } from '@/lib/api/ups-dpd';
+
+interface Props {
+  report: ValidationReport;
+  /** 只有警告时显示「忽略警告并生成」 */
+  onIgnoreWarnings?: () => void;
+  processing?: boolean;
+}
+
+const SOURCE_LABELS = { main: '主数据', detail: '明细表' } as const;
+
+export function ValidationReportCard({ report, onIgnoreWarnings, processing }: Props) {
+  const onlyWarnings = report.error_count === 0 && report.warning_count > 0;
+
+  return (
+    <Card className="p-6">
+      <div className="space-y-4">
+        <div className="flex flex-wrap items-center gap-2">
+          {report.issues.length === 0 ? (
+            <CheckCircle2 className="w-5 h-5 text-green-600" />
+          ) : (
+            <AlertTriangle className="w-5 h-5 text-destructive" />
+          )}
+          <h3 className="text-lg font-semibold">源数据校验</h3>
+          <Badge variant={report.error_count > 0 ? 'destructive' : 'secondary'}>
+            {report.error_count} 个错误
+          </Badge>
+          <Badge variant="secondary">{report.warning_count} 个警告</Badge>
+        </div>
+
+        {report.error_count > 0 && (
+          <p className="text-sm text-destructive">请修正源数据中的错误后重新处理</p>
+        )}
+
+        {report.issues.length > 0 && (
+          <div className="max-h-[300px] overflow-y-auto rounded-md border">
+            <Table>
+              <TableHeader>
+                <TableRow>
+                  <TableHead>级别</TableHead>
+                  <TableHead>文件</TableHead>
+                  <TableHead>行</TableHead>
+                  <TableHead>列</TableHead>
+                  <TableHead>说明</TableHead>
+                </TableRow>
+              </TableHeader>
+              <TableBody>
+                {report.issues.map((issue, index) => (
+                  <TableRow key={index}>
+                    <TableCell>
+                      <Badge variant={issue.severity === 'error' ? 'destructive' : 'outline'}>
+                        {issue.severity === 'error' ? '错误' : '警告'}
+                      </Badge>
+                    </TableCell>
+                    <TableCell title={issue.file ?? undefined}>
+                      {issue.file ? `${SOURCE_LABELS[issue.source]} · ${issue.file}` : SOURCE_LABELS[issue.source]}
+                    </TableCell>
+                    <TableCell>{issue.row ?? '整列'}</TableCell>
+                    <TableCell>{issue.column}</TableCell>
+                    <TableCell>{issue.message}</TableCell>
+                  </TableRow>
+                ))}
+              </TableBody>
+            </Table>
+          </div>
+        )}
+
+        {onlyWarnings && onIgnoreWarnings && (
+          <Button onClick={onIgnoreWarnings} disabled={processing} variant="outline" className="w-full">
+            忽略警告并生成
+          </Button>
+        )}
+      </div>
+    </Card>
+  );
+}
@@ -0,0 +1,35 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface BatchFileResult {
+  input_path: string;
+  output_path: string | null;
+  error: string | null;
+}
+
+export interface BatchSummary {
+  total: number;
+  succeeded: number;
+  failed: number;
+  results: BatchFileResult[];
+  zip_path: string | null;
+}
+
+export interface BatchProgress {
+  completed: number;
+  total: number;
+  input_path: string;
+  success: boolean;
+  error: string | null;
+}
+
+/**
+ * 监听批量处理进度事件
+ */
+export async function listenToBatchProgress(
+  eventName: 'excel-batch-progress' | 'ups-dpd-batch-progress',
+  callback: (progress: BatchProgress) => void
+): Promise<UnlistenFn> {
+  return await listen<BatchProgress>(eventName, (event) => {
+    callback(event.payload);
+  });
+}
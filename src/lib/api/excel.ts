@@ -1,15 +1,54 @@
 export type ProcessType = 'sea-rail-with-image' | 'sea-rail-no-image' | 'air-freight';
 
+export type SplitMode =
+  | { type: 'proportional'; by_column: number; by_header?: string | null }
+  | { type: 'even' }
+  | { type: 'first-row-only' }
+  | { type: 'copy' }
+  | { type: 'clear' }
+  | { type: 'keep-merged' };
+
+export type RemainderMode = 'largest-remainder' | 'last-row' | 'per-row';
+
+export interface ColumnRule {
+  column: number;
+  header?: string | null;
+  mode: SplitMode;
+  decimals: number;
+  remainder: RemainderMode;
+}
+
+export type FormulaMode = 'rebase' | 'rebase-or-value' | 'value';
+
+export interface ImageOptions {
+  max_width: number | null;
+  max_height: number | null;
+  jpeg_quality: number | null;
+  dedupe: boolean;
+  export_to_folder: boolean;
+  embed: boolean;
+  name_column?: number | null;
+  name_header?: string | null;
+}
+
 export interface ProcessConfig {
   process_type: ProcessType;
-  weight_column: number;
-  box_column: number;
+  header_row: number | null;
+  column_rules: ColumnRule[];
+  formula_mode: FormulaMode;
   copy_images: boolean;
+  images: ImageOptions;
 }
 
+export type SheetSelection =
+  | { mode: 'first' }
+  | { mode: 'named'; name: string }
+  | { mode: 'all' };
+
 export interface ProcessRequest {
   file_path: string;
   config: ProcessConfig;
+  sheets?: SheetSelection;
 }
 
 export interface ProcessResponse {
@@ -19,8 +58,69 @@ export interface ProcessResponse {
   logs: string[];
 }
 
+export interface ColumnDistribution {
+  column: number;
+  total: number | null;
+  /** 按比例分配时为数量，首行保留、复制、清空列为空数组 */
+  weights: number[];
+  /** 每一行的输出值，null 表示留空 */
+  values: (number | null)[];
+}
+
+export interface MergedBlockPreview {
+  start_row: number;
+  end_row: number;
+  start_col: number;
+  end_col: number;
+  keep_merged: boolean;
+  distributions: ColumnDistribution[];
+}
+
+export interface SheetPreview {
+  sheet_name: string;
+  header_row: number;
+  data_rows: number;
+  merged_blocks: MergedBlockPreview[];
+  image_count: number;
+  warnings: string[];
+}
+
+export interface SplitPreview {
+  sheets: SheetPreview[];
+}
+
+export const SplitModeLabels: Record<SplitMode['type'], string> = {
+  proportional: '按比例分配',
+  even: '平均分配',
+  'first-row-only': '仅第一行',
+  copy: '复制原值',
+  clear: '其他行留空',
+  'keep-merged': '保持合并',
+};
+
+export const FormulaModeLabels: Record<FormulaMode, string> = {
+  rebase: '平移公式引用',
+  'rebase-or-value': '平移引用，无法平移时写入结果',
+  value: '写入计算结果',
+};
+
 export const ProcessTypeLabels: Record<ProcessType, string> = {
   'sea-rail-with-image': '海铁数据（有图版）',
   'sea-rail-no-image': '海铁数据（无图版）',
   'air-freight': '空运数据',
 };
+
+export interface BatchProcessRequest {
+  source: string;
+  config: ProcessConfig;
+  sheets?: SheetSelection;
+  workers: number;
+  zip: boolean;
+}
+
+export interface ProcessProfile {
+  id: string;
+  name: string;
+  builtin: boolean;
+  config: ProcessConfig;
+}
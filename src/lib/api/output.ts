@@ -0,0 +1,45 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type OutputTool = 'excel-split' | 'alta' | 'tax' | 'ups-dpd';
+
+export type CollisionPolicy = 'overwrite' | 'suffix' | 'fail';
+
+export interface OutputRule {
+  template: string;
+  output_dir: string | null;
+  collision: CollisionPolicy;
+}
+
+export const OutputToolLabels: Record<OutputTool, string> = {
+  'excel-split': 'Excel 数据拆分',
+  alta: '禁运标记',
+  tax: '税率批量查询',
+  'ups-dpd': 'UPS/DPD 总结单',
+};
+
+export const OutputToolDefaultDirs: Record<OutputTool, string> = {
+  'excel-split': '输入文件所在目录',
+  alta: '输入文件所在目录',
+  tax: '输入文件所在目录',
+  'ups-dpd': '桌面（不可用时为文档或用户目录）',
+};
+
+export const CollisionPolicyLabels: Record<CollisionPolicy, string> = {
+  overwrite: '覆盖已有文件',
+  suffix: '自动加序号',
+  fail: '报错',
+};
+
+/**
+ * 获取所有工具的输出规则
+ */
+export async function getOutputRules(): Promise<Record<OutputTool, OutputRule>> {
+  return invoke('get_output_rules');
+}
+
+/**
+ * 保存指定工具的输出规则
+ */
+export async function saveOutputRule(tool: OutputTool, rule: OutputRule): Promise<void> {
+  return invoke('save_output_rule', { tool, rule });
+}
@@ -1,11 +1,45 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { BatchSummary } from './batch';
 
-export type TemplateType = 'ups' | 'dpd';
+export type TemplateType = 'ups' | 'dpd' | 'gls' | 'fedex' | 'royal-mail';
 
 export interface ProcessRequest {
-  main_file_path: string;
-  detail_file_path?: string;
+  /** 多个文件按表头合并，主数据按转单号、明细按子转单号去重 */
+  main_file_paths: string[];
+  detail_file_paths?: string[];
+  /** 读取每个文件的所有工作表 */
+  all_sheets?: boolean;
   template_type: TemplateType;
+  /** 源数据只有警告时仍然生成 */
+  ignore_warnings?: boolean;
+}
+
+export interface BatchProcessRequest {
+  source: string;
+  template_type: TemplateType;
+  workers: number;
+  zip: boolean;
+  ignore_warnings?: boolean;
+  all_sheets?: boolean;
+}
+
+export type Severity = 'error' | 'warning';
+
+export interface ValidationIssue {
+  source: 'main' | 'detail';
+  /** 来源文件（多个文件合并时） */
+  file: string | null;
+  /** 源文件中的行号，整列问题为空 */
+  row: number | null;
+  column: string;
+  severity: Severity;
+  message: string;
+}
+
+export interface ValidationReport {
+  issues: ValidationIssue[];
+  error_count: number;
+  warning_count: number;
 }
 
 export interface ProcessResponse {
@@ -19,13 +53,65 @@ export interface TemplateConfig {
   template_type: TemplateType;
   template_path?: string;
   use_default: boolean;
+  /** 模板描述文件，未设置时使用模板同目录同名的 .json 或内置描述 */
+  descriptor_path?: string | null;
+}
+
+export interface MappedField {
+  source: string;
+  header: string;
+  /** 列字母，如 "C" */
+  column: string;
+}
+
+export interface SheetInspection {
+  /** 模板描述中的用途（summary、sub_order 等） */
+  key: string;
+  expected_names: string[];
+  found: string | null;
+  /** 表头行（分桶工作表为空） */
+  header_row: number | null;
+  mapped: MappedField[];
+  unmapped: string[];
+  /** 数据开始写入的行 */
+  first_empty_row: number | null;
+  /** 分桶工作表中缺少的目标单元格 */
+  missing_cells: string[];
+}
+
+export interface TemplateInspection {
+  template_path: string;
+  descriptor: string;
+  /** 模板描述中的模板版本（0 表示未记录） */
+  template_version: number;
+  /** 当前内置默认模板的版本 */
+  default_version: number;
+  sheet_names: string[];
+  sheets: SheetInspection[];
+  /** 有错误时不能保存为自定义模板 */
+  errors: string[];
+  warnings: string[];
 }
 
 export const TemplateTypeLabels: Record<TemplateType, string> = {
   ups: 'UPS总结单',
   dpd: 'DPD数据预报',
+  gls: 'GLS清单',
+  fedex: 'FedEx清单',
+  'royal-mail': 'Royal Mail/Parcelforce清单',
 };
 
+export const TemplateTypes = Object.keys(TemplateTypeLabels) as TemplateType[];
+
+/**
+ * 校验源数据（不生成文件）
+ */
+export async function validateUpsDpdFile(
+  request: ProcessRequest
+): Promise<ValidationReport> {
+  return invoke('validate_ups_dpd_file', { request });
+}
+
 /**
  * 处理 UPS/DPD 文件
  */
@@ -35,6 +121,15 @@ export async function processUpsDpdFile(
   return invoke('process_ups_dpd_file', { request });
 }
 
+/**
+ * 批量处理文件夹或 glob 匹配的文件
+ */
+export async function processUpsDpdBatch(
+  request: BatchProcessRequest
+): Promise<BatchSummary> {
+  return invoke('process_ups_dpd_batch', { request });
+}
+
 /**
  * 获取模板配置
  */
@@ -60,6 +155,25 @@ export async function validateTemplateFile(filePath: string): Promise<boolean> {
   return invoke('validate_template_file', { filePath });
 }
 
+/**
+ * 检查模板的工作表、表头和字段映射（不保存配置）
+ */
+export async function inspectTemplate(
+  config: TemplateConfig
+): Promise<TemplateInspection> {
+  return invoke('inspect_template', { config });
+}
+
+/**
+ * 导出默认模板（及同名 .json 描述）供编辑，返回导出的模板路径
+ */
+export async function exportDefaultTemplate(
+  templateType: TemplateType,
+  targetPath: string
+): Promise<string> {
+  return invoke('export_default_template', { templateType, targetPath });
+}
+
 /**
  * 重置为默认模板
  */